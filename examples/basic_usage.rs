@@ -11,11 +11,10 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("----------------------------");
 
     // Display all LCZ types
-    for code in 1..=17 {
-        let lcz = Lcz::from_code(code);
+    for lcz in Lcz::iter() {
         println!(
             "LCZ {}: {} ({})",
-            code,
+            lcz.to_code(),
             lcz.full_name(),
             lcz.simple_category().as_ref()
         );
@@ -28,18 +27,21 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut urban_count = 0;
     let mut suburban_count = 0;
     let mut rural_count = 0;
+    let mut unknown_count = 0;
 
-    for code in 1..=17 {
-        match Lcz::from_code(code).simple_category() {
+    for lcz in Lcz::iter() {
+        match lcz.simple_category() {
             LczCategory::Urban => urban_count += 1,
             LczCategory::Suburban => suburban_count += 1,
             LczCategory::Rural => rural_count += 1,
+            LczCategory::Unknown => unknown_count += 1,
         }
     }
 
     println!("Urban types: {}", urban_count);
     println!("Suburban types: {}", suburban_count);
     println!("Rural types: {}", rural_count);
+    println!("Unknown types: {}", unknown_count);
 
     // Example 2: Creating sample station data
     println!("\n3. Sample Station Data");
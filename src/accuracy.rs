@@ -0,0 +1,255 @@
+//! Accuracy Assessment Against Reference Labels
+//!
+//! When station metadata includes a reference LCZ (e.g. from site surveys),
+//! the sampled classes can be evaluated against it. This module produces the
+//! standard remote-sensing accuracy measures:
+//!
+//! - **Confusion matrix**: Rows are reference classes, columns are sampled classes
+//! - **Overall accuracy**: Fraction of stations where both labels agree
+//! - **Cohen's kappa**: Agreement corrected for chance
+//!
+//! # Usage
+//!
+//! ```
+//! use polars::prelude::*;
+//! use urban_classifier::accuracy::assess_accuracy;
+//!
+//! let classified = df! {
+//!     "station_id" => ["A", "B", "C", "D"],
+//!     "lcz_code" => [2u32, 6, 14, 6],
+//!     "reference_lcz" => [2u32, 6, 14, 8],
+//! }?;
+//!
+//! let report = assess_accuracy(&classified, "lcz_code", "reference_lcz")?;
+//! assert_eq!(report.overall_accuracy, 0.75);
+//! println!("OA = {:.2}, kappa = {:.2}", report.overall_accuracy, report.kappa);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::columns::column_not_found;
+use crate::error::{ClassifierError, Result};
+use polars::prelude::*;
+
+/// Result of comparing sampled LCZ codes against reference labels
+#[derive(Debug, Clone, PartialEq)]
+pub struct AccuracyReport {
+    /// Sorted LCZ codes present in either the sampled or reference labels
+    pub classes: Vec<u8>,
+    /// Counts indexed as `[reference][sampled]`, in the order of `classes`
+    pub confusion_matrix: Vec<Vec<u64>>,
+    /// Number of stations compared
+    pub total: u64,
+    /// Fraction of stations where the sampled class equals the reference class
+    pub overall_accuracy: f64,
+    /// Cohen's kappa (NaN when chance agreement is already perfect)
+    pub kappa: f64,
+}
+
+impl AccuracyReport {
+    /// Build a report from paired sampled and reference codes
+    ///
+    /// # Arguments
+    /// * `sampled` - LCZ codes produced by the classifier
+    /// * `reference` - Reference LCZ codes, in the same order
+    pub fn from_codes(sampled: &[u8], reference: &[u8]) -> Result<Self> {
+        if sampled.len() != reference.len() {
+            return Err(ClassifierError::InvalidParameter {
+                message: format!(
+                    "Sampled and reference labels differ in length ({} vs {})",
+                    sampled.len(),
+                    reference.len()
+                ),
+            });
+        }
+        if sampled.is_empty() {
+            return Err(ClassifierError::InvalidParameter {
+                message: "No labels to compare".to_string(),
+            });
+        }
+
+        let mut classes: Vec<u8> = sampled.iter().chain(reference).copied().collect();
+        classes.sort_unstable();
+        classes.dedup();
+
+        let index_of = |code: u8| classes.binary_search(&code).unwrap_or_default();
+        let n = classes.len();
+        let mut confusion_matrix = vec![vec![0u64; n]; n];
+        for (&s, &r) in sampled.iter().zip(reference) {
+            confusion_matrix[index_of(r)][index_of(s)] += 1;
+        }
+
+        let total = sampled.len() as u64;
+        let agreed: u64 = (0..n).map(|i| confusion_matrix[i][i]).sum();
+        let overall_accuracy = agreed as f64 / total as f64;
+
+        // Expected agreement from the row and column marginals
+        let expected: f64 = (0..n)
+            .map(|i| {
+                let row: u64 = confusion_matrix[i].iter().sum();
+                let col: u64 = confusion_matrix.iter().map(|r| r[i]).sum();
+                row as f64 * col as f64
+            })
+            .sum::<f64>()
+            / (total as f64 * total as f64);
+
+        let kappa = if expected >= 1.0 {
+            f64::NAN
+        } else {
+            (overall_accuracy - expected) / (1.0 - expected)
+        };
+
+        Ok(AccuracyReport {
+            classes,
+            confusion_matrix,
+            total,
+            overall_accuracy,
+            kappa,
+        })
+    }
+
+    /// Convert the confusion matrix into a DataFrame
+    ///
+    /// The first column `reference` holds the reference class code, followed by
+    /// one `sampled_{code}` column per class.
+    pub fn confusion_dataframe(&self) -> Result<DataFrame> {
+        let mut columns = vec![Series::new(
+            "reference",
+            self.classes.iter().map(|&c| c as u32).collect::<Vec<u32>>(),
+        )];
+
+        for (j, code) in self.classes.iter().enumerate() {
+            let counts: Vec<u64> = self.confusion_matrix.iter().map(|row| row[j]).collect();
+            columns.push(Series::new(&format!("sampled_{}", code), counts));
+        }
+
+        Ok(DataFrame::new(columns)?)
+    }
+}
+
+/// Compare sampled LCZ codes against a reference column in a classified DataFrame
+///
+/// Rows where either label is null are skipped.
+///
+/// # Arguments
+/// * `classified_df` - DataFrame containing both label columns
+/// * `sampled_col` - Name of the sampled code column (usually `lcz_code`)
+/// * `reference_col` - Name of the reference code column
+pub fn assess_accuracy(
+    classified_df: &DataFrame,
+    sampled_col: &str,
+    reference_col: &str,
+) -> Result<AccuracyReport> {
+    let sampled = code_column(classified_df, sampled_col)?;
+    let reference = code_column(classified_df, reference_col)?;
+
+    let (sampled, reference): (Vec<u8>, Vec<u8>) = sampled
+        .into_iter()
+        .zip(reference)
+        .filter_map(|pair| match pair {
+            (Some(s), Some(r)) => Some((s, r)),
+            _ => None,
+        })
+        .unzip();
+
+    AccuracyReport::from_codes(&sampled, &reference)
+}
+
+/// Read an integer LCZ code column as optional u8 values
+fn code_column(df: &DataFrame, column: &str) -> Result<Vec<Option<u8>>> {
+    if !df.get_column_names().contains(&column) {
+        return Err(column_not_found(df, column));
+    }
+
+    let series = df.column(column)?;
+    if !series.dtype().is_integer() {
+        return Err(ClassifierError::SchemaValidation {
+            message: format!("LCZ code column '{}' must contain integer data", column),
+        });
+    }
+
+    series
+        .cast(&DataType::UInt32)?
+        .u32()?
+        .into_iter()
+        .map(|opt| match opt {
+            Some(code) => {
+                u8::try_from(code)
+                    .map(Some)
+                    .map_err(|_| ClassifierError::SchemaValidation {
+                        message: format!(
+                            "LCZ code column '{}' has value {} above 255",
+                            column, code
+                        ),
+                    })
+            }
+            None => Ok(None),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test confusion matrix layout and overall accuracy
+    #[test]
+    fn test_confusion_matrix() {
+        let report = AccuracyReport::from_codes(&[2, 2, 6, 14], &[2, 6, 6, 14]).unwrap();
+
+        assert_eq!(report.classes, vec![2, 6, 14]);
+        assert_eq!(report.total, 4);
+        assert_eq!(report.overall_accuracy, 0.75);
+
+        // Reference 6 was sampled once as 2 and once as 6
+        assert_eq!(report.confusion_matrix[1], vec![1, 1, 0]);
+        assert_eq!(report.confusion_matrix[0], vec![1, 0, 0]);
+    }
+
+    /// Test Cohen's kappa against a hand-computed value
+    #[test]
+    fn test_kappa() {
+        // po = 0.75, pe = (1*2 + 2*1 + 1*1) / 16 = 5/16
+        let report = AccuracyReport::from_codes(&[2, 2, 6, 14], &[2, 6, 6, 14]).unwrap();
+        let expected = (0.75 - 5.0 / 16.0) / (1.0 - 5.0 / 16.0);
+        assert!((report.kappa - expected).abs() < 1e-12);
+
+        // Perfect agreement gives kappa of 1
+        let perfect = AccuracyReport::from_codes(&[1, 11], &[1, 11]).unwrap();
+        assert_eq!(perfect.kappa, 1.0);
+
+        // A single class gives undefined kappa
+        let single = AccuracyReport::from_codes(&[6, 6], &[6, 6]).unwrap();
+        assert!(single.kappa.is_nan());
+    }
+
+    /// Test assessment from DataFrame columns with null reference labels
+    #[test]
+    fn test_assess_accuracy_from_dataframe() {
+        let df = df! {
+            "lcz_code" => [2u32, 6, 14],
+            "reference_lcz" => [Some(2i64), None, Some(11)],
+        }
+        .unwrap();
+
+        let report = assess_accuracy(&df, "lcz_code", "reference_lcz").unwrap();
+        assert_eq!(report.total, 2);
+        assert_eq!(report.overall_accuracy, 0.5);
+
+        let confusion = report.confusion_dataframe().unwrap();
+        assert_eq!(
+            confusion.get_column_names(),
+            vec!["reference", "sampled_2", "sampled_11", "sampled_14"]
+        );
+    }
+
+    /// Test that a missing reference column is reported
+    #[test]
+    fn test_missing_reference_column() {
+        let df = df! { "lcz_code" => [1u32] }.unwrap();
+        let result = assess_accuracy(&df, "lcz_code", "reference_lcz");
+        assert!(matches!(
+            result,
+            Err(ClassifierError::ColumnNotFound { .. })
+        ));
+    }
+}
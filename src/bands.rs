@@ -0,0 +1,356 @@
+//! Raster Band Selection and Auxiliary Band Sampling
+//!
+//! LCZ Generator products ship multi-band rasters: besides the class layer they
+//! include per-pixel classification probability and filtered layers. This module
+//! lets callers refer to bands by 1-based index or by band description, and
+//! sample auxiliary bands alongside the LCZ class, either one probability band
+//! or any set of bands in a single pass over the stations.
+
+#[cfg(feature = "gdal")]
+use crate::classifier::UrbanClassifier;
+#[cfg(feature = "gdal")]
+use crate::error::{ClassifierError, Result};
+#[cfg(feature = "gdal")]
+use crate::source::PixelLocator;
+
+#[cfg(feature = "gdal")]
+use gdal::raster::RasterBand;
+#[cfg(feature = "gdal")]
+use gdal::{Dataset, Metadata};
+#[cfg(feature = "gdal")]
+use polars::prelude::*;
+#[cfg(feature = "gdal")]
+use std::collections::HashMap;
+use std::fmt;
+
+/// Reference to a raster band by 1-based index or by description
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BandSelector {
+    /// 1-based band index, as used by GDAL
+    Index(usize),
+    /// Band description (matched case-insensitively), e.g. "lcz_probability"
+    Name(String),
+}
+
+impl fmt::Display for BandSelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BandSelector::Index(index) => write!(f, "band {}", index),
+            BandSelector::Name(name) => write!(f, "band '{}'", name),
+        }
+    }
+}
+
+impl From<usize> for BandSelector {
+    fn from(index: usize) -> Self {
+        BandSelector::Index(index)
+    }
+}
+
+impl From<&str> for BandSelector {
+    fn from(name: &str) -> Self {
+        BandSelector::Name(name.to_string())
+    }
+}
+
+/// Bands sampled by `UrbanClassifier::run_classification_with_bands`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BandSet {
+    /// Every band of the raster
+    All,
+    /// The listed bands, in order
+    Selected(Vec<BandSelector>),
+}
+
+impl<S: Into<BandSelector>> FromIterator<S> for BandSet {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        BandSet::Selected(iter.into_iter().map(Into::into).collect())
+    }
+}
+
+/// Resolve a band selector to a 1-based GDAL band index
+#[cfg(feature = "gdal")]
+pub fn resolve_band(dataset: &Dataset, selector: &BandSelector) -> Result<isize> {
+    let band_count = dataset.raster_count();
+
+    match selector {
+        BandSelector::Index(index) => {
+            if *index == 0 || *index as isize > band_count {
+                return Err(ClassifierError::GdalError {
+                    message: format!(
+                        "Band index {} out of range: dataset has {} band(s)",
+                        index, band_count
+                    ),
+                    source: None,
+                });
+            }
+            Ok(*index as isize)
+        }
+        BandSelector::Name(name) => {
+            let mut descriptions = Vec::new();
+            for index in 1..=band_count {
+                let description = dataset.rasterband(index)?.description()?;
+                if description.eq_ignore_ascii_case(name) {
+                    return Ok(index);
+                }
+                descriptions.push(description);
+            }
+            Err(ClassifierError::GdalError {
+                message: format!(
+                    "No band named '{}'. Available band descriptions: {:?}",
+                    name, descriptions
+                ),
+                source: None,
+            })
+        }
+    }
+}
+
+/// Output column name for a band: `band_<description>`, or `band_<index>` without one
+///
+/// Descriptions are lowercased and characters other than ASCII letters and
+/// digits replaced with underscores.
+#[cfg(feature = "gdal")]
+fn band_column_name(index: isize, description: &str) -> String {
+    let name: String = description
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() {
+        format!("band_{}", index)
+    } else {
+        format!("band_{}", name)
+    }
+}
+
+/// Sample a pixel as f64, returning `None` for nodata or out-of-bounds pixels
+#[cfg(feature = "gdal")]
+pub(crate) fn sample_band_f64(band: &RasterBand, pixel: isize, line: isize) -> Result<Option<f64>> {
+    let (width, height) = band.size();
+    if pixel < 0 || line < 0 || pixel >= width as isize || line >= height as isize {
+        return Ok(None);
+    }
+
+    let mut buffer = [0.0f64];
+    band.read_into_slice((pixel, line), (1, 1), (1, 1), &mut buffer, None)
+        .map_err(|e| ClassifierError::RasterSampling {
+            pixel,
+            line,
+            message: format!("Failed to read raster value: {}", e),
+            station_id: None,
+            row_index: None,
+        })?;
+
+    let value = buffer[0];
+    match band.no_data_value() {
+        Some(nodata) if value == nodata || (nodata.is_nan() && value.is_nan()) => Ok(None),
+        _ => Ok(Some(value)),
+    }
+}
+
+#[cfg(feature = "gdal")]
+impl UrbanClassifier {
+    /// Run LCZ classification and also sample a classification probability band
+    ///
+    /// # Arguments
+    /// * `stations_df` - DataFrame containing station data
+    /// * `station_id_col` - Name of the column containing station IDs
+    /// * `lon_col` - Name of the column containing longitude values
+    /// * `lat_col` - Name of the column containing latitude values
+    /// * `overrides` - Optional map of station IDs to manual LCZ codes
+    /// * `probability_band` - Band holding per-pixel probability, by index or name
+    ///
+    /// # Returns
+    /// The `run_classification` output with an additional `lcz_probability`
+    /// column holding the raw band value (null where the band has nodata).
+    /// LCZ Generator products store probability as a percentage (0-100).
+    pub fn run_classification_with_probability(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        probability_band: &BandSelector,
+    ) -> Result<DataFrame> {
+        let bands: Vec<(isize, String)> = self
+            .resolve_band_columns(
+                &BandSet::Selected(vec![probability_band.clone()]),
+                stations_df,
+            )?
+            .into_iter()
+            .map(|(index, _)| (index, "lcz_probability".to_string()))
+            .collect();
+
+        let mut classified =
+            self.run_classification(stations_df, station_id_col, lon_col, lat_col, overrides)?;
+        let (_, coordinates) =
+            self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+
+        classified.hstack_mut(&self.band_series(&bands, &coordinates)?)?;
+        Ok(classified)
+    }
+
+    /// Run LCZ classification and sample several bands at each station in one pass
+    ///
+    /// Each station is located once and every requested band is read at the
+    /// same pixel, which suits LCZ Generator outputs bundling class,
+    /// probability and filter bands.
+    ///
+    /// # Arguments
+    /// * `stations_df` - DataFrame containing station data
+    /// * `station_id_col` - Name of the column containing station IDs
+    /// * `lon_col` - Name of the column containing longitude values
+    /// * `lat_col` - Name of the column containing latitude values
+    /// * `overrides` - Optional map of station IDs to manual LCZ codes
+    /// * `bands` - `BandSet::All` or the bands to sample, by index or name
+    ///
+    /// # Returns
+    /// The `run_classification` output with one f64 column per band holding
+    /// the raw band value (null where the band has nodata). Columns are named
+    /// `band_<description>`, e.g. `band_lcz_probability`, or `band_<index>`
+    /// for bands without a description or whose name is already taken.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use polars::prelude::*;
+    /// use urban_classifier::bands::BandSet;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/lcz_generator.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A"],
+    ///     "longitude" => [-0.12],
+    ///     "latitude" => [51.5],
+    /// }?;
+    /// let bands: BandSet = ["lcz_probability", "lcz_filter"].into_iter().collect();
+    /// let result = classifier.run_classification_with_bands(
+    ///     &stations, "station_id", "longitude", "latitude", None, &bands,
+    /// )?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn run_classification_with_bands(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        bands: &BandSet,
+    ) -> Result<DataFrame> {
+        let bands = self.resolve_band_columns(bands, stations_df)?;
+
+        let mut classified =
+            self.run_classification(stations_df, station_id_col, lon_col, lat_col, overrides)?;
+        let (_, coordinates) =
+            self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+
+        classified.hstack_mut(&self.band_series(&bands, &coordinates)?)?;
+        Ok(classified)
+    }
+
+    /// Resolve a band set to (band index, output column name) pairs
+    ///
+    /// Names clashing with another band or a column of `stations_df` fall back
+    /// to `band_<index>`.
+    pub(crate) fn resolve_band_columns(
+        &self,
+        bands: &BandSet,
+        stations_df: &DataFrame,
+    ) -> Result<Vec<(isize, String)>> {
+        let dataset = self.dataset()?;
+        let band_indices: Vec<isize> = match bands {
+            BandSet::All => (1..=dataset.raster_count()).collect(),
+            BandSet::Selected(selectors) => selectors
+                .iter()
+                .map(|selector| resolve_band(&dataset, selector))
+                .collect::<Result<_>>()?,
+        };
+
+        let mut columns: Vec<(isize, String)> = Vec::with_capacity(band_indices.len());
+        for index in band_indices {
+            let description = dataset.rasterband(index)?.description()?;
+            let mut name = band_column_name(index, &description);
+            if columns.iter().any(|(_, taken)| *taken == name)
+                || stations_df.get_column_names().contains(&name.as_str())
+            {
+                name = format!("band_{}", index);
+            }
+            columns.push((index, name));
+        }
+        Ok(columns)
+    }
+
+    /// Sample resolved bands at each coordinate, one f64 column per band
+    pub(crate) fn band_series(
+        &self,
+        bands: &[(isize, String)],
+        coordinates: &[(f64, f64)],
+    ) -> Result<Vec<Series>> {
+        let dataset = self.dataset()?;
+        let raster_bands = bands
+            .iter()
+            .map(|(index, _)| dataset.rasterband(*index))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let locator = PixelLocator::new(self.source_metadata())?;
+
+        let mut values = vec![Vec::with_capacity(coordinates.len()); raster_bands.len()];
+        for (row, (lon, lat)) in coordinates.iter().enumerate() {
+            let (pixel, line) = locator.locate(*lon, *lat).map_err(|e| e.with_row(row))?;
+            for (band, column) in raster_bands.iter().zip(values.iter_mut()) {
+                column.push(sample_band_f64(band, pixel, line).map_err(|e| e.with_row(row))?);
+            }
+        }
+
+        Ok(bands
+            .iter()
+            .zip(values)
+            .map(|((_, name), column)| Series::new(name, column))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test band selector conversions and display
+    #[test]
+    fn test_band_selector() {
+        assert_eq!(BandSelector::from(2), BandSelector::Index(2));
+        assert_eq!(
+            BandSelector::from("probability"),
+            BandSelector::Name("probability".to_string())
+        );
+        assert_eq!(BandSelector::Index(3).to_string(), "band 3");
+        assert_eq!(
+            BandSelector::Name("lcz".to_string()).to_string(),
+            "band 'lcz'"
+        );
+    }
+
+    /// Test band column names from descriptions and indices
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_band_column_name() {
+        assert_eq!(
+            band_column_name(2, "LCZ Probability"),
+            "band_lcz_probability"
+        );
+        assert_eq!(band_column_name(3, "  "), "band_3");
+        assert_eq!(
+            ["lcz", "probability"].into_iter().collect::<BandSet>(),
+            BandSet::Selected(vec![
+                BandSelector::from("lcz"),
+                BandSelector::from("probability")
+            ])
+        );
+    }
+}
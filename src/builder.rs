@@ -0,0 +1,228 @@
+//! Fluent Classifier Construction
+//!
+//! `UrbanClassifier::builder()` collects everything that affects how the
+//! raster is opened and sampled, then opens it once in `build()`. This keeps
+//! construction in one place as options accumulate, instead of chaining
+//! `with_*` calls on an already opened classifier.
+//!
+//! # Examples
+//! ```no_run
+//! use urban_classifier::mapping::CodeMapping;
+//! use urban_classifier::preload::PreloadStrategy;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! let classifier = UrbanClassifier::builder()
+//!     .with_path("https://example.org/lcz_generator_cog.tif")
+//!     .with_band("lcz_filter")
+//!     .with_code_mapping(CodeMapping::natural_101_107())
+//!     .with_nodata_override(0)
+//!     .with_tile_cache(256)
+//!     .with_threads(4)
+//!     .build()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::bands::BandSelector;
+use crate::category::CategoryScheme;
+use crate::classifier::{UrbanClassifier, ValidationMode};
+use crate::error::{ClassifierError, Result};
+use crate::mapping::CodeMapping;
+use crate::open::OpenOptions;
+use crate::preload::PreloadStrategy;
+use crate::smoothing::MajorityFilter;
+use crate::source::RasterCrs;
+use crate::spatial::CrsSpec;
+use std::path::PathBuf;
+
+/// Options for opening and sampling an LCZ raster, applied by `build()`
+#[derive(Debug, Clone, Default)]
+pub struct UrbanClassifierBuilder {
+    path: Option<PathBuf>,
+    open_options: OpenOptions,
+    threads: Option<usize>,
+    code_mapping: CodeMapping,
+    category_scheme: CategoryScheme,
+    nodata_override: Option<u8>,
+    validation_mode: ValidationMode,
+    preload: PreloadStrategy,
+    tile_cache: usize,
+    overview_min_radius: Option<usize>,
+    majority_filter: Option<MajorityFilter>,
+    raw_value_column: bool,
+}
+
+impl UrbanClassifier {
+    /// Start configuring a classifier
+    pub fn builder() -> UrbanClassifierBuilder {
+        UrbanClassifierBuilder::default()
+    }
+}
+
+impl UrbanClassifierBuilder {
+    /// Set the raster path or `https://`, `s3://` or `gs://` URL
+    ///
+    /// Defaults to `UrbanClassifier::default_data_path()`.
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Sample LCZ classes from another band, by 1-based index or description
+    pub fn with_band(mut self, band: impl Into<BandSelector>) -> Self {
+        self.open_options = self.open_options.with_band(band);
+        self
+    }
+
+    /// Add a GDAL driver open option, e.g. `("GTIFF_DIRECT_IO", "YES")`
+    pub fn with_open_option(mut self, name: &str, value: &str) -> Self {
+        self.open_options = self.open_options.with_open_option(name, value);
+        self
+    }
+
+    /// CRS to use when the raster has no spatial reference of its own
+    pub fn assume_crs(mut self, crs: RasterCrs) -> Self {
+        self.open_options = self.open_options.assume_crs(crs);
+        self
+    }
+
+    /// Reproject the raster on the fly, e.g. to `CrsSpec::EQUAL_AREA`
+    pub fn with_warp_to(mut self, crs: CrsSpec) -> Self {
+        self.open_options = self.open_options.with_warp_to(crs);
+        self
+    }
+
+    /// Decode raster blocks with `threads` GDAL worker threads
+    ///
+    /// Sets the `NUM_THREADS` open option, overriding one set directly.
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Set the mapping applied to raw raster values before LCZ lookup
+    pub fn with_code_mapping(mut self, mapping: CodeMapping) -> Self {
+        self.code_mapping = mapping;
+        self
+    }
+
+    /// Treat raw raster values equal to `value` as nodata, replacing the
+    /// nodata value from the raster's metadata
+    pub fn with_nodata_override(mut self, value: u8) -> Self {
+        self.nodata_override = Some(value);
+        self
+    }
+
+    /// Set how codes outside the 17 standard LCZ classes are handled
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.validation_mode = mode;
+        self
+    }
+
+    /// Hold the LCZ band, or part of it, in memory once opened
+    pub fn with_preload(mut self, strategy: PreloadStrategy) -> Self {
+        self.preload = strategy;
+        self
+    }
+
+    /// Set the grouping of LCZ classes used for the `simple_class` column
+    pub fn with_category_scheme(mut self, scheme: CategoryScheme) -> Self {
+        self.category_scheme = scheme;
+        self
+    }
+
+    /// Cache up to `capacity` decoded blocks; zero (the default) disables the cache
+    pub fn with_tile_cache(mut self, capacity: usize) -> Self {
+        self.tile_cache = capacity;
+        self
+    }
+
+    /// Read large buffer statistics from overviews; see `UrbanClassifier::with_overviews`
+    pub fn with_overviews(mut self, min_radius_pixels: Option<usize>) -> Self {
+        self.overview_min_radius = min_radius_pixels;
+        self
+    }
+
+    /// Add a `raw_value` column with each station's unmapped pixel value
+    pub fn with_raw_value_column(mut self, enabled: bool) -> Self {
+        self.raw_value_column = enabled;
+        self
+    }
+
+    /// Smooth the LCZ map with a majority filter before sampling stations
+    pub fn with_majority_filter(mut self, filter: MajorityFilter) -> Self {
+        self.majority_filter = Some(filter);
+        self
+    }
+
+    /// Open the raster and apply every configured option
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::InvalidParameter` for a thread count of zero,
+    /// and otherwise the errors of `UrbanClassifier::open_with` and
+    /// `UrbanClassifier::with_preload`.
+    pub fn build(self) -> Result<UrbanClassifier> {
+        let mut open_options = self.open_options;
+        match self.threads {
+            Some(0) => {
+                return Err(ClassifierError::InvalidParameter {
+                    message: "Thread count must be at least 1".to_string(),
+                })
+            }
+            Some(threads) => {
+                open_options = open_options.with_open_option("NUM_THREADS", &threads.to_string())
+            }
+            None => {}
+        }
+
+        let path = self.path.unwrap_or_else(UrbanClassifier::default_data_path);
+        let classifier = UrbanClassifier::open_with(path, &open_options)?
+            .with_code_mapping(self.code_mapping)
+            .with_category_scheme(self.category_scheme)
+            .with_validation_mode(self.validation_mode)
+            .with_tile_cache(self.tile_cache)
+            .with_overviews(self.overview_min_radius)
+            .with_raw_value_column(self.raw_value_column);
+        let classifier = match self.nodata_override {
+            Some(value) => classifier.with_nodata_override(value),
+            None => classifier,
+        };
+        let classifier = match self.majority_filter {
+            Some(filter) => classifier.with_majority_filter(filter),
+            None => classifier,
+        };
+
+        classifier.with_preload(self.preload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that a zero thread count is rejected before opening the raster
+    #[test]
+    fn test_zero_threads() {
+        let result = UrbanClassifier::builder()
+            .with_path("/nonexistent/lcz.tif")
+            .with_threads(0)
+            .build();
+        assert!(matches!(
+            result,
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+    }
+
+    /// Test that missing rasters are reported with their path
+    #[test]
+    fn test_missing_raster() {
+        let result = UrbanClassifier::builder()
+            .with_path("/nonexistent/lcz.tif")
+            .with_band(2)
+            .with_tile_cache(16)
+            .build();
+        assert!(matches!(
+            result,
+            Err(ClassifierError::FileNotFound { path }) if path == "/nonexistent/lcz.tif"
+        ));
+    }
+}
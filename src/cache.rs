@@ -0,0 +1,174 @@
+//! LRU Cache of Decoded Raster Blocks
+//!
+//! GeoTIFFs are stored in compressed blocks (tiles or strips), and GDAL decodes
+//! a whole block to return a single pixel. When stations are spatially clustered,
+//! many samples fall in the same few blocks. This cache keeps recently used
+//! decoded blocks of the LCZ band in memory so those samples skip the file.
+//!
+//! The cache is shared by all threads using a classifier and bounded by a
+//! number of blocks; the least recently used block is evicted first.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::metrics::SampleCounts;
+use crate::source::{RasterMetadata, RasterSource};
+use crate::spatial::PixelWindow;
+use crate::trace::trace_event;
+use lru::LruCache;
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Hit and miss counts of a tile cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Samples served from a cached block
+    pub hits: u64,
+    /// Samples that required reading a block from the file
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Fraction of samples served from the cache, or `None` before any sample
+    pub fn hit_rate(&self) -> Option<f64> {
+        let total = self.hits + self.misses;
+        (total > 0).then(|| self.hits as f64 / total as f64)
+    }
+}
+
+/// LRU cache of decoded LCZ band blocks, keyed by block column and row
+pub(crate) struct TileCache {
+    blocks: Mutex<LruCache<(usize, usize), Arc<PixelWindow>>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl TileCache {
+    /// Create a cache holding at most `capacity` blocks
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        TileCache {
+            blocks: Mutex::new(LruCache::new(capacity)),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Sample a pixel through the cache, recording hits and block reads in `counts`
+    ///
+    /// Returns `None` for pixels outside the raster, leaving the caller to
+    /// report the out-of-bounds error.
+    pub(crate) fn sample(
+        &self,
+        source: &dyn RasterSource,
+        metadata: &RasterMetadata,
+        pixel: isize,
+        line: isize,
+        counts: &mut SampleCounts,
+    ) -> Result<Option<u8>> {
+        if pixel < 0
+            || line < 0
+            || pixel >= metadata.width as isize
+            || line >= metadata.height as isize
+        {
+            return Ok(None);
+        }
+
+        let (block_width, block_height) = metadata.block_size;
+        let block_size = (block_width.max(1), block_height.max(1));
+        let key = (pixel as usize / block_size.0, line as usize / block_size.1);
+
+        if let Some(block) = self.lock_blocks().get(&key).cloned() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            counts.cache_hits += 1;
+            return Ok(block.get(pixel, line));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        trace_event!(block_col = key.0, block_row = key.1, "tile cache miss");
+
+        // Read outside the lock so other threads are not blocked on I/O
+        counts.raster_reads += 1;
+        let block = match source.read_window(
+            (key.0 * block_size.0) as isize,
+            (key.1 * block_size.1) as isize,
+            block_size.0,
+            block_size.1,
+        ) {
+            Ok(block) => Arc::new(block),
+            // A block holding a value that is not a byte code cannot be
+            // cached; read the pixel alone so only its own value can fail
+            Err(ClassifierError::UnexpectedRasterValue { .. }) => {
+                counts.raster_reads += 1;
+                return source.sample(pixel, line).map(Some);
+            }
+            Err(e) => return Err(e),
+        };
+        let value = block.get(pixel, line);
+        self.lock_blocks().put(key, block);
+
+        Ok(value)
+    }
+
+    /// Current hit and miss counts
+    pub(crate) fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Lock the block map, recovering from poisoning
+    ///
+    /// Entries are only inserted whole, so a panic while holding the lock
+    /// cannot leave a partially written block behind.
+    fn lock_blocks(&self) -> MutexGuard<'_, LruCache<(usize, usize), Arc<PixelWindow>>> {
+        self.blocks
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl UrbanClassifier {
+    /// Cache up to `capacity` decoded blocks of the LCZ band for point sampling
+    ///
+    /// A capacity of zero disables the cache. Samples served from a preloaded
+    /// band do not go through the cache.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?.with_tile_cache(256);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_tile_cache(mut self, capacity: usize) -> Self {
+        self.set_tile_cache(NonZeroUsize::new(capacity).map(TileCache::new));
+        self
+    }
+
+    /// Hit and miss counts of the tile cache, if one is configured
+    pub fn tile_cache_stats(&self) -> Option<CacheStats> {
+        self.tile_cache().map(TileCache::stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test hit rate computation
+    #[test]
+    fn test_hit_rate() {
+        assert_eq!(CacheStats::default().hit_rate(), None);
+
+        let stats = CacheStats { hits: 3, misses: 1 };
+        assert_eq!(stats.hit_rate(), Some(0.75));
+    }
+
+    /// Test that the cache can be shared between threads
+    #[test]
+    fn test_cache_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<TileCache>();
+    }
+}
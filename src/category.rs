@@ -0,0 +1,262 @@
+//! LCZ to Simple Category Mapping
+//!
+//! The `simple_class` output groups LCZ classes into Urban, Suburban and
+//! Rural. The default grouping (1-6 Urban, 7-10 Suburban, A-G Rural) is one
+//! convention among several: many urban heat island studies treat sparsely
+//! built areas (LCZ 9) as rural. A `CategoryScheme` lets callers choose the
+//! category of each class.
+//!
+//! # Unknown Codes and Nodata
+//!
+//! Codes outside the 17 classes are Rural by default, which biases results
+//! when unexpected values are common. `with_unknown_category` assigns them
+//! another category, e.g. `LczCategory::Unknown`. Nodata stations have a null
+//! `simple_class` unless `with_nodata_category` gives them one.
+//!
+//! # Other Groupings
+//!
+//! `Grouping` provides fixed alternative groupings (built vs natural,
+//! urban form, UHI potential). `Lcz::category` labels a class under one, and
+//! `ClassificationRequest::with_grouping` adds a column for it to the output.
+//!
+//! # Examples
+//!
+//! ```
+//! use urban_classifier::category::CategoryScheme;
+//! use urban_classifier::{Lcz, LczCategory};
+//!
+//! let scheme = CategoryScheme::standard().with_category(Lcz::SparselyBuilt, LczCategory::Rural);
+//! assert_eq!(scheme.category(Lcz::SparselyBuilt), LczCategory::Rural);
+//! assert_eq!(scheme.category(Lcz::HeavyIndustry), LczCategory::Suburban);
+//! ```
+
+use crate::lcz::{Lcz, LczCategory};
+use polars::prelude::*;
+
+/// Category assigned to each of the 17 standard LCZ classes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CategoryScheme {
+    categories: [LczCategory; 17],
+    unknown: LczCategory,
+    nodata: Option<LczCategory>,
+}
+
+impl Default for CategoryScheme {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl CategoryScheme {
+    /// Create the default grouping of `Lcz::simple_category`
+    pub fn standard() -> Self {
+        CategoryScheme {
+            categories: Lcz::ALL.map(|lcz| lcz.simple_category()),
+            unknown: LczCategory::Rural,
+            nodata: None,
+        }
+    }
+
+    /// Create the standard grouping with sparsely built areas (LCZ 9) as Rural
+    pub fn sparsely_built_rural() -> Self {
+        Self::standard().with_category(Lcz::SparselyBuilt, LczCategory::Rural)
+    }
+
+    /// Assign a class to a category; unknown classes are ignored, see
+    /// `with_unknown_category`
+    pub fn with_category(mut self, lcz: Lcz, category: LczCategory) -> Self {
+        if let Some(index) = Self::index(lcz) {
+            self.categories[index] = category;
+        }
+        self
+    }
+
+    /// Assign codes outside the 17 classes to a category (Rural by default)
+    pub fn with_unknown_category(mut self, category: LczCategory) -> Self {
+        self.unknown = category;
+        self
+    }
+
+    /// Assign nodata stations to a category instead of leaving them null
+    pub fn with_nodata_category(mut self, category: LczCategory) -> Self {
+        self.nodata = Some(category);
+        self
+    }
+
+    /// Get the category of nodata stations, if they have one
+    pub fn nodata_category(&self) -> Option<LczCategory> {
+        self.nodata
+    }
+
+    /// Check whether the scheme matches the default grouping
+    pub fn is_standard(&self) -> bool {
+        *self == Self::standard()
+    }
+
+    /// Get the category of a class
+    pub fn category(&self, lcz: Lcz) -> LczCategory {
+        match Self::index(lcz) {
+            Some(index) => self.categories[index],
+            None => self.unknown,
+        }
+    }
+
+    fn index(lcz: Lcz) -> Option<usize> {
+        lcz.is_standard().then(|| usize::from(lcz.to_code()) - 1)
+    }
+}
+
+/// Built-in alternative groupings of LCZ classes
+///
+/// Codes outside the 17 classes are labelled `Unknown` in every grouping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Grouping {
+    /// `Built` (LCZ 1-10) or `Natural` (LCZ A-G)
+    BuiltNatural,
+    /// Urban form and land cover: `Compact` (1-3, 7), `Open` (4-6, 9),
+    /// `Industrial` (8, 10), `Vegetated` (A-D), `Bare` (E, F) or `Water` (G)
+    UrbanForm,
+    /// Coarse ranking of nocturnal urban heat island potential: `High`
+    /// (1-3, 7, 10), `Moderate` (4, 5, 8), `Low` (6, 9, E) or `Minimal`
+    /// (A-D, F, G)
+    UhiPotential,
+}
+
+impl Grouping {
+    /// Name of the output column added for this grouping
+    pub fn column_name(&self) -> &'static str {
+        match self {
+            Grouping::BuiltNatural => "built_natural",
+            Grouping::UrbanForm => "urban_form",
+            Grouping::UhiPotential => "uhi_potential",
+        }
+    }
+
+    /// Label of a class under this grouping
+    pub fn label(&self, lcz: Lcz) -> &'static str {
+        use Lcz::*;
+
+        if !lcz.is_standard() {
+            return "Unknown";
+        }
+        match self {
+            Grouping::BuiltNatural => {
+                if lcz.to_code() <= 10 {
+                    "Built"
+                } else {
+                    "Natural"
+                }
+            }
+            Grouping::UrbanForm => match lcz {
+                CompactHighRise | CompactMidRise | CompactLowRise | LightweightLowRise => "Compact",
+                OpenHighRise | OpenMidRise | OpenLowRise | SparselyBuilt => "Open",
+                LargeLowRise | HeavyIndustry => "Industrial",
+                DenseTrees | ScatteredTrees | BushScrub | LowPlants => "Vegetated",
+                BareRockPaved | BareSoilSand => "Bare",
+                Water | Unknown(_) => "Water",
+            },
+            Grouping::UhiPotential => match lcz {
+                CompactHighRise | CompactMidRise | CompactLowRise | LightweightLowRise
+                | HeavyIndustry => "High",
+                OpenHighRise | OpenMidRise | LargeLowRise => "Moderate",
+                OpenLowRise | SparselyBuilt | BareRockPaved => "Low",
+                _ => "Minimal",
+            },
+        }
+    }
+
+    /// Label each code of an `lcz_code` column, keeping nulls
+    pub(crate) fn series(&self, codes: &UInt32Chunked) -> Series {
+        let labels: Vec<Option<&str>> = codes
+            .into_iter()
+            .map(|code| code.map(|code| self.label(Lcz::from_code(code as u8))))
+            .collect();
+        Series::new(self.column_name(), labels)
+    }
+}
+
+impl Lcz {
+    /// Label this class under a built-in grouping
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::category::Grouping;
+    /// use urban_classifier::Lcz;
+    ///
+    /// assert_eq!(Lcz::LargeLowRise.category(Grouping::BuiltNatural), "Built");
+    /// assert_eq!(Lcz::LargeLowRise.category(Grouping::UrbanForm), "Industrial");
+    /// ```
+    pub fn category(&self, grouping: Grouping) -> &'static str {
+        grouping.label(*self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the standard scheme matches `Lcz::simple_category`
+    #[test]
+    fn test_standard_scheme() {
+        let scheme = CategoryScheme::default();
+        assert!(scheme.is_standard());
+        for lcz in Lcz::iter() {
+            assert_eq!(scheme.category(lcz), lcz.simple_category());
+        }
+        assert_eq!(scheme.category(Lcz::Unknown(0)), LczCategory::Rural);
+        assert_eq!(scheme.nodata_category(), None);
+    }
+
+    /// Test custom assignments, with later entries taking precedence
+    #[test]
+    fn test_custom_scheme() {
+        let scheme = CategoryScheme::sparsely_built_rural()
+            .with_category(Lcz::HeavyIndustry, LczCategory::Urban)
+            .with_category(Lcz::HeavyIndustry, LczCategory::Rural)
+            .with_category(Lcz::Unknown(99), LczCategory::Urban);
+
+        assert!(!scheme.is_standard());
+        assert_eq!(scheme.category(Lcz::SparselyBuilt), LczCategory::Rural);
+        assert_eq!(scheme.category(Lcz::HeavyIndustry), LczCategory::Rural);
+        assert_eq!(scheme.category(Lcz::LargeLowRise), LczCategory::Suburban);
+        assert_eq!(scheme.category(Lcz::Unknown(99)), LczCategory::Rural);
+    }
+
+    /// Test the categories of unknown codes and nodata
+    #[test]
+    fn test_unknown_and_nodata_categories() {
+        let scheme = CategoryScheme::standard()
+            .with_unknown_category(LczCategory::Unknown)
+            .with_nodata_category(LczCategory::Unknown);
+
+        assert!(!scheme.is_standard());
+        assert_eq!(scheme.category(Lcz::Unknown(0)), LczCategory::Unknown);
+        assert_eq!(scheme.category(Lcz::Water), LczCategory::Rural);
+        assert_eq!(scheme.nodata_category(), Some(LczCategory::Unknown));
+        assert_eq!(LczCategory::Unknown.as_ref(), "Unknown");
+    }
+
+    /// Test labels of the built-in groupings
+    #[test]
+    fn test_groupings() {
+        for lcz in Lcz::iter() {
+            let built = lcz.category(Grouping::BuiltNatural) == "Built";
+            assert_eq!(built, lcz.to_code() <= 10);
+        }
+        assert_eq!(
+            Lcz::LightweightLowRise.category(Grouping::UrbanForm),
+            "Compact"
+        );
+        assert_eq!(Lcz::SparselyBuilt.category(Grouping::UrbanForm), "Open");
+        assert_eq!(Lcz::Water.category(Grouping::UrbanForm), "Water");
+        assert_eq!(Lcz::CompactMidRise.category(Grouping::UhiPotential), "High");
+        assert_eq!(Lcz::DenseTrees.category(Grouping::UhiPotential), "Minimal");
+        assert_eq!(Lcz::Unknown(0).category(Grouping::UhiPotential), "Unknown");
+
+        let codes = UInt32Chunked::from_iter([Some(2), None, Some(14)]);
+        let series = Grouping::BuiltNatural.series(&codes);
+        assert_eq!(series.name(), "built_natural");
+        let labels: Vec<Option<&str>> = series.str().unwrap().into_iter().collect();
+        assert_eq!(labels, vec![Some("Built"), None, Some("Natural")]);
+    }
+}
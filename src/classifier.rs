@@ -8,7 +8,7 @@
 //! 1. Load a WUDAPT GeoTIFF file containing LCZ classifications
 //! 2. Accept a DataFrame with station locations (ID, longitude, latitude)
 //! 3. Transform WGS84 coordinates to the raster's coordinate system
-//! 4. Sample the raster at each location to get LCZ codes
+//! 4. Sample the raster at each location and map raw values to LCZ codes
 //! 5. Apply optional manual overrides
 //! 6. Return enhanced DataFrame with LCZ classifications
 //!
@@ -17,26 +17,98 @@
 //! - Automatic coordinate system transformation
 //! - Bounds checking for raster sampling
 //! - Manual override support for known misclassifications
+//! - Configurable code mapping for non-WUDAPT LCZ encodings
+//! - Optional strict validation of unexpected raster values
 //! - Detailed error reporting
 
+#[cfg(feature = "gdal")]
+use crate::bands::resolve_band;
+use crate::cache::TileCache;
+use crate::category::CategoryScheme;
+use crate::columns::column_not_found;
+use crate::composition::{class_counts_in_buffer, composition_columns};
 use crate::error::{ClassifierError, Result};
+use crate::failures::{ClassificationErrors, FailureMode, FailureSink};
 use crate::lcz::Lcz;
-use crate::spatial::{
-    create_wgs84_to_raster_transform, geo_to_pixel, sample_raster_value, transform_coordinate,
-    validate_geo_transform,
-};
+use crate::mapping::CodeMapping;
+use crate::metrics::{ClassificationMetrics, SampleCounts};
+#[cfg(feature = "gdal")]
+use crate::open::OpenOptions;
+#[cfg(feature = "gdal")]
+use crate::overview::{overview_levels, select_overview};
+#[cfg(feature = "gdal")]
+use crate::pool::{DatasetPool, PooledDataset};
+use crate::preload::PreloadedBand;
+#[cfg(feature = "gdal")]
+use crate::remote::to_gdal_path;
+use crate::request::{ClassificationRequest, OutputColumns};
+use crate::smoothing::MajorityFilter;
+#[cfg(feature = "gdal")]
+use crate::source::GdalSource;
+use crate::source::{PixelLocator, RasterMetadata, RasterSource};
+#[cfg(feature = "gdal")]
+use crate::spatial::read_pixel_window;
+use crate::spatial::{buffer_radius_pixels, validate_geo_transform, PixelWindow};
+use crate::stability::{agreement_fraction, jitter_offsets, offset_lonlat, StabilityConfig};
+use crate::trace::{debug_event, stage_span, trace_event};
+#[cfg(feature = "gdal")]
+use crate::warp::Warp;
 
+#[cfg(feature = "gdal")]
+use gdal::spatial_ref::SpatialRef;
+#[cfg(feature = "gdal")]
 use gdal::Dataset;
 use polars::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::{Path, PathBuf};
+#[cfg(feature = "gdal")]
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Instant;
 
 /// Type alias for station IDs and their coordinates
 type StationCoordinates = (Vec<String>, Vec<(f64, f64)>);
 
+/// How classification treats codes outside the 17 standard LCZ classes
+///
+/// Codes are checked after the code mapping and manual overrides are applied.
+/// Values mapped to nodata are never considered invalid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationMode {
+    /// Report unexpected codes as `Lcz::Unknown` (categorised as Rural unless
+    /// the `CategoryScheme` says otherwise)
+    #[default]
+    Lenient,
+    /// As `Lenient`, but add an `lcz_valid` boolean column to the output
+    Flag,
+    /// Fail with `ClassifierError::UnexpectedRasterValue` on the first unexpected code
+    Strict,
+}
+
 /// Main classifier struct that holds the WUDAPT GeoTIFF dataset
+///
+/// The classifier is `Send + Sync` and can be shared between threads, e.g.
+/// behind an `Arc` in web server state. Concurrent calls each use their own
+/// GDAL dataset handle, opened on demand from the same file.
 pub struct UrbanClassifier {
-    dataset: Dataset,
+    source: Arc<dyn RasterSource>,
+    metadata: RasterMetadata,
+    #[cfg(feature = "gdal")]
+    datasets: Option<Arc<DatasetPool>>,
+    code_mapping: CodeMapping,
+    category_scheme: CategoryScheme,
+    validation_mode: ValidationMode,
+    preloaded: Option<PreloadedBand>,
+    tile_cache: Option<TileCache>,
+    #[cfg(feature = "gdal")]
+    overview_min_radius: Option<usize>,
+    #[cfg(feature = "gdal")]
+    lcz_band: isize,
+    nodata_override: Option<u8>,
+    majority_filter: Option<MajorityFilter>,
+    raw_value_column: bool,
 }
 
 impl UrbanClassifier {
@@ -72,6 +144,7 @@ impl UrbanClassifier {
     /// let classifier = UrbanClassifier::from_default_data()?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
+    #[cfg(feature = "gdal")]
     pub fn from_default_data() -> Result<Self> {
         let default_path = Self::default_data_path();
         Self::new(default_path)
@@ -80,7 +153,8 @@ impl UrbanClassifier {
     /// Create a new UrbanClassifier from a WUDAPT GeoTIFF file
     ///
     /// # Arguments
-    /// * `wudapt_geotiff_path` - Path to the WUDAPT LCZ GeoTIFF file
+    /// * `wudapt_geotiff_path` - Path to the WUDAPT LCZ GeoTIFF file, or an
+    ///   `https://`, `s3://` or `gs://` URL of a cloud-optimised GeoTIFF
     ///
     /// # Returns
     /// A new UrbanClassifier instance or an error if the file cannot be opened
@@ -91,33 +165,286 @@ impl UrbanClassifier {
     ///
     /// // Load from custom path
     /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    ///
+    /// // Read a hosted COG over HTTP, fetching only the blocks that are sampled
+    /// let remote = UrbanClassifier::new("https://example.org/wudapt_lcz_global.tif")?;
     /// # Ok::<(), Box<dyn std::error::Error>>(())
     /// ```
+    #[cfg(feature = "gdal")]
     pub fn new<P: AsRef<Path>>(wudapt_geotiff_path: P) -> Result<Self> {
-        let path = wudapt_geotiff_path.as_ref();
+        Self::open_with(wudapt_geotiff_path, &OpenOptions::default())
+    }
 
-        // Check if file exists
-        if !path.exists() {
+    /// Create a new UrbanClassifier with GDAL open options and a chosen LCZ band
+    ///
+    /// # Arguments
+    /// * `wudapt_geotiff_path` - Path or URL of the LCZ raster, as for `new()`
+    /// * `options` - Driver open options and the band holding the LCZ classes
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::GdalError` if the file cannot be opened with
+    /// the given options or the selected band does not exist.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::open::OpenOptions;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let options = OpenOptions::new()
+    ///     .with_open_option("NUM_THREADS", "ALL_CPUS")
+    ///     .with_band(2);
+    /// let classifier = UrbanClassifier::open_with("path/to/lcz.tif", &options)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn open_with<P: AsRef<Path>>(
+        wudapt_geotiff_path: P,
+        options: &OpenOptions,
+    ) -> Result<Self> {
+        let remote_path = wudapt_geotiff_path
+            .as_ref()
+            .to_str()
+            .and_then(to_gdal_path)
+            .map(PathBuf::from);
+        let path = remote_path
+            .as_deref()
+            .unwrap_or(wudapt_geotiff_path.as_ref());
+
+        // Check if file exists (remote files are checked when GDAL opens them)
+        if remote_path.is_none() && !path.exists() {
             return Err(ClassifierError::FileNotFound {
                 path: path.to_string_lossy().to_string(),
             });
         }
 
         // Open the dataset with GDAL
-        let dataset = Dataset::open(path)?;
+        let warp = match options.warp_crs() {
+            Some(crs) => Some(Warp::new(crs, options.assumed_crs())?),
+            None => None,
+        };
+        let datasets = Arc::new(DatasetPool::open(
+            path,
+            options.gdal_options().to_vec(),
+            warp,
+        )?);
+        Self::from_pool(datasets, options)
+    }
 
-        // Validate that we have at least one raster band
-        if dataset.raster_count() == 0 {
-            return Err(ClassifierError::GdalError {
-                message: "GeoTIFF file contains no raster bands".to_string(),
-            });
-        }
+    /// Create a classifier from a GDAL dataset opened by the caller
+    ///
+    /// Use this for datasets that `new` cannot open by name, such as warped
+    /// VRTs built in code, or when the dataset is already open. The LCZ classes
+    /// are read from band 1.
+    ///
+    /// Concurrent operations reopen the dataset from its description, which
+    /// works for files, VSI paths and subdatasets. Datasets that cannot be
+    /// reopened, such as in-memory ones, serve one operation at a time.
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::GdalError` if the dataset has no raster bands
+    /// or no usable geotransform or spatial reference.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use gdal::Dataset;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let dataset = Dataset::open("NETCDF:\"lcz.nc\":lcz")?;
+    /// let classifier = UrbanClassifier::from_dataset(dataset)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn from_dataset(dataset: Dataset) -> Result<Self> {
+        let datasets = Arc::new(DatasetPool::from_dataset(dataset));
+        Self::from_pool(datasets, &OpenOptions::default())
+    }
+
+    /// Create a classifier reading the LCZ band selected by `options` from pooled datasets
+    #[cfg(feature = "gdal")]
+    fn from_pool(datasets: Arc<DatasetPool>, options: &OpenOptions) -> Result<Self> {
+        // Validate that we have at least one raster band and find the LCZ band
+        let band_index = {
+            let dataset = datasets.get()?;
+            if dataset.raster_count() == 0 {
+                return Err(ClassifierError::GdalError {
+                    message: "GeoTIFF file contains no raster bands".to_string(),
+                    source: None,
+                });
+            }
+            resolve_band(&dataset, options.band())?
+        };
+
+        let source = GdalSource::new(
+            Arc::clone(&datasets),
+            band_index,
+            options.assumed_crs().cloned(),
+        );
+
+        let mut classifier = Self::from_source(source)?;
+        classifier.datasets = Some(datasets);
+        classifier.lcz_band = band_index;
+        Ok(classifier)
+    }
+
+    /// Create a classifier reading LCZ values from a custom raster source
+    ///
+    /// Classification, stability scoring, buffer composition and station
+    /// validation work with any source. Operations that need GDAL-specific
+    /// capabilities return `ClassifierError::GdalError` for custom sources.
+    ///
+    /// # Errors
+    /// Returns an error if the source's metadata cannot be read or its
+    /// geotransform is invalid.
+    pub fn from_source<S: RasterSource + 'static>(source: S) -> Result<Self> {
+        let metadata = source.metadata()?;
 
         // Validate the geotransform
-        let geo_transform = dataset.geo_transform()?;
-        validate_geo_transform(&geo_transform)?;
+        validate_geo_transform(&metadata.geo_transform)?;
+
+        Ok(UrbanClassifier {
+            source: Arc::new(source),
+            metadata,
+            #[cfg(feature = "gdal")]
+            datasets: None,
+            code_mapping: CodeMapping::default(),
+            category_scheme: CategoryScheme::default(),
+            validation_mode: ValidationMode::default(),
+            preloaded: None,
+            tile_cache: None,
+            #[cfg(feature = "gdal")]
+            overview_min_radius: None,
+            #[cfg(feature = "gdal")]
+            lcz_band: 1,
+            nodata_override: None,
+            majority_filter: None,
+            raw_value_column: false,
+        })
+    }
+
+    /// Set the mapping applied to raw raster values before LCZ lookup
+    ///
+    /// Use this for LCZ products that do not follow the WUDAPT 1-17 encoding,
+    /// e.g. natural classes stored as 101-107 or 0 used as nodata.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::mapping::CodeMapping;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/lcz_generator.tif")?
+    ///     .with_code_mapping(CodeMapping::natural_101_107().with_nodata(0));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_code_mapping(mut self, mapping: CodeMapping) -> Self {
+        self.code_mapping = mapping;
+        self
+    }
+
+    /// Get the mapping applied to raw raster values
+    pub fn code_mapping(&self) -> &CodeMapping {
+        &self.code_mapping
+    }
+
+    /// Set the grouping of LCZ classes used for the `simple_class` column
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::category::CategoryScheme;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// // Treat sparsely built areas (LCZ 9) as rural
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?
+    ///     .with_category_scheme(CategoryScheme::sparsely_built_rural());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_category_scheme(mut self, scheme: CategoryScheme) -> Self {
+        self.category_scheme = scheme;
+        self
+    }
+
+    /// Get the grouping of LCZ classes used for the `simple_class` column
+    pub fn category_scheme(&self) -> &CategoryScheme {
+        &self.category_scheme
+    }
 
-        Ok(UrbanClassifier { dataset })
+    /// Treat raw raster values equal to `value` as nodata
+    ///
+    /// By default the nodata value of the LCZ band is read from the raster's
+    /// metadata. Use this for rasters whose nodata metadata is missing or
+    /// wrong; the override replaces the metadata value. Matching samples
+    /// produce null output columns instead of `Lcz::Unknown`, and are left out
+    /// of buffer compositions. Nodata is checked before the code mapping.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/lcz.tif")?.with_nodata_override(255);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_nodata_override(mut self, value: u8) -> Self {
+        self.nodata_override = Some(value);
+        self
+    }
+
+    /// Get the raw value treated as nodata, if overridden
+    pub fn nodata_override(&self) -> Option<u8> {
+        self.nodata_override
+    }
+
+    /// Get the raw value treated as nodata: the override if set, otherwise
+    /// the nodata value from the raster's metadata
+    pub fn nodata_value(&self) -> Option<u8> {
+        self.nodata_override.or(self.metadata.nodata)
+    }
+
+    /// Add a `raw_value` column with each station's unmapped pixel value
+    ///
+    /// The column holds the value read from the raster before the nodata
+    /// override, code mapping and manual overrides, so it can be compared with
+    /// `lcz_code` to audit how they changed the data. It is null only for
+    /// stations that could not be sampled.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::mapping::CodeMapping;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/lcz_generator.tif")?
+    ///     .with_code_mapping(CodeMapping::natural_101_107())
+    ///     .with_raw_value_column(true);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_raw_value_column(mut self, enabled: bool) -> Self {
+        self.raw_value_column = enabled;
+        self
+    }
+
+    /// Set how codes outside the 17 standard LCZ classes are handled
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::classifier::ValidationMode;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// // Fail instead of silently classifying unexpected values as Rural
+    /// let classifier =
+    ///     UrbanClassifier::new("path/to/wudapt.tif")?.with_validation_mode(ValidationMode::Strict);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_validation_mode(mut self, mode: ValidationMode) -> Self {
+        self.validation_mode = mode;
+        self
+    }
+
+    /// Get the current validation mode
+    pub fn validation_mode(&self) -> ValidationMode {
+        self.validation_mode
     }
 
     /// Run LCZ classification on a DataFrame of station locations
@@ -133,7 +460,16 @@ impl UrbanClassifier {
     /// Enhanced DataFrame with additional columns:
     /// - `lcz_code`: Numeric LCZ code (1-17, or 0 for unknown)
     /// - `lcz_name`: Human-readable LCZ name
-    /// - `simple_class`: Simplified category (Urban/Suburban/Rural)
+    /// - `simple_class`: Simplified category (Urban/Suburban/Rural) under the category scheme
+    ///
+    /// All three columns are null for stations whose raster value the code
+    /// mapping treats as nodata, unless an override applies. With
+    /// `ValidationMode::Flag`, an `lcz_valid` column is added as well, and
+    /// `with_raw_value_column` adds the unmapped `raw_value`.
+    ///
+    /// # Errors
+    /// With `ValidationMode::Strict`, returns `UnexpectedRasterValue` for a
+    /// sampled code outside 1-17, or `OverrideApplication` for such an override.
     pub fn run_classification(
         &self,
         stations_df: &DataFrame,
@@ -142,66 +478,641 @@ impl UrbanClassifier {
         lat_col: &str,
         overrides: Option<&HashMap<String, u8>>,
     ) -> Result<DataFrame> {
-        // 1. Validate DataFrame schema
-        self.validate_dataframe_schema(stations_df, station_id_col, lon_col, lat_col)?;
+        let request = ClassificationRequest::new()
+            .with_station_id_column(station_id_col)
+            .with_longitude_column(lon_col)
+            .with_latitude_column(lat_col)
+            .without_swap_detection();
+        let request = match overrides {
+            Some(overrides) => request.with_overrides(overrides),
+            None => request,
+        };
+        self.classify_with(stations_df, &request)
+    }
 
-        // 2. Get spatial reference and create coordinate transform
-        let raster_srs = self.dataset.spatial_ref()?;
-        let transform = create_wgs84_to_raster_transform(&raster_srs)?;
+    /// Run LCZ classification and report per-stage timings and raster access counts
+    ///
+    /// Takes the same arguments and returns the same DataFrame as
+    /// `run_classification`, together with the run's `ClassificationMetrics`.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?.with_tile_cache(256);
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.12, -0.45],
+    ///     "latitude" => [51.5, 51.47],
+    /// }?;
+    /// let (_, metrics) = classifier.run_classification_with_metrics(
+    ///     &stations, "station_id", "longitude", "latitude", None,
+    /// )?;
+    /// println!("{:?} sampling, {} raster reads", metrics.sampling, metrics.raster_reads);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn run_classification_with_metrics(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+    ) -> Result<(DataFrame, ClassificationMetrics)> {
+        self.classify_core(
+            stations_df,
+            station_id_col,
+            lon_col,
+            lat_col,
+            overrides,
+            FailureMode::FailFast,
+            OutputColumns::All,
+            self.majority_filter(),
+            &self.category_scheme,
+        )
+        .map(|(result_df, metrics, _)| (result_df, metrics))
+    }
 
-        // 3. Get geotransform and raster band
-        let geo_transform = self.dataset.geo_transform()?;
-        let band = self.dataset.rasterband(1)?;
+    /// Run LCZ classification, choosing whether failing stations abort the run
+    ///
+    /// With `FailureMode::FailFast` this behaves like `run_classification` and
+    /// the returned report is always empty. With `FailureMode::CollectAll`
+    /// every station that cannot be classified is recorded in the report and
+    /// its LCZ columns are null; all other stations are classified as usual.
+    ///
+    /// # Errors
+    /// Errors that affect the whole run, such as missing columns or null
+    /// coordinates, are returned regardless of the mode.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::failures::FailureMode;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.12, 540.0],
+    ///     "latitude" => [51.5, 51.47],
+    /// }?;
+    /// let (result, errors) = classifier.run_classification_with_failures(
+    ///     &stations, "station_id", "longitude", "latitude", None, FailureMode::CollectAll,
+    /// )?;
+    /// for error in &errors {
+    ///     eprintln!("{}", error);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn run_classification_with_failures(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        mode: FailureMode,
+    ) -> Result<(DataFrame, ClassificationErrors)> {
+        self.classify_core(
+            stations_df,
+            station_id_col,
+            lon_col,
+            lat_col,
+            overrides,
+            mode,
+            OutputColumns::All,
+            self.majority_filter(),
+            &self.category_scheme,
+        )
+        .map(|(result_df, _, errors)| (result_df, errors))
+    }
 
-        // 4. Extract coordinates and station IDs
+    /// Shared implementation of the `run_classification*` entry points
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "run_classification",
+            level = "debug",
+            skip_all,
+            fields(stations = stations_df.height())
+        )
+    )]
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn classify_core(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        mode: FailureMode,
+        output: OutputColumns,
+        majority_filter: Option<&MajorityFilter>,
+        category_scheme: &CategoryScheme,
+    ) -> Result<(DataFrame, ClassificationMetrics, ClassificationErrors)> {
+        let run_started = Instant::now();
+        let mut metrics = ClassificationMetrics::default();
+
+        // 1. Validate DataFrame schema
+        let stage = stage_span!("validation");
+        let started = Instant::now();
+        self.validate_dataframe_schema(stations_df, station_id_col, lon_col, lat_col)?;
+
+        // 2. Extract coordinates and station IDs
         let (station_ids, coordinates) =
             self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+        metrics.validation = started.elapsed();
+        drop(stage);
+
+        // 3. Prepare the transform from WGS84 to the raster's pixel grid
+        let stage = stage_span!("transform");
+        let started = Instant::now();
+        let locator = PixelLocator::new(&self.metadata)?;
+        metrics.transform = started.elapsed();
+        drop(stage);
 
-        // 5. Transform coordinates and sample raster
+        // 4-5. Transform coordinates and sample raster
+        let stage = stage_span!("sampling");
+        let mut counts = SampleCounts::default();
+        let mut failures = FailureSink::new(mode);
         let mut lcz_codes = Vec::with_capacity(coordinates.len());
+        let mut raw_values = Vec::with_capacity(coordinates.len());
+        // WGS84 extent of the raster, computed for the first station outside it
+        let mut raster_extent = None;
 
         for (i, (lon, lat)) in coordinates.iter().enumerate() {
-            // Transform coordinate
-            let (x, y) = transform_coordinate(*lon, *lat, &transform)?;
+            // Transform coordinate and convert to pixel coordinates
+            let started = Instant::now();
+            let located = locator.locate(*lon, *lat);
+            metrics.transform += started.elapsed();
+            let (pixel, line) = match located {
+                Ok(location) => location,
+                Err(e) => {
+                    failures.record(&station_ids[i], i, e)?;
+                    lcz_codes.push(None);
+                    raw_values.push(None);
+                    continue;
+                }
+            };
+            trace_event!(station = %station_ids[i], lon, lat, pixel, line, "located station");
 
-            // Convert to pixel coordinates
-            let (pixel, line) = geo_to_pixel(x, y, &geo_transform);
+            let (width, height) = (self.metadata.width as isize, self.metadata.height as isize);
+            if !(0..width).contains(&pixel) || !(0..height).contains(&line) {
+                let cause =
+                    self.out_of_bounds_error((*lon, *lat), (pixel, line), &mut raster_extent)?;
+                failures.record(&station_ids[i], i, cause)?;
+                lcz_codes.push(None);
+                raw_values.push(None);
+                continue;
+            }
 
-            // Sample raster value
-            match sample_raster_value(&band, pixel, line) {
-                Ok(code) => lcz_codes.push(code),
-                Err(e) => {
-                    return Err(ClassifierError::RasterSampling {
+            // Sample raster value and map it to an LCZ code
+            let started = Instant::now();
+            let sampled = self.sample_raw_counted(pixel, line, &mut counts);
+            metrics.sampling += started.elapsed();
+            match sampled {
+                Ok(raw) => {
+                    lcz_codes.push(self.smoothed_code(
+                        majority_filter,
                         pixel,
                         line,
-                        message: format!(
-                            "Failed to sample raster for station {}: {}",
-                            station_ids[i], e
-                        ),
-                    });
+                        self.map_raw(raw),
+                    )?);
+                    raw_values.push(Some(u32::from(raw)));
+                }
+                Err(e) => {
+                    let cause = match e {
+                        ClassifierError::UnexpectedRasterValue { .. } => e,
+                        e => ClassifierError::RasterSampling {
+                            pixel,
+                            line,
+                            message: format!("Failed to sample raster: {}", e),
+                            station_id: None,
+                            row_index: None,
+                        },
+                    };
+                    failures.record(&station_ids[i], i, cause)?;
+                    lcz_codes.push(None);
+                    raw_values.push(None);
                 }
             }
         }
 
+        debug_event!(
+            stations = lcz_codes.len(),
+            nodata = lcz_codes.iter().filter(|code| code.is_none()).count(),
+            raster_reads = counts.raster_reads,
+            cache_hits = counts.cache_hits,
+            preloaded = counts.preloaded,
+            "sampled stations"
+        );
+        drop(stage);
+
+        let started = Instant::now();
+
         // 6. Apply manual overrides if provided
         if let Some(overrides_map) = overrides {
-            self.apply_overrides(&mut lcz_codes, &station_ids, overrides_map)?;
+            debug_event!(overrides = overrides_map.len(), "applying overrides");
+            self.apply_overrides(&mut lcz_codes, &station_ids, overrides_map, &mut failures)?;
         }
 
-        // 7. Create result columns
-        let lcz_series = self.create_lcz_columns(&lcz_codes)?;
+        // 7. Check codes against the validation mode and create result columns
+        let _stage = stage_span!("columns");
+        let validity = self.check_codes(&mut lcz_codes, &station_ids, &mut failures)?;
+        let mut lcz_series = self.create_lcz_columns(&lcz_codes, category_scheme)?;
+        if self.validation_mode == ValidationMode::Flag {
+            lcz_series.push(Series::new("lcz_valid", validity));
+        }
+        if self.raw_value_column {
+            lcz_series.push(Series::new("raw_value", raw_values));
+        }
 
-        // 8. Return enhanced DataFrame
-        let mut result_df = stations_df.clone();
-        for series in lcz_series {
-            result_df = result_df.with_column(series)?.clone();
+        // 8. Return enhanced DataFrame, or the LCZ columns keyed by station ID.
+        // Input columns are shared with `stations_df`, not copied; the new
+        // columns are attached in place, replacing any of the same name left
+        // by an earlier run.
+        let mut result_df = match output {
+            OutputColumns::All => stations_df.clone(),
+            OutputColumns::LczOnly => stations_df.select([station_id_col])?,
+        };
+        let (replaced, appended): (Vec<Series>, Vec<Series>) = lcz_series
+            .into_iter()
+            .partition(|series| result_df.get_column_index(series.name()).is_some());
+        for series in replaced {
+            let name = series.name().to_string();
+            result_df.replace(&name, series)?;
         }
+        result_df.hstack_mut(&appended)?;
+        metrics.columns = started.elapsed();
 
-        Ok(result_df)
+        metrics.stations = coordinates.len();
+        metrics.raster_reads = counts.raster_reads;
+        metrics.cache_hits = counts.cache_hits;
+        metrics.preloaded_samples = counts.preloaded;
+        metrics.total = run_started.elapsed();
+
+        Ok((result_df, metrics, failures.finish()))
+    }
+
+    /// Run LCZ classification and add the class composition of a buffer around each station
+    ///
+    /// # Arguments
+    /// * `stations_df` - DataFrame containing station data
+    /// * `station_id_col` - Name of the column containing station IDs
+    /// * `lon_col` - Name of the column containing longitude values
+    /// * `lat_col` - Name of the column containing latitude values
+    /// * `overrides` - Optional map of station IDs to manual LCZ codes
+    /// * `radius_m` - Buffer radius around each station in metres
+    ///
+    /// # Returns
+    /// The `run_classification` output with 17 additional columns `lcz_1_pct` to
+    /// `lcz_17_pct` holding the percentage of valid pixels of each class within
+    /// the buffer. Overrides only affect `lcz_code`, not the composition.
+    /// Large buffers are read from overviews when enabled with `with_overviews`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(stations = stations_df.height(), radius_m))
+    )]
+    pub fn run_classification_with_composition(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        radius_m: f64,
+    ) -> Result<DataFrame> {
+        check_buffer_radius(radius_m)?;
+
+        let mut classified =
+            self.run_classification(stations_df, station_id_col, lon_col, lat_col, overrides)?;
+        let (_, coordinates) =
+            self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+
+        classified.hstack_mut(&self.composition_series(&coordinates, radius_m)?)?;
+        Ok(classified)
+    }
+
+    /// Class composition columns `lcz_1_pct` to `lcz_17_pct` for each coordinate
+    pub(crate) fn composition_series(
+        &self,
+        coordinates: &[(f64, f64)],
+        radius_m: f64,
+    ) -> Result<Vec<Series>> {
+        let locator = PixelLocator::new(&self.metadata)?;
+        let geo_transform = self.metadata.geo_transform;
+        let units = self.metadata.crs.units()?;
+
+        // Overviews are a GDAL capability; other sources always use full resolution
+        #[cfg(feature = "gdal")]
+        let dataset = match (self.overview_min_radius, &self.datasets) {
+            (Some(_), Some(_)) => Some(self.dataset()?),
+            _ => None,
+        };
+        #[cfg(feature = "gdal")]
+        let band = dataset
+            .as_ref()
+            .map(|d| d.rasterband(self.lcz_band))
+            .transpose()?;
+        #[cfg(feature = "gdal")]
+        let overviews = match &band {
+            Some(band) => overview_levels(band)?,
+            None => Vec::new(),
+        };
+        #[cfg(feature = "gdal")]
+        let scales: Vec<(f64, f64)> = overviews.iter().map(|level| level.scale).collect();
+        #[cfg(feature = "gdal")]
+        debug_event!(overviews = overviews.len(), "reading buffer composition");
+
+        let _stage = stage_span!("composition");
+        let mut counts = Vec::with_capacity(coordinates.len());
+        for (row, (lon, lat)) in coordinates.iter().enumerate() {
+            let (pixel, line) = locator.locate(*lon, *lat).map_err(|e| e.with_row(row))?;
+            let (rx, ry) = buffer_radius_pixels(radius_m, *lat, &geo_transform, units);
+
+            // Large buffers: count on the coarsest overview that keeps enough pixels
+            #[cfg(feature = "gdal")]
+            if let Some(level) = self
+                .overview_min_radius
+                .and_then(|min_radius| select_overview((rx, ry), &scales, min_radius))
+                .map(|index| &overviews[index])
+            {
+                trace_event!(scale = ?level.scale, rx, ry, "buffer read from overview");
+                let (pixel, line) = level.to_overview_pixel(pixel, line);
+                let (rx, ry) = level.to_overview_radius((rx, ry));
+                let window = self.map_window(read_pixel_window(
+                    &level.band,
+                    pixel - rx as isize,
+                    line - ry as isize,
+                    2 * rx + 1,
+                    2 * ry + 1,
+                )?);
+                counts.push(class_counts_in_buffer(&window, (pixel, line), (rx, ry)));
+                continue;
+            }
+
+            let window = self.map_window(self.read_window(
+                pixel - rx as isize,
+                line - ry as isize,
+                2 * rx + 1,
+                2 * ry + 1,
+            )?);
+            counts.push(class_counts_in_buffer(&window, (pixel, line), (rx, ry)));
+        }
+
+        Ok(composition_columns(&counts))
+    }
+
+    /// Run LCZ classification and score how stable each class is under positional jitter
+    ///
+    /// Each station is re-sampled at `config.samples` positions spread within
+    /// `config.radius_m` of its nominal coordinate.
+    ///
+    /// # Arguments
+    /// * `stations_df` - DataFrame containing station data
+    /// * `station_id_col` - Name of the column containing station IDs
+    /// * `lon_col` - Name of the column containing longitude values
+    /// * `lat_col` - Name of the column containing latitude values
+    /// * `overrides` - Optional map of station IDs to manual LCZ codes
+    /// * `config` - Perturbation radius, sample count and flagging threshold
+    ///
+    /// # Returns
+    /// The `run_classification` output with two additional columns:
+    /// - `lcz_stability`: Fraction of perturbed samples agreeing with the sampled class
+    /// - `lcz_unstable`: True when the stability is below `config.min_agreement`
+    ///
+    /// Stability is computed against the sampled class, before overrides, and is
+    /// null for stations whose sampled value is mapped to nodata. With a
+    /// majority filter, every sample is smoothed as the `lcz_code` column is.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(stations = stations_df.height(), samples = config.samples))
+    )]
+    pub fn run_classification_with_stability(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<&HashMap<String, u8>>,
+        config: &StabilityConfig,
+    ) -> Result<DataFrame> {
+        check_stability_config(config)?;
+
+        let mut classified =
+            self.run_classification(stations_df, station_id_col, lon_col, lat_col, overrides)?;
+        let (_, coordinates) =
+            self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+
+        classified.hstack_mut(&self.stability_series(
+            &coordinates,
+            config,
+            self.majority_filter(),
+        )?)?;
+        Ok(classified)
+    }
+
+    /// Stability columns `lcz_stability` and `lcz_unstable` for each coordinate
+    ///
+    /// The nominal and jittered samples are smoothed with `majority_filter`,
+    /// as the `lcz_code` column is.
+    pub(crate) fn stability_series(
+        &self,
+        coordinates: &[(f64, f64)],
+        config: &StabilityConfig,
+        majority_filter: Option<&MajorityFilter>,
+    ) -> Result<Vec<Series>> {
+        let _stage = stage_span!("stability");
+        let locator = PixelLocator::new(&self.metadata)?;
+        let offsets = jitter_offsets(config.samples, config.radius_m);
+        let sample = |pixel: isize, line: isize| {
+            self.sample_code(pixel, line)
+                .and_then(|code| self.smoothed_code(majority_filter, pixel, line, code))
+        };
+
+        let mut stability = Vec::with_capacity(coordinates.len());
+        let mut unstable = Vec::with_capacity(coordinates.len());
+
+        for (row, (lon, lat)) in coordinates.iter().enumerate() {
+            let (pixel, line) = locator.locate(*lon, *lat).map_err(|e| e.with_row(row))?;
+            let nominal = sample(pixel, line).map_err(|e| e.with_row(row))?;
+
+            let samples: Vec<Option<u8>> = offsets
+                .iter()
+                .map(|(east, north)| {
+                    let (jlon, jlat) = offset_lonlat(*lon, *lat, *east, *north);
+                    let (pixel, line) = locator.locate(jlon, jlat).ok()?;
+                    sample(pixel, line).ok().flatten()
+                })
+                .collect();
+
+            let fraction = nominal.and_then(|code| agreement_fraction(code, &samples));
+            stability.push(fraction);
+            unstable.push(fraction.map(|f| f < config.min_agreement));
+        }
+
+        Ok(vec![
+            Series::new("lcz_stability", stability),
+            Series::new("lcz_unstable", unstable),
+        ])
+    }
+
+    /// Sample a pixel and translate it through the code mapping
+    ///
+    /// Returns `None` when the raw value is mapped to nodata.
+    pub(crate) fn sample_code(&self, pixel: isize, line: isize) -> Result<Option<u8>> {
+        self.sample_raw_counted(pixel, line, &mut SampleCounts::default())
+            .map(|raw| self.map_raw(raw))
+    }
+
+    /// Sample a raw pixel value, recording where it was served from
+    fn sample_raw_counted(
+        &self,
+        pixel: isize,
+        line: isize,
+        counts: &mut SampleCounts,
+    ) -> Result<u8> {
+        let mut raw = self.preloaded.as_ref().and_then(|p| p.get(pixel, line));
+        if raw.is_some() {
+            counts.preloaded += 1;
+        } else if let Some(cache) = &self.tile_cache {
+            raw = cache.sample(self.source.as_ref(), &self.metadata, pixel, line, counts)?;
+        }
+        match raw {
+            Some(raw) => Ok(raw),
+            None => {
+                counts.raster_reads += 1;
+                self.source.sample(pixel, line)
+            }
+        }
+    }
+
+    /// Translate a raw raster value to an LCZ code, returning `None` for nodata
+    pub(crate) fn map_raw(&self, raw: u8) -> Option<u8> {
+        if self.nodata_value() == Some(raw) {
+            return None;
+        }
+        self.code_mapping.apply(raw)
+    }
+
+    /// Read a window of the LCZ band, from memory when it has been preloaded
+    ///
+    /// Same semantics as `read_pixel_window`: the window is clipped to the raster.
+    pub(crate) fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        let preloaded = self
+            .preloaded
+            .as_ref()
+            .and_then(|p| p.window(pixel, line, width, height));
+        match preloaded {
+            Some(window) => Ok(window),
+            None => self.source.read_window(pixel, line, width, height),
+        }
+    }
+
+    /// Replace the in-memory copy of the LCZ band
+    pub(crate) fn set_preloaded(&mut self, preloaded: Option<PreloadedBand>) {
+        self.preloaded = preloaded;
+    }
+
+    /// Replace the majority filter applied before station lookup
+    pub(crate) fn set_majority_filter(&mut self, filter: Option<MajorityFilter>) {
+        self.majority_filter = filter;
+    }
+
+    /// Get the majority filter applied before station lookup, if any
+    pub fn majority_filter(&self) -> Option<&MajorityFilter> {
+        self.majority_filter.as_ref()
+    }
+
+    /// Replace the cache of decoded LCZ band blocks
+    pub(crate) fn set_tile_cache(&mut self, cache: Option<TileCache>) {
+        self.tile_cache = cache;
+    }
+
+    /// Set the minimum overview radius for buffer statistics (`None` disables overviews)
+    #[cfg(feature = "gdal")]
+    pub(crate) fn set_overview_min_radius(&mut self, min_radius_pixels: Option<usize>) {
+        self.overview_min_radius = min_radius_pixels;
+    }
+
+    /// Access the cache of decoded LCZ band blocks, if configured
+    pub(crate) fn tile_cache(&self) -> Option<&TileCache> {
+        self.tile_cache.as_ref()
+    }
+
+    /// Translate every value of a raster window through the nodata override and code mapping
+    ///
+    /// Nodata values become 0, which is not an LCZ class and is therefore
+    /// excluded from class counts.
+    pub(crate) fn map_window(&self, mut window: PixelWindow) -> PixelWindow {
+        if !self.code_mapping.is_identity() || self.nodata_value().is_some() {
+            for value in window.data.iter_mut() {
+                *value = self.map_raw(*value).unwrap_or(0);
+            }
+        }
+        window
+    }
+
+    /// Read a window directly from the raster source, bypassing any preload
+    pub(crate) fn read_source_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        self.source.read_window(pixel, line, width, height)
+    }
+
+    /// Size and georeferencing of the raster source
+    pub(crate) fn source_metadata(&self) -> &RasterMetadata {
+        &self.metadata
+    }
+
+    /// GDAL spatial reference of the raster, including an assumed CRS
+    #[cfg(feature = "gdal")]
+    pub(crate) fn raster_spatial_ref(&self) -> Result<SpatialRef> {
+        self.metadata.crs.to_spatial_ref()
+    }
+
+    /// 1-based index of the GDAL band holding the LCZ classes
+    #[cfg(feature = "gdal")]
+    pub(crate) fn lcz_band(&self) -> isize {
+        self.lcz_band
+    }
+
+    /// Check out a GDAL dataset for the duration of an operation
+    ///
+    /// Bind the returned handle to a local before borrowing bands from it.
+    /// Fails for classifiers created from a custom raster source.
+    #[cfg(feature = "gdal")]
+    pub(crate) fn dataset(&self) -> Result<PooledDataset<'_>> {
+        self.datasets
+            .as_ref()
+            .ok_or_else(|| ClassifierError::GdalError {
+                message: "This operation requires a raster opened through GDAL".to_string(),
+                source: None,
+            })?
+            .get()
+    }
+
+    /// Path the GDAL dataset was opened from, if opened through GDAL
+    #[cfg(feature = "gdal")]
+    pub(crate) fn dataset_path(&self) -> Option<&Path> {
+        self.datasets.as_deref().map(DatasetPool::path)
     }
 
     /// Validate that the input DataFrame has required columns with correct types
-    fn validate_dataframe_schema(
+    pub(crate) fn validate_dataframe_schema(
         &self,
         df: &DataFrame,
         station_id_col: &str,
@@ -212,21 +1123,15 @@ impl UrbanClassifier {
 
         // Check if required columns exist
         if !columns.contains(&station_id_col) {
-            return Err(ClassifierError::ColumnNotFound {
-                column: station_id_col.to_string(),
-            });
+            return Err(column_not_found(df, station_id_col));
         }
 
         if !columns.contains(&lon_col) {
-            return Err(ClassifierError::ColumnNotFound {
-                column: lon_col.to_string(),
-            });
+            return Err(column_not_found(df, lon_col));
         }
 
         if !columns.contains(&lat_col) {
-            return Err(ClassifierError::ColumnNotFound {
-                column: lat_col.to_string(),
-            });
+            return Err(column_not_found(df, lat_col));
         }
 
         // Validate that longitude and latitude columns contain numeric data
@@ -249,7 +1154,7 @@ impl UrbanClassifier {
     }
 
     /// Extract station IDs and coordinates from the DataFrame
-    fn extract_coordinates(
+    pub(crate) fn extract_coordinates(
         &self,
         df: &DataFrame,
         station_id_col: &str,
@@ -303,37 +1208,95 @@ impl UrbanClassifier {
     }
 
     /// Apply manual LCZ code overrides for specific stations
+    ///
+    /// In strict mode an invalid override is recorded as a failure of its
+    /// station, whose code becomes null.
     fn apply_overrides(
         &self,
-        lcz_codes: &mut [u8],
+        lcz_codes: &mut [Option<u8>],
         station_ids: &[String],
         overrides: &HashMap<String, u8>,
+        failures: &mut FailureSink,
     ) -> Result<()> {
         for (i, station_id) in station_ids.iter().enumerate() {
             if let Some(&override_code) = overrides.get(station_id) {
-                lcz_codes[i] = override_code;
+                if self.validation_mode == ValidationMode::Strict
+                    && !Lcz::from_code(override_code).is_standard()
+                {
+                    let cause = ClassifierError::OverrideApplication {
+                        station_id: station_id.clone(),
+                        message: format!("{} is not an LCZ code 1-17", override_code),
+                    };
+                    failures.record(station_id, i, cause)?;
+                    lcz_codes[i] = None;
+                    continue;
+                }
+                lcz_codes[i] = Some(override_code);
             }
         }
         Ok(())
     }
 
+    /// Check whether each code is a standard LCZ class (null for nodata)
+    ///
+    /// In strict mode each unexpected code is recorded as a failure of its
+    /// station, whose code becomes null.
+    fn check_codes(
+        &self,
+        lcz_codes: &mut [Option<u8>],
+        station_ids: &[String],
+        failures: &mut FailureSink,
+    ) -> Result<Vec<Option<bool>>> {
+        let mut validity = Vec::with_capacity(lcz_codes.len());
+        for (i, (code, station_id)) in lcz_codes.iter_mut().zip(station_ids).enumerate() {
+            let Some(value) = *code else {
+                validity.push(None);
+                continue;
+            };
+            let valid = Lcz::from_code(value).is_standard();
+            if !valid && self.validation_mode == ValidationMode::Strict {
+                let cause = ClassifierError::UnexpectedRasterValue {
+                    value: f64::from(value),
+                    station_id: None,
+                    row_index: None,
+                };
+                failures.record(station_id, i, cause)?;
+                *code = None;
+                validity.push(None);
+                continue;
+            }
+            validity.push(Some(valid));
+        }
+        Ok(validity)
+    }
+
     /// Create the three output columns: lcz_code, lcz_name, and simple_class
-    fn create_lcz_columns(&self, lcz_codes: &[u8]) -> Result<Vec<Series>> {
+    fn create_lcz_columns(
+        &self,
+        lcz_codes: &[Option<u8>],
+        category_scheme: &CategoryScheme,
+    ) -> Result<Vec<Series>> {
         // Create lcz_code column - convert u8 to u32 for better Polars compatibility
-        let lcz_codes_u32: Vec<u32> = lcz_codes.iter().map(|&x| x as u32).collect();
+        let lcz_codes_u32: Vec<Option<u32>> = lcz_codes.iter().map(|x| x.map(u32::from)).collect();
         let lcz_code_series = Series::new("lcz_code", lcz_codes_u32);
 
         // Create lcz_name column
-        let lcz_names: Vec<String> = lcz_codes
+        let lcz_names: Vec<Option<String>> = lcz_codes
             .iter()
-            .map(|&code| Lcz::from_code(code).full_name().to_string())
+            .map(|code| code.map(|c| Lcz::from_code(c).full_name().to_string()))
             .collect();
         let lcz_name_series = Series::new("lcz_name", lcz_names);
 
         // Create simple_class column
-        let simple_classes: Vec<String> = lcz_codes
+        let simple_classes: Vec<Option<String>> = lcz_codes
             .iter()
-            .map(|&code| Lcz::from_code(code).simple_category().as_ref().to_string())
+            .map(|code| {
+                let category = match code {
+                    Some(c) => Some(category_scheme.category(Lcz::from_code(*c))),
+                    None => category_scheme.nodata_category(),
+                };
+                category.map(|category| category.as_ref().to_string())
+            })
             .collect();
         let simple_class_series = Series::new("simple_class", simple_classes);
 
@@ -341,6 +1304,45 @@ impl UrbanClassifier {
     }
 }
 
+/// Reject buffer radii that are not a non-negative distance
+pub(crate) fn check_buffer_radius(radius_m: f64) -> Result<()> {
+    if !radius_m.is_finite() || radius_m < 0.0 {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "Buffer radius must be a non-negative distance, got {}",
+                radius_m
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Reject stability configurations with an invalid radius or no samples
+pub(crate) fn check_stability_config(config: &StabilityConfig) -> Result<()> {
+    if !config.radius_m.is_finite() || config.radius_m < 0.0 {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "Jitter radius must be a non-negative distance, got {}",
+                config.radius_m
+            ),
+        });
+    }
+    if config.samples == 0 {
+        return Err(ClassifierError::InvalidParameter {
+            message: "Jitter sample count must be at least 1".to_string(),
+        });
+    }
+    if !(0.0..=1.0).contains(&config.min_agreement) {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "Minimum agreement must be a fraction between 0 and 1, got {}",
+                config.min_agreement
+            ),
+        });
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -348,6 +1350,7 @@ mod tests {
 
     /// Test that missing files are properly reported
     #[test]
+    #[cfg(feature = "gdal")]
     fn test_file_not_found() {
         let result = UrbanClassifier::new("/nonexistent/path.tif");
         assert!(matches!(result, Err(ClassifierError::FileNotFound { .. })));
@@ -384,6 +1387,7 @@ mod tests {
 
     /// Test creation of LCZ result columns
     #[test]
+    #[cfg(feature = "gdal")]
     fn test_lcz_column_creation() {
         // Create a test classifier
         let temp_file = NamedTempFile::new().unwrap();
@@ -405,6 +1409,19 @@ mod tests {
         assert_eq!(Lcz::from_code(11).simple_category().as_ref(), "Rural");
     }
 
+    /// Test that the classifier can be shared between threads
+    #[test]
+    fn test_classifier_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<UrbanClassifier>();
+    }
+
+    /// Test that validation defaults to lenient handling of unknown codes
+    #[test]
+    fn test_validation_mode_default() {
+        assert_eq!(ValidationMode::default(), ValidationMode::Lenient);
+    }
+
     /// Test default data path functionality
     #[test]
     fn test_default_data_path() {
@@ -424,6 +1441,7 @@ mod tests {
 
     /// Test from_default_data constructor (will fail without actual file)  
     #[test]
+    #[cfg(feature = "gdal")]
     fn test_from_default_data_missing_file() {
         let result = UrbanClassifier::from_default_data();
 
@@ -442,4 +1460,69 @@ mod tests {
             }
         }
     }
+
+    /// Test validation of jitter radius, sample count and agreement threshold
+    #[test]
+    fn test_check_stability_config() {
+        assert!(check_stability_config(&StabilityConfig::default()).is_ok());
+
+        let invalid = [
+            StabilityConfig {
+                radius_m: -1.0,
+                ..StabilityConfig::default()
+            },
+            StabilityConfig {
+                samples: 0,
+                ..StabilityConfig::default()
+            },
+            StabilityConfig {
+                min_agreement: 1.5,
+                ..StabilityConfig::default()
+            },
+            StabilityConfig {
+                min_agreement: f64::NAN,
+                ..StabilityConfig::default()
+            },
+        ];
+        for config in &invalid {
+            assert!(matches!(
+                check_stability_config(config),
+                Err(ClassifierError::InvalidParameter { .. })
+            ));
+        }
+    }
+
+    /// Test that cached samples of a UInt16 band match direct samples
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_cached_sample_uint16() {
+        use gdal::raster::Buffer;
+        use gdal::DriverManager;
+
+        let driver = DriverManager::get_driver_by_name("MEM").unwrap();
+        let mut dataset = driver.create_with_band_type::<u16, _>("", 3, 1, 1).unwrap();
+        dataset
+            .set_geo_transform(&[-1.5, 1.0, 0.0, 52.0, 0.0, -1.0])
+            .unwrap();
+        dataset
+            .set_spatial_ref(&SpatialRef::from_epsg(4326).unwrap())
+            .unwrap();
+        let mut band = dataset.rasterband(1).unwrap();
+        band.write((0, 0), (3, 1), &Buffer::new((3, 1), vec![5u16, 300, 1017]))
+            .unwrap();
+
+        let direct = UrbanClassifier::from_dataset(dataset).unwrap();
+        let check = |classifier: &UrbanClassifier| {
+            let mut counts = SampleCounts::default();
+            assert_eq!(classifier.sample_raw_counted(0, 0, &mut counts).unwrap(), 5);
+            for (pixel, expected) in [(1, 300.0), (2, 1017.0)] {
+                assert!(matches!(
+                    classifier.sample_raw_counted(pixel, 0, &mut counts),
+                    Err(ClassifierError::UnexpectedRasterValue { value, .. }) if value == expected
+                ));
+            }
+        };
+        check(&direct);
+        check(&direct.with_tile_cache(4));
+    }
 }
@@ -0,0 +1,584 @@
+//! Station Column Name Resolution
+//!
+//! Third-party station lists name their coordinate columns `lat`, `LAT`,
+//! `Latitude`, `lng` and so on. With aliases enabled
+//! (`ClassificationRequest::with_column_aliases`), a requested column that is
+//! not present under its exact name is looked up case-insensitively, then
+//! through the alias group it belongs to.
+//!
+//! # Alias Groups
+//!
+//! - Station ID: `station_id`, `station`, `id`, `src_id`, `site_id`, `site`
+//! - Longitude: `longitude`, `lon`, `lng`, `long`
+//! - Latitude: `latitude`, `lat`
+//!
+//! # Coordinate Detection
+//!
+//! For unfamiliar files, `detect_coordinate_columns` guesses the longitude
+//! and latitude columns from their names and value ranges
+//! (`ClassificationRequest::with_coordinate_detection`).
+//!
+//! # Swapped Coordinates
+//!
+//! `coordinates_swapped` recognises frames whose longitude column holds
+//! latitudes and vice versa. Such runs are rejected unless swap correction is
+//! enabled (`ClassificationRequest::with_swap_correction`).
+//!
+//! # String Coordinates
+//!
+//! CSV readers often load coordinates as strings. With parsing enabled
+//! (`ClassificationRequest::with_coordinate_parsing`), string coordinate
+//! columns are converted with `parse_coordinate`, which also accepts
+//! hemisphere suffixes such as `51.5074 N` or `0.1278°W`.
+//!
+//! # Missing Columns
+//!
+//! `ClassifierError::ColumnNotFound` lists the columns the DataFrame does
+//! have and, where `suggest_column` finds one, the column that was probably
+//! meant.
+
+use crate::config::ColumnNames;
+use crate::error::{ClassifierError, Result};
+use polars::prelude::*;
+
+/// Accepted names for the station ID column, in order of preference
+pub const STATION_ID_ALIASES: &[&str] =
+    &["station_id", "station", "id", "src_id", "site_id", "site"];
+
+/// Accepted names for the longitude column, in order of preference
+pub const LONGITUDE_ALIASES: &[&str] = &["longitude", "lon", "lng", "long"];
+
+/// Accepted names for the latitude column, in order of preference
+pub const LATITUDE_ALIASES: &[&str] = &["latitude", "lat"];
+
+/// Find the column of `df` matching `requested`, allowing case differences and aliases
+///
+/// An exact match always wins. Otherwise the requested name and then each
+/// entry of `aliases` (if the requested name is one of them) is compared
+/// case-insensitively; a name matching several columns is skipped as
+/// ambiguous. Returns `None` when nothing matches.
+pub fn resolve_column(df: &DataFrame, requested: &str, aliases: &[&str]) -> Option<String> {
+    let names = df.get_column_names();
+    if names.contains(&requested) {
+        return Some(requested.to_string());
+    }
+
+    let unique_match = |candidate: &str| {
+        let mut matches = names
+            .iter()
+            .filter(|name| name.eq_ignore_ascii_case(candidate));
+        match (matches.next(), matches.next()) {
+            (Some(name), None) => Some(name.to_string()),
+            _ => None,
+        }
+    };
+
+    if let Some(name) = unique_match(requested) {
+        return Some(name);
+    }
+    if aliases
+        .iter()
+        .any(|alias| alias.eq_ignore_ascii_case(requested))
+    {
+        return aliases.iter().find_map(|alias| unique_match(alias));
+    }
+    None
+}
+
+impl ColumnNames {
+    /// Resolve each column name against `df` through its alias group
+    ///
+    /// Names that cannot be resolved are kept as given, so the usual missing
+    /// column error names what was requested.
+    pub fn resolve_aliases(&self, df: &DataFrame) -> ColumnNames {
+        let resolve = |requested: &str, aliases: &[&str]| {
+            resolve_column(df, requested, aliases).unwrap_or_else(|| requested.to_string())
+        };
+        ColumnNames {
+            station_id: resolve(&self.station_id, STATION_ID_ALIASES),
+            longitude: resolve(&self.longitude, LONGITUDE_ALIASES),
+            latitude: resolve(&self.latitude, LATITUDE_ALIASES),
+        }
+    }
+}
+
+/// Guess the longitude and latitude columns of `df`
+///
+/// Only `f64` columns with at least one value are considered. A column is a
+/// longitude candidate if all its values lie within ±180 and a latitude
+/// candidate if they lie within ±90. Among the candidates, names from the
+/// alias groups are preferred, then names containing `lon`/`lng` or `lat`.
+/// Without a usable name, a column is only chosen from its values when it is
+/// the single remaining candidate, e.g. the one column exceeding ±90 for
+/// longitude.
+///
+/// # Returns
+/// The `(longitude, latitude)` column names
+///
+/// # Errors
+/// Returns `ClassifierError::SchemaValidation` when either column cannot be
+/// identified unambiguously.
+///
+/// # Examples
+/// ```
+/// use polars::prelude::*;
+/// use urban_classifier::columns::detect_coordinate_columns;
+///
+/// let df = df! {
+///     "name" => ["Heathrow"],
+///     "elevation_m" => [25.0],
+///     "Latitude" => [51.48],
+///     "LNG" => [-0.45],
+/// }?;
+/// let (lon, lat) = detect_coordinate_columns(&df)?;
+/// assert_eq!((lon.as_str(), lat.as_str()), ("LNG", "Latitude"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn detect_coordinate_columns(df: &DataFrame) -> Result<(String, String)> {
+    let ranges: Vec<(&str, f64)> = df
+        .get_columns()
+        .iter()
+        .filter_map(|series| {
+            let values = series.f64().ok()?;
+            let max_abs = values
+                .into_iter()
+                .flatten()
+                .map(f64::abs)
+                .reduce(f64::max)?;
+            Some((series.name(), max_abs))
+        })
+        .collect();
+    let lon_candidates: Vec<&str> = ranges
+        .iter()
+        .filter(|(_, max_abs)| *max_abs <= 180.0)
+        .map(|(name, _)| *name)
+        .collect();
+    let lat_candidates: Vec<&str> = ranges
+        .iter()
+        .filter(|(_, max_abs)| *max_abs <= 90.0)
+        .map(|(name, _)| *name)
+        .collect();
+
+    let mut lon = best_named(&lon_candidates, LONGITUDE_ALIASES, &["lon", "lng"], None);
+    let mut lat = best_named(&lat_candidates, LATITUDE_ALIASES, &["lat"], lon);
+    if lon.is_none() {
+        lon = best_named(&lon_candidates, LONGITUDE_ALIASES, &["lon", "lng"], lat);
+    }
+
+    // Fall back to values: longitude is the one column outside ±90
+    if lon.is_none() {
+        let wide: Vec<&str> = lon_candidates
+            .iter()
+            .copied()
+            .filter(|name| !lat_candidates.contains(name) && Some(*name) != lat)
+            .collect();
+        lon = single(&wide);
+    }
+    if lat.is_none() {
+        let remaining: Vec<&str> = lat_candidates
+            .iter()
+            .copied()
+            .filter(|name| Some(*name) != lon)
+            .collect();
+        lat = single(&remaining);
+    }
+    if lon.is_none() {
+        let remaining: Vec<&str> = lon_candidates
+            .iter()
+            .copied()
+            .filter(|name| Some(*name) != lat)
+            .collect();
+        lon = single(&remaining);
+    }
+
+    match (lon, lat) {
+        (Some(lon), Some(lat)) => Ok((lon.to_string(), lat.to_string())),
+        _ => Err(ClassifierError::SchemaValidation {
+            message: format!(
+                "Could not detect the {} column among {:?}; name the columns explicitly",
+                match (lon, lat) {
+                    (None, None) => "longitude and latitude",
+                    (None, Some(_)) => "longitude",
+                    _ => "latitude",
+                },
+                ranges.iter().map(|(name, _)| *name).collect::<Vec<_>>()
+            ),
+        }),
+    }
+}
+
+/// Whether the longitude and latitude columns of `df` appear to be swapped
+///
+/// True when most latitude values lie outside ±90, which no latitude can,
+/// while most longitude values lie within ±90. Columns that are missing, not
+/// `f64` or entirely null are never reported as swapped.
+///
+/// # Examples
+/// ```
+/// use polars::prelude::*;
+/// use urban_classifier::columns::coordinates_swapped;
+///
+/// let df = df! {
+///     "longitude" => [35.7, -33.9],
+///     "latitude" => [139.7, 151.2],
+/// }?;
+/// assert!(coordinates_swapped(&df, "longitude", "latitude"));
+/// assert!(!coordinates_swapped(&df, "latitude", "longitude"));
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn coordinates_swapped(df: &DataFrame, lon_col: &str, lat_col: &str) -> bool {
+    let share_within_90 = |name: &str| {
+        let values = df.column(name).ok()?.f64().ok()?;
+        let (within, total) = values
+            .into_iter()
+            .flatten()
+            .fold((0usize, 0usize), |(within, total), value| {
+                (within + usize::from(value.abs() <= 90.0), total + 1)
+            });
+        (total > 0).then(|| within as f64 / total as f64)
+    };
+    match (share_within_90(lon_col), share_within_90(lat_col)) {
+        (Some(lon_within), Some(lat_within)) => lon_within > 0.5 && lat_within < 0.5,
+        _ => false,
+    }
+}
+
+/// Parse a coordinate written as text
+///
+/// Accepts plain decimal degrees and decimal degrees with a hemisphere letter
+/// before or after the number, optionally with a degree sign. `S` and `W`
+/// make the value negative; a negative number with a hemisphere letter is
+/// rejected as contradictory.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::columns::parse_coordinate;
+///
+/// assert_eq!(parse_coordinate(" -0.1278 "), Some(-0.1278));
+/// assert_eq!(parse_coordinate("51.5074 N"), Some(51.5074));
+/// assert_eq!(parse_coordinate("0.1278°W"), Some(-0.1278));
+/// assert_eq!(parse_coordinate("S 33.9"), Some(-33.9));
+/// assert_eq!(parse_coordinate("north"), None);
+/// ```
+pub fn parse_coordinate(text: &str) -> Option<f64> {
+    let text = text.trim();
+    let hemisphere = |c: char| matches!(c.to_ascii_uppercase(), 'N' | 'S' | 'E' | 'W');
+
+    let (number, sign) = match (text.chars().next(), text.chars().last()) {
+        (_, Some(c)) if hemisphere(c) => (&text[..text.len() - 1], Some(hemisphere_sign(c))),
+        (Some(c), _) if hemisphere(c) => (&text[1..], Some(hemisphere_sign(c))),
+        _ => (text, None),
+    };
+    let number = number.trim().trim_end_matches('°').trim_end();
+    let value: f64 = number.parse().ok()?;
+    if !value.is_finite() {
+        return None;
+    }
+
+    match sign {
+        None => Some(value),
+        Some(_) if value < 0.0 => None,
+        Some(sign) => Some(sign * value),
+    }
+}
+
+/// Sign implied by a hemisphere letter
+fn hemisphere_sign(c: char) -> f64 {
+    match c.to_ascii_uppercase() {
+        'S' | 'W' => -1.0,
+        _ => 1.0,
+    }
+}
+
+/// Convert a string coordinate column to `f64` with `parse_coordinate`
+///
+/// Nulls and blank strings become nulls.
+///
+/// # Returns
+/// The parsed column under the same name, or `None` when `series` is not a
+/// string column and needs no parsing
+///
+/// # Errors
+/// Returns `ClassifierError::CoordinateParse` listing every row whose value
+/// cannot be parsed.
+pub fn parse_coordinate_column(series: &Series) -> Result<Option<Series>> {
+    let Ok(strings) = series.str() else {
+        return Ok(None);
+    };
+
+    let mut failures = Vec::new();
+    let values: Vec<Option<f64>> = strings
+        .into_iter()
+        .enumerate()
+        .map(|(row, text)| {
+            let text = text.filter(|text| !text.trim().is_empty())?;
+            let value = parse_coordinate(text);
+            if value.is_none() {
+                failures.push((row, text.to_string()));
+            }
+            value
+        })
+        .collect();
+
+    if !failures.is_empty() {
+        return Err(ClassifierError::CoordinateParse {
+            column: series.name().to_string(),
+            rows: failures,
+        });
+    }
+    Ok(Some(Series::new(series.name(), values)))
+}
+
+/// Guess which of `available` was meant by a missing column name
+///
+/// A column in the same alias group as `requested` wins, e.g. `lng` for
+/// `longitude`; otherwise the closest name by case-insensitive edit distance,
+/// if it differs in at most a third of the characters.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::columns::suggest_column;
+///
+/// let available = ["station_id".to_string(), "lng".to_string(), "latitdue".to_string()];
+/// assert_eq!(suggest_column("longitude", &available), Some("lng"));
+/// assert_eq!(suggest_column("latitude", &available), Some("latitdue"));
+/// assert_eq!(suggest_column("elevation", &available), None);
+/// ```
+pub fn suggest_column<'a>(requested: &str, available: &'a [String]) -> Option<&'a str> {
+    let requested = requested.to_lowercase();
+    let group = [STATION_ID_ALIASES, LONGITUDE_ALIASES, LATITUDE_ALIASES]
+        .into_iter()
+        .find(|aliases| aliases.contains(&requested.as_str()));
+    if let Some(aliases) = group {
+        let alias = available
+            .iter()
+            .find(|name| aliases.contains(&name.to_lowercase().as_str()));
+        if let Some(alias) = alias {
+            return Some(alias);
+        }
+    }
+
+    let max_distance = (requested.chars().count() / 3).max(1);
+    available
+        .iter()
+        .map(|name| (edit_distance(&requested, &name.to_lowercase()), name))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.as_str())
+}
+
+/// Levenshtein distance between two strings, in characters
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Error for a column missing from `df`, listing the columns it has
+pub(crate) fn column_not_found(df: &DataFrame, column: &str) -> ClassifierError {
+    ClassifierError::ColumnNotFound {
+        column: column.to_string(),
+        available: df
+            .get_column_names()
+            .iter()
+            .map(|name| name.to_string())
+            .collect(),
+    }
+}
+
+/// The single candidate with the strongest name match, if unambiguous
+///
+/// Exact (case-insensitive) aliases beat names merely containing one of
+/// `fragments`; `taken` is excluded.
+fn best_named<'a>(
+    candidates: &[&'a str],
+    aliases: &[&str],
+    fragments: &[&str],
+    taken: Option<&str>,
+) -> Option<&'a str> {
+    let score = |name: &str| {
+        let lower = name.to_lowercase();
+        if aliases.iter().any(|alias| *alias == lower) {
+            2
+        } else if fragments.iter().any(|fragment| lower.contains(fragment)) {
+            1
+        } else {
+            0
+        }
+    };
+    let available: Vec<&str> = candidates
+        .iter()
+        .copied()
+        .filter(|name| Some(*name) != taken)
+        .collect();
+    let best = available.iter().map(|name| score(name)).max()?;
+    if best == 0 {
+        return None;
+    }
+    let top: Vec<&str> = available
+        .into_iter()
+        .filter(|name| score(name) == best)
+        .collect();
+    single(&top)
+}
+
+fn single<'a>(names: &[&'a str]) -> Option<&'a str> {
+    match names {
+        [name] => Some(name),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test case-insensitive and alias matching of column names
+    #[test]
+    fn test_resolve_column() {
+        let df = df! {
+            "Site_ID" => ["A"],
+            "LNG" => [-0.1],
+            "Lat" => [51.5],
+            "latitude_err" => [0.01],
+        }
+        .unwrap();
+
+        let resolved = ColumnNames::default().resolve_aliases(&df);
+        assert_eq!(resolved.station_id, "Site_ID");
+        assert_eq!(resolved.longitude, "LNG");
+        assert_eq!(resolved.latitude, "Lat");
+
+        assert_eq!(resolve_column(&df, "elevation", LATITUDE_ALIASES), None);
+    }
+
+    /// Test that exact matches win and ambiguous matches are skipped
+    #[test]
+    fn test_resolve_column_ambiguous() {
+        let df = df! {
+            "lat" => [51.5],
+            "LAT" => [51.6],
+            "latitude" => [51.7],
+        }
+        .unwrap();
+
+        assert_eq!(
+            resolve_column(&df, "LAT", LATITUDE_ALIASES).as_deref(),
+            Some("LAT")
+        );
+        assert_eq!(
+            resolve_column(&df, "Lat", LATITUDE_ALIASES).as_deref(),
+            Some("latitude")
+        );
+    }
+
+    /// Test suggestions for misspelled and aliased column names
+    #[test]
+    fn test_suggest_column() {
+        let available: Vec<String> = ["Station", "LONG", "lattitude"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(suggest_column("station_id", &available), Some("Station"));
+        assert_eq!(suggest_column("lon", &available), Some("LONG"));
+        assert_eq!(suggest_column("latitude", &available), Some("lattitude"));
+        assert_eq!(suggest_column("elevation", &available), None);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+
+        let df = df! { "lat" => [51.5] }.unwrap();
+        assert_eq!(
+            column_not_found(&df, "Lat").to_string(),
+            "Column not found in DataFrame: Lat. Available columns: lat. Did you mean 'lat'?"
+        );
+    }
+
+    /// Test coordinate detection from names and value ranges
+    #[test]
+    fn test_detect_coordinate_columns() {
+        let df = df! {
+            "station" => ["A", "B"],
+            "Lat_deg" => [51.5, 48.9],
+            "Long" => [-0.1, 2.35],
+        }
+        .unwrap();
+        let (lon, lat) = detect_coordinate_columns(&df).unwrap();
+        assert_eq!((lon.as_str(), lat.as_str()), ("Long", "Lat_deg"));
+
+        // Unnamed columns: only one exceeds ±90
+        let df = df! {
+            "a" => [35.7, -33.9],
+            "b" => [139.7, 151.2],
+        }
+        .unwrap();
+        let (lon, lat) = detect_coordinate_columns(&df).unwrap();
+        assert_eq!((lon.as_str(), lat.as_str()), ("b", "a"));
+
+        let df = df! {
+            "a" => [51.5],
+            "b" => [-0.1],
+        }
+        .unwrap();
+        assert!(matches!(
+            detect_coordinate_columns(&df),
+            Err(ClassifierError::SchemaValidation { .. })
+        ));
+    }
+
+    /// Test detection of swapped longitude and latitude columns
+    #[test]
+    fn test_coordinates_swapped() {
+        // Sydney, Tokyo and one station entered correctly
+        let df = df! {
+            "lon" => [-33.9, 35.7, 151.2],
+            "lat" => [151.2, 139.7, -33.9],
+        }
+        .unwrap();
+        assert!(coordinates_swapped(&df, "lon", "lat"));
+        assert!(!coordinates_swapped(&df, "lat", "lon"));
+
+        // European stations are ambiguous and never reported
+        let df = df! {
+            "lon" => [-0.1, 2.35],
+            "lat" => [51.5, 48.9],
+        }
+        .unwrap();
+        assert!(!coordinates_swapped(&df, "lon", "lat"));
+        assert!(!coordinates_swapped(&df, "lat", "lon"));
+        assert!(!coordinates_swapped(&df, "lon", "missing"));
+    }
+
+    /// Test parsing of string coordinate columns with row-level failures
+    #[test]
+    fn test_parse_coordinate_column() {
+        let series = Series::new("lat", &[Some("51.5074 N"), None, Some(" "), Some("33.9S")]);
+        let parsed = parse_coordinate_column(&series).unwrap().unwrap();
+        assert_eq!(parsed.name(), "lat");
+        assert_eq!(
+            parsed.f64().unwrap().into_iter().collect::<Vec<_>>(),
+            vec![Some(51.5074), None, None, Some(-33.9)]
+        );
+
+        let series = Series::new("lon", &["-0.1", "-0.1 W", "n/a"]);
+        match parse_coordinate_column(&series) {
+            Err(ClassifierError::CoordinateParse { column, rows }) => {
+                assert_eq!(column, "lon");
+                assert_eq!(
+                    rows,
+                    vec![(1, "-0.1 W".to_string()), (2, "n/a".to_string())]
+                );
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        let numeric = Series::new("lon", &[-0.1]);
+        assert!(parse_coordinate_column(&numeric).unwrap().is_none());
+    }
+}
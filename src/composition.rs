@@ -0,0 +1,404 @@
+//! Landscape Composition Around Stations
+//!
+//! This module computes per-class LCZ histograms inside a circular buffer around
+//! a location, giving the full landscape context of a station rather than the
+//! single class of the pixel it sits on.
+//!
+//! # Output
+//!
+//! Composition is reported as 17 percentage columns named `lcz_{code}_pct`
+//! (e.g. `lcz_6_pct`, `lcz_14_pct`). Percentages are relative to the number of
+//! pixels in the buffer holding a standard LCZ code (1-17); nodata and unknown
+//! values are excluded from the denominator.
+//!
+//! # Region Histograms
+//!
+//! `UrbanClassifier::lcz_histogram` counts pixels per class over an arbitrary
+//! WGS84 bounding box, for characterising a study region as a whole.
+//!
+//! `UrbanClassifier::lcz_area_km2` reports the area of each class within a
+//! bounding box or polygon instead. Pixels of the geographic-CRS global map
+//! shrink towards the poles, so pixel counts from different latitudes are not
+//! comparable; each pixel is weighted by its area at its own latitude.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::lcz::Lcz;
+use crate::source::PixelLocator;
+use crate::spatial::{pixel_area_m2, pixel_center_to_geo, BoundingBox, PixelWindow};
+use geo_types::{LineString, Polygon};
+use polars::prelude::*;
+use std::collections::HashMap;
+
+/// Number of raster rows read per strip when scanning large regions
+const HISTOGRAM_STRIP_ROWS: usize = 512;
+
+/// Number of standard LCZ classes counted in a composition histogram
+pub const LCZ_CLASS_COUNT: usize = 17;
+
+/// Pixel counts per LCZ class, indexed by `code - 1`
+pub type ClassCounts = [u64; LCZ_CLASS_COUNT];
+
+/// Count pixels of each LCZ class inside an elliptical buffer
+///
+/// # Arguments
+/// * `window` - Raster window containing the buffer (may be clipped at raster edges)
+/// * `center` - (pixel, line) of the buffer centre
+/// * `radius` - Buffer radius in pixels along the X and Y axes
+///
+/// # Returns
+/// Counts indexed by `code - 1`. Values outside 1-17 are ignored.
+pub fn class_counts_in_buffer(
+    window: &PixelWindow,
+    center: (isize, isize),
+    radius: (usize, usize),
+) -> ClassCounts {
+    let mut counts = [0u64; LCZ_CLASS_COUNT];
+    let rx = radius.0 as i64;
+    let ry = radius.1 as i64;
+
+    for row in 0..window.height {
+        for col in 0..window.width {
+            let dx = (window.x_off + col as isize - center.0) as i64;
+            let dy = (window.y_off + row as isize - center.1) as i64;
+
+            // Integer ellipse test: (dx/rx)^2 + (dy/ry)^2 <= 1
+            if dx * dx * ry * ry + dy * dy * rx * rx > rx * rx * ry * ry {
+                continue;
+            }
+            if (rx == 0 && dx != 0) || (ry == 0 && dy != 0) {
+                continue;
+            }
+
+            let code = window.data[row * window.width + col];
+            if (1..=LCZ_CLASS_COUNT as u8).contains(&code) {
+                counts[code as usize - 1] += 1;
+            }
+        }
+    }
+
+    counts
+}
+
+/// Convert class counts into percentages of all valid pixels
+///
+/// Returns `None` when the buffer contains no valid LCZ pixels.
+pub fn class_percentages(counts: &ClassCounts) -> Option<[f64; LCZ_CLASS_COUNT]> {
+    let total: u64 = counts.iter().sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut percentages = [0.0; LCZ_CLASS_COUNT];
+    for (pct, &count) in percentages.iter_mut().zip(counts.iter()) {
+        *pct = count as f64 * 100.0 / total as f64;
+    }
+    Some(percentages)
+}
+
+/// Build the 17 `lcz_{code}_pct` columns from per-station class counts
+pub(crate) fn composition_columns(counts: &[ClassCounts]) -> Vec<Series> {
+    let percentages: Vec<Option<[f64; LCZ_CLASS_COUNT]>> =
+        counts.iter().map(class_percentages).collect();
+
+    (0..LCZ_CLASS_COUNT)
+        .map(|i| {
+            let values: Vec<Option<f64>> = percentages
+                .iter()
+                .map(|station| station.map(|pct| pct[i]))
+                .collect();
+            Series::new(&format!("lcz_{}_pct", i + 1), values)
+        })
+        .collect()
+}
+
+impl UrbanClassifier {
+    /// Count raster pixels per LCZ class inside a WGS84 bounding box
+    ///
+    /// A pixel is counted when its centre lies inside the box. Values the code
+    /// mapping treats as nodata are skipped; other values outside 1-17 are
+    /// reported under `Lcz::Unknown(value)`.
+    /// The region is read in strips, so large boxes do not need to fit in memory.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::spatial::BoundingBox;
+    /// use urban_classifier::{Lcz, UrbanClassifier};
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let greater_london = BoundingBox::new(-0.51, 51.28, 0.33, 51.69)?;
+    /// let histogram = classifier.lcz_histogram(&greater_london)?;
+    /// println!("Open low-rise pixels: {:?}", histogram.get(&Lcz::OpenLowRise));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lcz_histogram(&self, bbox: &BoundingBox) -> Result<HashMap<Lcz, u64>> {
+        let mut histogram = HashMap::new();
+        self.scan_region(bbox, |lon, lat, code| {
+            if bbox.contains(lon, lat) {
+                *histogram.entry(Lcz::from_code(code)).or_insert(0) += 1;
+            }
+        })?;
+        Ok(histogram)
+    }
+
+    /// Area in km² of each LCZ class inside a WGS84 bounding box or polygon
+    ///
+    /// A pixel is included when its centre lies inside the region, and
+    /// contributes its own area (see `spatial::pixel_area_m2`), so results are
+    /// comparable between regions at different latitudes. Nodata pixels are
+    /// skipped and other values outside 1-17 are reported under `Lcz::Unknown`.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use geo_types::polygon;
+    /// use urban_classifier::composition::Region;
+    /// use urban_classifier::{Lcz, UrbanClassifier};
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let area = polygon![(x: -0.2, y: 51.4), (x: 0.0, y: 51.6), (x: 0.2, y: 51.4)];
+    /// let areas = classifier.lcz_area_km2(&Region::from(area))?;
+    /// println!("Compact midrise: {:?} km²", areas.get(&Lcz::CompactMidRise));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn lcz_area_km2(&self, region: &Region) -> Result<HashMap<Lcz, f64>> {
+        let metadata = self.source_metadata();
+        let geo_transform = metadata.geo_transform;
+        let units = metadata.crs.units()?;
+
+        let mut areas = HashMap::new();
+        self.scan_region(&region.bounding_box()?, |lon, lat, code| {
+            if region.contains(lon, lat) {
+                *areas.entry(Lcz::from_code(code)).or_insert(0.0) +=
+                    pixel_area_m2(&geo_transform, lat, units) / 1e6;
+            }
+        })?;
+        Ok(areas)
+    }
+
+    /// Visit the WGS84 centre and mapped code of every pixel in the window
+    /// covering `bbox`, skipping nodata
+    ///
+    /// The region is read in strips, so large boxes do not need to fit in memory.
+    fn scan_region(&self, bbox: &BoundingBox, mut visit: impl FnMut(f64, f64, u8)) -> Result<()> {
+        let metadata = self.source_metadata();
+        let geo_transform = metadata.geo_transform;
+        let (pixel, line, width, height) = PixelLocator::new(metadata)?.bbox_window(bbox)?;
+
+        let mut strip_start = 0;
+        while strip_start < height {
+            let strip_rows = HISTOGRAM_STRIP_ROWS.min(height - strip_start);
+            let window = self.read_window(pixel, line + strip_start as isize, width, strip_rows)?;
+            strip_start += strip_rows;
+
+            if window.data.is_empty() {
+                continue;
+            }
+
+            let mut xs = Vec::with_capacity(window.data.len());
+            let mut ys = Vec::with_capacity(window.data.len());
+            for row in 0..window.height as isize {
+                for col in 0..window.width as isize {
+                    let (x, y) =
+                        pixel_center_to_geo(window.x_off + col, window.y_off + row, &geo_transform);
+                    xs.push(x);
+                    ys.push(y);
+                }
+            }
+            metadata.crs.transform_to_wgs84(&mut xs, &mut ys)?;
+
+            for (i, &raw) in window.data.iter().enumerate() {
+                if let Some(code) = self.map_raw(raw) {
+                    visit(xs[i], ys[i], code);
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A WGS84 study region: a bounding box or a polygon with optional holes
+///
+/// Polygon coordinates are (longitude, latitude) in degrees.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Region {
+    /// An axis-aligned bounding box
+    BoundingBox(BoundingBox),
+    /// A polygon; points inside a hole are outside the region
+    Polygon(Polygon<f64>),
+}
+
+impl Region {
+    /// The bounding box enclosing the region
+    ///
+    /// # Errors
+    /// Returns an error for empty polygons and polygons outside the WGS84 range.
+    pub fn bounding_box(&self) -> Result<BoundingBox> {
+        match self {
+            Region::BoundingBox(bbox) => Ok(*bbox),
+            Region::Polygon(polygon) => {
+                let (min_lon, min_lat, max_lon, max_lat) = polygon.exterior().coords().fold(
+                    (
+                        f64::INFINITY,
+                        f64::INFINITY,
+                        f64::NEG_INFINITY,
+                        f64::NEG_INFINITY,
+                    ),
+                    |(min_lon, min_lat, max_lon, max_lat), coord| {
+                        (
+                            min_lon.min(coord.x),
+                            min_lat.min(coord.y),
+                            max_lon.max(coord.x),
+                            max_lat.max(coord.y),
+                        )
+                    },
+                );
+                if min_lon > max_lon {
+                    return Err(ClassifierError::InvalidParameter {
+                        message: "Region polygon has no exterior coordinates".to_string(),
+                    });
+                }
+                BoundingBox::new(min_lon, min_lat, max_lon, max_lat)
+            }
+        }
+    }
+
+    /// Check whether a WGS84 coordinate lies inside the region
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        match self {
+            Region::BoundingBox(bbox) => bbox.contains(lon, lat),
+            Region::Polygon(polygon) => {
+                ring_contains(polygon.exterior(), lon, lat)
+                    && !polygon
+                        .interiors()
+                        .iter()
+                        .any(|hole| ring_contains(hole, lon, lat))
+            }
+        }
+    }
+}
+
+impl From<BoundingBox> for Region {
+    fn from(bbox: BoundingBox) -> Self {
+        Region::BoundingBox(bbox)
+    }
+}
+
+impl From<Polygon<f64>> for Region {
+    fn from(polygon: Polygon<f64>) -> Self {
+        Region::Polygon(polygon)
+    }
+}
+
+/// Even-odd ray casting test of a point against a ring
+fn ring_contains(ring: &LineString<f64>, x: f64, y: f64) -> bool {
+    let mut inside = false;
+    for line in ring.lines() {
+        let (a, b) = (line.start, line.end);
+        if (a.y > y) != (b.y > y) && x < a.x + (y - a.y) * (b.x - a.x) / (b.y - a.y) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform_window(code: u8, size: usize) -> PixelWindow {
+        PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width: size,
+            height: size,
+            data: vec![code; size * size],
+        }
+    }
+
+    /// Test that the buffer is circular rather than square
+    #[test]
+    fn test_counts_use_circular_buffer() {
+        let window = uniform_window(6, 5);
+        let counts = class_counts_in_buffer(&window, (2, 2), (2, 2));
+
+        // A radius-2 disc covers 13 of the 25 pixels in a 5x5 window
+        assert_eq!(counts[5], 13);
+        assert_eq!(counts.iter().sum::<u64>(), 13);
+    }
+
+    /// Test that a zero radius only counts the centre pixel
+    #[test]
+    fn test_zero_radius_counts_centre() {
+        let mut window = uniform_window(14, 3);
+        window.data[4] = 2;
+
+        let counts = class_counts_in_buffer(&window, (1, 1), (0, 0));
+        assert_eq!(counts[1], 1);
+        assert_eq!(counts.iter().sum::<u64>(), 1);
+    }
+
+    /// Test that nodata and unknown codes are excluded
+    #[test]
+    fn test_invalid_codes_ignored() {
+        let window = PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width: 2,
+            height: 2,
+            data: vec![0, 255, 17, 17],
+        };
+
+        let counts = class_counts_in_buffer(&window, (0, 0), (3, 3));
+        assert_eq!(counts[16], 2);
+        assert_eq!(counts.iter().sum::<u64>(), 2);
+
+        let pct = class_percentages(&counts).unwrap();
+        assert_eq!(pct[16], 100.0);
+    }
+
+    /// Test polygon regions, including holes, and their bounding boxes
+    #[test]
+    fn test_polygon_region() {
+        use geo_types::polygon;
+
+        let square = polygon!(
+            exterior: [(x: 0.0, y: 0.0), (x: 4.0, y: 0.0), (x: 4.0, y: 4.0), (x: 0.0, y: 4.0)],
+            interiors: [[(x: 1.0, y: 1.0), (x: 2.0, y: 1.0), (x: 2.0, y: 2.0), (x: 1.0, y: 2.0)]],
+        );
+        let region = Region::from(square);
+        assert!(region.contains(3.0, 3.0));
+        assert!(!region.contains(1.5, 1.5));
+        assert!(!region.contains(5.0, 3.0));
+        assert_eq!(
+            region.bounding_box().unwrap(),
+            BoundingBox::new(0.0, 0.0, 4.0, 4.0).unwrap()
+        );
+
+        let triangle = Region::from(polygon![(x: 0.0, y: 0.0), (x: 2.0, y: 0.0), (x: 0.0, y: 2.0)]);
+        assert!(triangle.contains(0.5, 0.5));
+        assert!(!triangle.contains(1.5, 1.5));
+    }
+
+    /// Test percentage columns, including stations without valid pixels
+    #[test]
+    fn test_composition_columns() {
+        let mut counts = [0u64; LCZ_CLASS_COUNT];
+        counts[0] = 1;
+        counts[10] = 3;
+
+        let columns = composition_columns(&[counts, [0; LCZ_CLASS_COUNT]]);
+        assert_eq!(columns.len(), LCZ_CLASS_COUNT);
+        assert_eq!(columns[0].name(), "lcz_1_pct");
+        assert_eq!(columns[16].name(), "lcz_17_pct");
+
+        let lcz_1 = columns[0].f64().unwrap();
+        assert_eq!(lcz_1.get(0), Some(25.0));
+        assert_eq!(lcz_1.get(1), None);
+
+        let lcz_11 = columns[10].f64().unwrap();
+        assert_eq!(lcz_11.get(0), Some(75.0));
+    }
+}
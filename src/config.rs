@@ -0,0 +1,290 @@
+//! TOML Classifier Configuration
+//!
+//! Pipeline settings can be kept in a TOML file versioned alongside the
+//! analysis code instead of being embedded in Rust:
+//!
+//! ```toml
+//! raster_path = "data/wudapt_lcz_global.tif"
+//! buffer_radius_m = 500.0
+//! validation_mode = "flag"        # "lenient" (default), "flag" or "strict"
+//! nodata_values = [0, 255]
+//! nodata_override = 254           # replaces wrong or missing raster nodata metadata
+//! overrides_file = "overrides.csv"
+//!
+//! [columns]
+//! station_id = "src_id"
+//! longitude = "lon"
+//! latitude = "lat"
+//! ```
+//!
+//! Relative `raster_path` and `overrides_file` entries are resolved against
+//! the directory containing the configuration file. The overrides file is a
+//! CSV with `station_id` and `lcz_code` columns. Codes may also be written as
+//! subclasses such as `2_3` or `Ab` (see `variant`), which override with
+//! their parent class.
+
+use crate::classifier::{UrbanClassifier, ValidationMode};
+use crate::error::{ClassifierError, Result};
+use crate::mapping::CodeMapping;
+use crate::request::ClassificationRequest;
+use crate::variant::LczVariant;
+use polars::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Station column names used by a configured pipeline
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColumnNames {
+    /// Column holding station IDs
+    pub station_id: String,
+    /// Column holding longitudes in degrees
+    pub longitude: String,
+    /// Column holding latitudes in degrees
+    pub latitude: String,
+}
+
+impl Default for ColumnNames {
+    fn default() -> Self {
+        ColumnNames {
+            station_id: "station_id".to_string(),
+            longitude: "longitude".to_string(),
+            latitude: "latitude".to_string(),
+        }
+    }
+}
+
+/// Classifier options loaded from a TOML file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ClassifierConfig {
+    /// Path or URL of the LCZ raster
+    pub raster_path: PathBuf,
+    /// Buffer radius in metres; adds class composition columns when set
+    #[serde(default)]
+    pub buffer_radius_m: Option<f64>,
+    /// Treatment of codes outside the 17 standard classes
+    #[serde(default)]
+    pub validation_mode: ValidationMode,
+    /// Raw raster values treated as nodata
+    #[serde(default)]
+    pub nodata_values: Vec<u8>,
+    /// Raw raster value treated as nodata in place of the raster's own metadata
+    #[serde(default)]
+    pub nodata_override: Option<u8>,
+    /// Station column names
+    #[serde(default)]
+    pub columns: ColumnNames,
+    /// CSV file of manual overrides (`station_id`, `lcz_code`)
+    #[serde(default)]
+    pub overrides_file: Option<PathBuf>,
+}
+
+impl ClassifierConfig {
+    /// Parse a configuration from TOML text
+    ///
+    /// Relative paths are kept as written, i.e. relative to the working directory.
+    pub fn from_toml_str(text: &str) -> Result<Self> {
+        toml::from_str(text).map_err(|e| ClassifierError::Config {
+            message: e.to_string(),
+        })
+    }
+
+    /// Load a configuration file, resolving relative paths against its directory
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ClassifierError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+
+        let mut config =
+            Self::from_toml_str(&std::fs::read_to_string(path)?).map_err(|e| match e {
+                ClassifierError::Config { message } => ClassifierError::Config {
+                    message: format!("{}: {}", path.display(), message),
+                },
+                other => other,
+            })?;
+
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        if !is_url(&config.raster_path) {
+            config.raster_path = base.join(&config.raster_path);
+        }
+        if let Some(overrides) = &config.overrides_file {
+            config.overrides_file = Some(base.join(overrides));
+        }
+
+        Ok(config)
+    }
+
+    /// Code mapping treating the configured values as nodata
+    pub fn code_mapping(&self) -> CodeMapping {
+        self.nodata_values
+            .iter()
+            .fold(CodeMapping::identity(), |mapping, &raw| {
+                mapping.with_nodata(raw)
+            })
+    }
+
+    /// Open the configured raster with the configured policies
+    #[cfg(feature = "gdal")]
+    pub fn build_classifier(&self) -> Result<UrbanClassifier> {
+        let builder = UrbanClassifier::builder()
+            .with_path(&self.raster_path)
+            .with_code_mapping(self.code_mapping())
+            .with_validation_mode(self.validation_mode);
+        match self.nodata_override {
+            Some(value) => builder.with_nodata_override(value),
+            None => builder,
+        }
+        .build()
+    }
+
+    /// Read the overrides file, if one is configured
+    pub fn load_overrides(&self) -> Result<Option<HashMap<String, u8>>> {
+        let Some(path) = &self.overrides_file else {
+            return Ok(None);
+        };
+        if !path.exists() {
+            return Err(ClassifierError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+
+        let df = CsvReader::from_path(path)?.has_header(true).finish()?;
+        let ids = df.column("station_id")?.cast(&DataType::String)?;
+        let codes = df.column("lcz_code")?.cast(&DataType::String)?;
+
+        let mut overrides = HashMap::new();
+        for (id, code) in ids.str()?.into_iter().zip(codes.str()?) {
+            let (Some(id), Some(code)) = (id, code) else {
+                return Err(ClassifierError::Config {
+                    message: format!("{}: overrides must not be empty", path.display()),
+                });
+            };
+            let code = code
+                .trim()
+                .parse::<u8>()
+                .ok()
+                .or_else(|| {
+                    code.parse::<LczVariant>()
+                        .ok()
+                        .map(|variant| variant.code())
+                })
+                .ok_or_else(|| ClassifierError::Config {
+                    message: format!(
+                        "{}: override for station {} is not a valid code: {}",
+                        path.display(),
+                        id,
+                        code
+                    ),
+                })?;
+            overrides.insert(id.to_string(), code);
+        }
+
+        Ok(Some(overrides))
+    }
+
+    /// Classify stations with a classifier built from this configuration
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::config::ClassifierConfig;
+    ///
+    /// let config = ClassifierConfig::from_file("analysis/classifier.toml")?;
+    /// let classifier = config.build_classifier()?;
+    /// let stations = CsvReader::from_path("analysis/stations.csv")?.finish()?;
+    /// let result = config.classify(&classifier, &stations)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify(
+        &self,
+        classifier: &UrbanClassifier,
+        stations: &DataFrame,
+    ) -> Result<DataFrame> {
+        let overrides = self.load_overrides()?;
+        let mut request = ClassificationRequest::new().with_columns(self.columns.clone());
+        if let Some(overrides) = &overrides {
+            request = request.with_overrides(overrides);
+        }
+        if let Some(radius_m) = self.buffer_radius_m {
+            request = request.with_buffer(radius_m);
+        }
+        classifier.classify_with(stations, &request)
+    }
+}
+
+/// Check whether a configured raster path is a URL rather than a file path
+fn is_url(path: &Path) -> bool {
+    path.to_str().is_some_and(crate::remote::is_remote)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing a full configuration
+    #[test]
+    fn test_parse_config() {
+        let config = ClassifierConfig::from_toml_str(
+            r#"
+            raster_path = "lcz.tif"
+            buffer_radius_m = 250.0
+            validation_mode = "strict"
+            nodata_values = [0]
+            nodata_override = 255
+
+            [columns]
+            station_id = "src_id"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.buffer_radius_m, Some(250.0));
+        assert_eq!(config.validation_mode, ValidationMode::Strict);
+        assert_eq!(config.columns.station_id, "src_id");
+        assert_eq!(config.columns.latitude, "latitude");
+        assert_eq!(config.code_mapping().apply(0), None);
+        assert_eq!(config.code_mapping().apply(3), Some(3));
+        assert_eq!(config.nodata_override, Some(255));
+    }
+
+    /// Test that unknown keys and missing raster paths are rejected
+    #[test]
+    fn test_invalid_config() {
+        assert!(matches!(
+            ClassifierConfig::from_toml_str("raster_path = \"a.tif\"\nbufer_radius_m = 5.0"),
+            Err(ClassifierError::Config { .. })
+        ));
+        assert!(ClassifierConfig::from_toml_str("buffer_radius_m = 5.0").is_err());
+    }
+
+    /// Test path resolution and overrides loading from a configuration file
+    #[test]
+    fn test_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("classifier.toml"),
+            "raster_path = \"lcz.tif\"\noverrides_file = \"overrides.csv\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("overrides.csv"),
+            "station_id,lcz_code\nA,2\nB,14\nC,2_3\nD,Ab\n",
+        )
+        .unwrap();
+
+        let config = ClassifierConfig::from_file(dir.path().join("classifier.toml")).unwrap();
+        assert_eq!(config.raster_path, dir.path().join("lcz.tif"));
+
+        let overrides = config.load_overrides().unwrap().unwrap();
+        assert_eq!(overrides.get("A"), Some(&2));
+        assert_eq!(overrides.get("B"), Some(&14));
+        assert_eq!(overrides.get("C"), Some(&2));
+        assert_eq!(overrides.get("D"), Some(&11));
+    }
+}
@@ -0,0 +1,123 @@
+//! Class Definitions and Land-Use Examples
+//!
+//! `Lcz::description` returns the definition of each class after Stewart and
+//! Oke (2012, Fig. 2), and `Lcz::examples` lists representative land uses,
+//! for legends, tooltips and report text.
+
+use crate::lcz::Lcz;
+
+/// Definitions of LCZ 1-10 and A-G, indexed by code - 1
+const DESCRIPTIONS: [&str; 17] = [
+    "Dense mix of tall buildings to tens of stories. Few or no trees. Land cover mostly \
+     paved. Concrete, steel, stone, and glass construction materials.",
+    "Dense mix of midrise buildings (3-9 stories). Few or no trees. Land cover mostly \
+     paved. Stone, brick, tile, and concrete construction materials.",
+    "Dense mix of low-rise buildings (1-3 stories). Few or no trees. Land cover mostly \
+     paved. Stone, brick, tile, and concrete construction materials.",
+    "Open arrangement of tall buildings to tens of stories. Abundance of pervious land \
+     cover (low plants, scattered trees). Concrete, steel, stone, and glass construction \
+     materials.",
+    "Open arrangement of midrise buildings (3-9 stories). Abundance of pervious land cover \
+     (low plants, scattered trees). Concrete, steel, stone, and glass construction materials.",
+    "Open arrangement of low-rise buildings (1-3 stories). Abundance of pervious land cover \
+     (low plants, scattered trees). Wood, brick, stone, tile, and concrete construction \
+     materials.",
+    "Dense mix of single-story buildings. Few or no trees. Land cover mostly hard-packed. \
+     Lightweight construction materials (e.g., wood, thatch, corrugated metal).",
+    "Open arrangement of large low-rise buildings (1-3 stories). Few or no trees. Land \
+     cover mostly paved. Steel, concrete, metal, and stone construction materials.",
+    "Sparse arrangement of small or medium-sized buildings in a natural setting. Abundance \
+     of pervious land cover (low plants, scattered trees).",
+    "Low-rise and midrise industrial structures (towers, tanks, stacks). Few or no trees. \
+     Land cover mostly paved or hard-packed. Metal, steel, and concrete construction \
+     materials.",
+    "Heavily wooded landscape of deciduous and/or evergreen trees. Land cover mostly \
+     pervious (low plants). Zone function is natural forest, tree cultivation, or urban park.",
+    "Lightly wooded landscape of deciduous and/or evergreen trees. Land cover mostly \
+     pervious (low plants). Zone function is natural forest, tree cultivation, or urban park.",
+    "Open arrangement of bushes, shrubs, and short, woody trees. Land cover mostly \
+     pervious (bare soil or sand). Zone function is natural scrubland or agriculture.",
+    "Featureless landscape of grass or herbaceous plants/crops. Few or no trees. Zone \
+     function is natural grassland, agriculture, or urban park.",
+    "Featureless landscape of rock or paved cover. Few or no trees or plants. Zone \
+     function is natural desert (rock) or urban transportation.",
+    "Featureless landscape of soil or sand cover. Few or no trees or plants. Zone function \
+     is natural desert or agriculture.",
+    "Large, open water bodies such as seas and lakes, or small bodies such as rivers, \
+     reservoirs, and lagoons.",
+];
+
+/// Representative land uses of LCZ 1-10 and A-G, indexed by code - 1
+const EXAMPLES: [&[&str]; 17] = [
+    &[
+        "Downtown core",
+        "Central business district",
+        "High-density residential towers",
+    ],
+    &[
+        "Historic city centre",
+        "Inner-city apartment blocks",
+        "Mixed-use terraces",
+    ],
+    &["Old town core", "Dense row housing", "Village centre"],
+    &["Office park", "Residential tower estate"],
+    &["Apartment blocks with courtyards", "Institutional campus"],
+    &["Suburban detached housing", "Townhouse estate"],
+    &["Informal settlement", "Shanty town"],
+    &["Warehouse district", "Shopping centre", "Light industry"],
+    &["Rural village", "Farmstead", "Exurban housing"],
+    &["Refinery", "Steel mill", "Power station"],
+    &["Natural forest", "Tree plantation", "Wooded urban park"],
+    &["Open woodland", "Orchard", "Park with scattered trees"],
+    &["Natural scrubland", "Savanna", "Vineyard"],
+    &["Grassland", "Cropland", "Golf course", "Sports field"],
+    &["Rock outcrop", "Airport runway", "Large car park"],
+    &["Desert", "Beach", "Ploughed field"],
+    &["Sea", "Lake", "River", "Reservoir"],
+];
+
+impl Lcz {
+    /// Definition of this class after Stewart and Oke (2012), or `None` for
+    /// unknown codes
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// let text = Lcz::LightweightLowRise.description().unwrap();
+    /// assert!(text.starts_with("Dense mix of single-story buildings."));
+    /// ```
+    pub fn description(&self) -> Option<&'static str> {
+        match self {
+            Lcz::Unknown(_) => None,
+            lcz => Some(DESCRIPTIONS[usize::from(lcz.to_code()) - 1]),
+        }
+    }
+
+    /// Representative land uses of this class; empty for unknown codes
+    pub fn examples(&self) -> &'static [&'static str] {
+        match self {
+            Lcz::Unknown(_) => &[],
+            lcz => EXAMPLES[usize::from(lcz.to_code()) - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every standard class has a definition and examples
+    #[test]
+    fn test_descriptions_and_examples() {
+        for lcz in Lcz::iter() {
+            let description = lcz.description().unwrap();
+            assert!(description.ends_with('.'), "{}", lcz);
+            assert!(!description.contains("  "), "{}", lcz);
+            assert!(!lcz.examples().is_empty(), "{}", lcz);
+        }
+        assert!(Lcz::Water.description().unwrap().contains("lakes"));
+        assert_eq!(Lcz::Unknown(0).description(), None);
+        assert!(Lcz::Unknown(0).examples().is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+//! Rich Diagnostics with miette (`diagnostics` feature)
+//!
+//! Implements `miette::Diagnostic` for `ClassifierError` and `StationError`,
+//! so command-line tools can report errors with their stable code, a hint on
+//! how to fix them and, for missing or unparseable columns, a label on the
+//! offending column name. The failures of a `Multiple` error are reported as
+//! related diagnostics.
+//!
+//! # Examples
+//! ```no_run
+//! use polars::prelude::*;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! fn main() -> miette::Result<()> {
+//!     let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+//!     let stations = df! {
+//!         "station_id" => ["A"],
+//!         "lng" => [-0.12],
+//!         "lat" => [51.5],
+//!     }
+//!     .expect("valid DataFrame");
+//!     // Reports the missing `longitude` column and suggests `lng`
+//!     classifier.classify(&stations)?;
+//!     Ok(())
+//! }
+//! ```
+
+use crate::error::ClassifierError;
+use crate::failures::StationError;
+use miette::{Diagnostic, LabeledSpan, SourceCode};
+use std::fmt::Display;
+
+impl Diagnostic for ClassifierError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(ClassifierError::code(self)))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        let help = match self {
+            ClassifierError::FileNotFound { .. } => {
+                "Check the path, or run download_wudapt to fetch the global LCZ map"
+            }
+            ClassifierError::ColumnNotFound { .. } => {
+                "Name the columns with ClassificationRequest::with_columns, or enable \
+                 case-insensitive matching with with_column_aliases"
+            }
+            ClassifierError::InvalidCoordinate { .. } => {
+                "Longitudes must lie within -180..180 and latitudes within -90..90; \
+                 check whether the coordinate columns are swapped"
+            }
+            ClassifierError::CoordinateTransform { .. } => {
+                "Check the input CRS given with ClassificationRequest::with_input_crs \
+                 and the CRS of the raster"
+            }
+            ClassifierError::OutOfBounds { .. } => {
+                "The station lies outside the loaded map; load a map covering it, \
+                 such as the global WUDAPT map"
+            }
+            ClassifierError::SchemaValidation { .. } => {
+                "Coordinate columns must be numeric; enable \
+                 ClassificationRequest::with_coordinate_parsing for string columns"
+            }
+            ClassifierError::UnexpectedRasterValue { .. } => {
+                "The raster may use another encoding: configure a CodeMapping, or use \
+                 ValidationMode::Lenient to report such values as unknown; values that \
+                 are not integers from 0 to 255 need the band converted to bytes"
+            }
+            ClassifierError::UnsupportedRaster { .. } => {
+                "Convert the raster to a GeoTIFF with gdal_translate, or open it with \
+                 the GDAL backend"
+            }
+            ClassifierError::Download {
+                retryable: true, ..
+            } => "The failure looks transient; try again later",
+            ClassifierError::Download { .. } => {
+                "Check the URL, or download the map manually from \
+                 https://lcz-generator.rub.de/downloads"
+            }
+            ClassifierError::CoordinateParse { .. } => {
+                "Coordinates must be decimal degrees, optionally with a hemisphere \
+                 suffix such as `51.5074 N`"
+            }
+            _ => return None,
+        };
+        Some(Box::new(help))
+    }
+
+    fn source_code(&self) -> Option<&dyn SourceCode> {
+        match self {
+            ClassifierError::ColumnNotFound { column, .. }
+            | ClassifierError::CoordinateParse { column, .. } => Some(column),
+            _ => None,
+        }
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        let (column, label) = match self {
+            ClassifierError::ColumnNotFound { column, .. } => {
+                (column, "not a column of the DataFrame".to_string())
+            }
+            ClassifierError::CoordinateParse { column, rows } => (
+                column,
+                format!("{} value(s) could not be parsed", rows.len()),
+            ),
+            _ => return None,
+        };
+        let span = LabeledSpan::new(Some(label), 0, column.len());
+        Some(Box::new(std::iter::once(span)))
+    }
+
+    fn related<'a>(&'a self) -> Option<Box<dyn Iterator<Item = &'a dyn Diagnostic> + 'a>> {
+        match self {
+            ClassifierError::Multiple(errors) => Some(Box::new(
+                errors.iter().map(|error| error as &dyn Diagnostic),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl Diagnostic for StationError {
+    fn code<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Some(Box::new(self.cause.code()))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn Display + 'a>> {
+        Diagnostic::help(&self.cause)
+    }
+
+    fn diagnostic_source(&self) -> Option<&dyn Diagnostic> {
+        Some(&self.cause)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that missing columns carry a code, help and a label on the column name
+    #[test]
+    fn test_column_not_found_diagnostic() {
+        let error = ClassifierError::ColumnNotFound {
+            column: "longitude".to_string(),
+            available: vec!["lng".to_string()],
+        };
+        assert_eq!(
+            Diagnostic::code(&error).unwrap().to_string(),
+            "E_COLUMN_NOT_FOUND"
+        );
+        assert!(error.help().is_some());
+        let labels: Vec<LabeledSpan> = error.labels().unwrap().collect();
+        assert_eq!(labels.len(), 1);
+        assert_eq!((labels[0].offset(), labels[0].len()), (0, 9));
+    }
+}
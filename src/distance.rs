@@ -0,0 +1,225 @@
+//! Distance to the Nearest Pixel of a Class
+//!
+//! Metrics such as "distance to the nearest urban area" or "distance to open
+//! water" describe a station's exposure beyond its own pixel. This module
+//! provides the primitive behind them: `distance_to_class` searches rings of
+//! increasing radius around a pixel until it finds the target class, reading
+//! a progressively larger window so that nearby hits stay cheap.
+//!
+//! Distances are Euclidean in pixel space; `ClassDistance::metres` converts
+//! them using the metric pixel size at the station's latitude, and
+//! `ClassDistance::great_circle_m` measures the great-circle distance between
+//! pixel centres of geographic rasters. For geographic rasters away from the
+//! equator pixels are narrower than they are tall, so the pixel found is the
+//! nearest in pixel space, which may differ slightly from the nearest in metres.
+
+use crate::error::Result;
+use crate::lcz::Lcz;
+use crate::spatial::{
+    haversine_distance_m, pixel_center_to_geo, pixel_size_m, read_pixel_window, CrsUnits,
+    PixelWindow,
+};
+use gdal::raster::RasterBand;
+
+/// Radius of the first window read by `distance_to_class`, in pixels
+const INITIAL_SEARCH_RADIUS: usize = 8;
+
+/// Nearest pixel of a target class, relative to the search origin
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClassDistance {
+    /// Column offset of the nearest pixel from the origin
+    pub dx: isize,
+    /// Row offset of the nearest pixel from the origin
+    pub dy: isize,
+}
+
+impl ClassDistance {
+    /// Euclidean distance between pixel centres, in pixels
+    pub fn pixels(&self) -> f64 {
+        (self.dx as f64).hypot(self.dy as f64)
+    }
+
+    /// Distance between pixel centres in metres, for an origin at latitude `lat`
+    pub fn metres(&self, geo_transform: &[f64; 6], lat: f64, units: CrsUnits) -> f64 {
+        let (width_m, height_m) = pixel_size_m(geo_transform, lat, units);
+        (self.dx as f64 * width_m).hypot(self.dy as f64 * height_m)
+    }
+
+    /// Great-circle distance between pixel centres in metres, for an origin at
+    /// (`pixel`, `line`) of a geographic raster
+    ///
+    /// Unlike `metres`, accounts for the change in pixel width between the
+    /// origin's latitude and the match's, which matters for distant matches
+    /// at high latitudes.
+    pub fn great_circle_m(&self, geo_transform: &[f64; 6], pixel: isize, line: isize) -> f64 {
+        let (lon1, lat1) = pixel_center_to_geo(pixel, line, geo_transform);
+        let (lon2, lat2) = pixel_center_to_geo(pixel + self.dx, line + self.dy, geo_transform);
+        haversine_distance_m(lon1, lat1, lon2, lat2)
+    }
+}
+
+/// Find the nearest pixel holding `target` within `max_radius` pixels
+///
+/// Raw band values are compared with the code of `target`, without any code
+/// mapping. Pixels outside the raster are never matched.
+///
+/// # Returns
+/// The offset of the nearest matching pixel, `ClassDistance { dx: 0, dy: 0 }`
+/// if the origin itself matches, or `None` if no pixel within `max_radius`
+/// matches
+///
+/// # Examples
+/// ```no_run
+/// use gdal::Dataset;
+/// use urban_classifier::distance::distance_to_class;
+/// use urban_classifier::spatial::CrsUnits;
+/// use urban_classifier::Lcz;
+///
+/// let dataset = Dataset::open("path/to/wudapt.tif")?;
+/// let band = dataset.rasterband(1)?;
+/// if let Some(nearest) = distance_to_class(&band, 20_000, 7_000, Lcz::Water, 50)? {
+///     let metres = nearest.metres(&dataset.geo_transform()?, 51.5, CrsUnits::Degrees);
+///     println!("Water within {:.0} m", metres);
+/// }
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn distance_to_class(
+    band: &RasterBand,
+    pixel: isize,
+    line: isize,
+    target: Lcz,
+    max_radius: usize,
+) -> Result<Option<ClassDistance>> {
+    let code = target.to_code();
+    let mut best = None;
+    let mut searched = 0;
+    let mut radius = INITIAL_SEARCH_RADIUS.min(max_radius);
+    loop {
+        let size = 2 * radius + 1;
+        let window = read_pixel_window(
+            band,
+            pixel - radius as isize,
+            line - radius as isize,
+            size,
+            size,
+        )?;
+        let rings = searched..=radius;
+        if search_rings(&window, (pixel, line), code, rings, max_radius, &mut best)
+            || radius == max_radius
+        {
+            return Ok(best);
+        }
+        searched = radius + 1;
+        radius = (2 * radius).clamp(1, max_radius);
+    }
+}
+
+/// Search rings `rings` around `origin` in `window`, updating `best`
+///
+/// Returns `true` once `best` is known to be the nearest match: no pixel on a
+/// ring beyond its distance can be closer.
+fn search_rings(
+    window: &PixelWindow,
+    origin: (isize, isize),
+    code: u8,
+    rings: std::ops::RangeInclusive<usize>,
+    max_radius: usize,
+    best: &mut Option<ClassDistance>,
+) -> bool {
+    for ring in rings {
+        if best.is_some_and(|best| ring as f64 > best.pixels()) {
+            return true;
+        }
+        for (dx, dy) in ring_offsets(ring) {
+            let candidate = ClassDistance { dx, dy };
+            if window.get(origin.0 + dx, origin.1 + dy) == Some(code)
+                && candidate.pixels() <= max_radius as f64
+                && !best.is_some_and(|best| best.pixels() <= candidate.pixels())
+            {
+                *best = Some(candidate);
+            }
+        }
+    }
+    false
+}
+
+/// Offsets of the pixels on the square ring at Chebyshev distance `ring`
+fn ring_offsets(ring: usize) -> impl Iterator<Item = (isize, isize)> {
+    let r = ring as isize;
+    (-r..=r).flat_map(move |dy| {
+        // Inner rows only contribute their two end pixels
+        let step = if dy.abs() == r { 1 } else { 2 * ring };
+        (-r..=r).step_by(step).map(move |dx| (dx, dy))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(width: usize, data: Vec<u8>) -> PixelWindow {
+        PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width,
+            height: data.len() / width,
+            data,
+        }
+    }
+
+    /// Test that rings enumerate exactly the pixels at each Chebyshev distance
+    #[test]
+    fn test_ring_offsets() {
+        assert_eq!(ring_offsets(0).collect::<Vec<_>>(), vec![(0, 0)]);
+        for ring in 1..5 {
+            let offsets: Vec<(isize, isize)> = ring_offsets(ring).collect();
+            assert_eq!(offsets.len(), 8 * ring);
+            assert!(offsets
+                .iter()
+                .all(|(dx, dy)| dx.abs().max(dy.abs()) == ring as isize));
+        }
+    }
+
+    /// Test that a later ring's edge wins over an earlier ring's farther corner
+    #[test]
+    fn test_search_rings() {
+        // 17 at a ring-3 corner (4.24 px) and at (4, 0) on ring 4 (4 px)
+        let mut data = vec![14; 81];
+        data[10] = 17;
+        data[44] = 17;
+        let window = window(9, data);
+
+        let mut best = None;
+        search_rings(&window, (4, 4), 17, 0..=4, 10, &mut best);
+        assert_eq!(best, Some(ClassDistance { dx: 4, dy: 0 }));
+
+        let mut best = None;
+        search_rings(&window, (4, 4), 17, 0..=4, 3, &mut best);
+        assert_eq!(best, None);
+
+        let mut best = None;
+        assert!(search_rings(&window, (4, 4), 14, 0..=4, 10, &mut best));
+        assert_eq!(best, Some(ClassDistance { dx: 0, dy: 0 }));
+    }
+
+    /// Test metric conversion on projected and geographic grids
+    #[test]
+    fn test_class_distance_metres() {
+        let distance = ClassDistance { dx: 3, dy: -4 };
+        assert_eq!(distance.pixels(), 5.0);
+
+        let projected = [0.0, 100.0, 0.0, 0.0, 0.0, -100.0];
+        assert_eq!(distance.metres(&projected, 0.0, CrsUnits::METRES), 500.0);
+
+        let geographic = [0.0, 0.001, 0.0, 0.0, 0.0, -0.001];
+        let east = ClassDistance { dx: 10, dy: 0 };
+        let at_equator = east.metres(&geographic, 0.0, CrsUnits::Degrees);
+        let at_sixty = east.metres(&geographic, 60.0, CrsUnits::Degrees);
+        assert!((at_sixty / at_equator - 0.5).abs() < 1e-9);
+
+        // Pixel (0, 0) is centred at 60°N
+        let geographic = [0.0, 0.001, 0.0, 60.0005, 0.0, -0.001];
+        let great_circle = east.great_circle_m(&geographic, 0, 0);
+        assert!((great_circle / at_sixty - 1.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,506 @@
+//! WUDAPT Global LCZ Map Download
+//!
+//! Shared download logic for the `download_wudapt` binary and
+//! `UrbanClassifier::with_auto_download`. The global map is fetched from the
+//! first WUDAPT mirror that responds, written to a `.part` file next to the
+//! destination and renamed into place once complete and verified, so an
+//! interrupted or invalid download is never mistaken for a cached copy. Transient failures, such as
+//! timeouts and HTTP 503 responses, are retried up to `DOWNLOAD_ATTEMPTS`
+//! times before moving on to the next mirror.
+//!
+//! `fetch_wudapt` downloads a specific map version, e.g. for the Python
+//! `download_wudapt` function.
+//!
+//! `UrbanClassifier::from_default_locations` looks for an existing copy in the
+//! places listed by `search_paths`, starting with `URBAN_CLASSIFIER_WUDAPT_PATH`.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Known WUDAPT download URLs (as of 2024), in order of preference
+pub const WUDAPT_URLS: &[(&str, &str)] = &[
+    (
+        "lcz-generator-v3",
+        "https://lcz-generator.rub.de/cogs/lcz_filter_v3_cog.tif",
+    ),
+    (
+        "zenodo-v3",
+        "https://zenodo.org/records/6364594/files/lcz_filter_v3.tif",
+    ),
+    (
+        "lcz-generator-v2",
+        "https://lcz-generator.rub.de/cogs/lcz_filter_v2_cog.tif",
+    ),
+];
+
+/// Attempts per mirror while downloads fail with retryable errors
+pub const DOWNLOAD_ATTEMPTS: u32 = 3;
+
+/// Environment variable naming the LCZ map checked first by `from_default_locations`
+pub const WUDAPT_PATH_ENV: &str = "URBAN_CLASSIFIER_WUDAPT_PATH";
+
+/// File name of the global LCZ map in every default location
+const WUDAPT_FILE_NAME: &str = "wudapt_lcz_global.tif";
+
+/// Default locations where the `download_wudapt` tool places the map
+pub fn default_locations() -> Vec<PathBuf> {
+    let current_dir = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
+
+    vec![
+        current_dir.join(WUDAPT_FILE_NAME),
+        current_dir.join("data").join(WUDAPT_FILE_NAME),
+        PathBuf::from("/tmp").join(WUDAPT_FILE_NAME),
+        dirs::home_dir()
+            .unwrap_or_default()
+            .join(".cache")
+            .join("urban_classifier")
+            .join(WUDAPT_FILE_NAME),
+    ]
+}
+
+/// All locations searched by `from_default_locations`, in order
+///
+/// 1. The path in `URBAN_CLASSIFIER_WUDAPT_PATH`, if set
+/// 2. The user cache directory (`$XDG_CACHE_HOME/urban_classifier` on Linux)
+/// 3. `UrbanClassifier::default_data_path()`
+/// 4. The download tool's `default_locations()`
+pub fn search_paths() -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    if let Some(path) = std::env::var_os(WUDAPT_PATH_ENV).filter(|p| !p.is_empty()) {
+        paths.push(PathBuf::from(path));
+    }
+    if let Some(cache) = dirs::cache_dir() {
+        paths.push(cache.join("urban_classifier").join(WUDAPT_FILE_NAME));
+    }
+    paths.push(UrbanClassifier::default_data_path());
+    paths.extend(default_locations());
+
+    let mut seen = std::collections::HashSet::new();
+    paths.retain(|path| seen.insert(path.clone()));
+    paths
+}
+
+/// Download a file, reporting progress as (bytes downloaded, total bytes if known)
+///
+/// Parent directories are created as needed. The file only appears at
+/// `output_path` once the download has completed.
+///
+/// # Returns
+/// Size of the downloaded file in bytes
+pub fn download_file<F>(url: &str, output_path: &Path, on_progress: F) -> Result<u64>
+where
+    F: FnMut(u64, Option<u64>),
+{
+    download_file_verified(url, output_path, |_| Ok(()), on_progress)
+}
+
+/// Download a file, checking it with `verify` before it replaces `output_path`
+///
+/// As `download_file`. `verify` is called on the completed temporary file;
+/// if it fails, the temporary file is removed and any existing file at
+/// `output_path` is left untouched.
+///
+/// # Examples
+/// ```no_run
+/// use std::path::Path;
+/// use urban_classifier::download::{download_file_verified, verify_geotiff, WUDAPT_URLS};
+///
+/// let (_, url) = WUDAPT_URLS[0];
+/// download_file_verified(url, Path::new("lcz.tif"), verify_geotiff, |_, _| {})?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn download_file_verified<V, F>(
+    url: &str,
+    output_path: &Path,
+    verify: V,
+    mut on_progress: F,
+) -> Result<u64>
+where
+    V: Fn(&Path) -> Result<()>,
+    F: FnMut(u64, Option<u64>),
+{
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // No overall timeout: the global map is several gigabytes
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(30))
+        .timeout(None)
+        .build()
+        .map_err(|e| download_error(url, e, false))?;
+
+    // Errors other than building the request are connection failures or timeouts
+    let mut response = client
+        .get(url)
+        .send()
+        .map_err(|e| download_error(url, &e, !e.is_builder()))?;
+    let status = response.status();
+    if !status.is_success() {
+        let transient = status.is_server_error()
+            || status == StatusCode::TOO_MANY_REQUESTS
+            || status == StatusCode::REQUEST_TIMEOUT;
+        return Err(download_error(url, format!("HTTP {}", status), transient));
+    }
+    let total_size = response.content_length();
+
+    let part_path = part_path(output_path);
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&part_path)?);
+        let mut downloaded = 0u64;
+        let mut buffer = [0; 8192];
+
+        loop {
+            let n = response.read(&mut buffer)?;
+            if n == 0 {
+                break;
+            }
+            writer.write_all(&buffer[..n])?;
+            downloaded += n as u64;
+            on_progress(downloaded, total_size);
+        }
+        writer.flush()?;
+        drop(writer);
+
+        if downloaded == 0 {
+            return Err(download_error(url, "downloaded file is empty", false));
+        }
+        verify(&part_path)?;
+        Ok(downloaded)
+    })();
+
+    match result {
+        Ok(size) => {
+            fs::rename(&part_path, output_path)?;
+            Ok(size)
+        }
+        Err(e) => {
+            let _ = fs::remove_file(&part_path);
+            Err(e)
+        }
+    }
+}
+
+/// Run `operation` until it succeeds, fails with a permanent error, or has
+/// been tried `attempts` times
+///
+/// The operation always runs at least once. The wait between attempts starts
+/// at one second and doubles after each retryable failure.
+///
+/// # Examples
+/// ```no_run
+/// use std::path::Path;
+/// use urban_classifier::download::{download_file, with_retries, DOWNLOAD_ATTEMPTS, WUDAPT_URLS};
+///
+/// let (_, url) = WUDAPT_URLS[0];
+/// let size = with_retries(DOWNLOAD_ATTEMPTS, || {
+///     download_file(url, Path::new("lcz.tif"), |_, _| {})
+/// })?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn with_retries<T>(attempts: u32, mut operation: impl FnMut() -> Result<T>) -> Result<T> {
+    let mut delay = Duration::from_secs(1);
+    for _ in 1..attempts {
+        match operation() {
+            Err(e) if e.is_retryable() => {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+            outcome => return outcome,
+        }
+    }
+    operation()
+}
+
+/// Check that a file is non-empty and starts with a TIFF signature
+pub fn verify_geotiff(path: &Path) -> Result<()> {
+    let mut header = [0u8; 4];
+    File::open(path)?
+        .read_exact(&mut header)
+        .map_err(|_| invalid_file(path, "file is empty or truncated"))?;
+
+    // TIFF files start with either "II*\0" (little-endian) or "MM\0*" (big-endian);
+    // BigTIFF uses 0x2B in place of 0x2A
+    match header {
+        [0x49, 0x49, 0x2A | 0x2B, 0x00] | [0x4D, 0x4D, 0x00, 0x2A | 0x2B] => Ok(()),
+        _ => Err(invalid_file(path, "file does not appear to be a TIFF")),
+    }
+}
+
+/// Mirrors of version `version` (e.g. `"v3"`) of the global LCZ map, in
+/// order of preference
+///
+/// The version is the suffix of the mirror names in `WUDAPT_URLS`; unknown
+/// versions have no mirrors.
+pub fn version_urls(version: &str) -> Vec<&'static str> {
+    WUDAPT_URLS
+        .iter()
+        .filter(|(name, _)| name.rsplit('-').next() == Some(version))
+        .map(|(_, url)| *url)
+        .collect()
+}
+
+/// Make sure a verified copy of the global LCZ map exists at `path`
+///
+/// An existing file that passes verification is reused; otherwise each
+/// WUDAPT mirror is tried in turn.
+pub fn ensure_wudapt(path: &Path) -> Result<()> {
+    if path.exists() && verify_geotiff(path).is_ok() {
+        return Ok(());
+    }
+
+    let urls: Vec<&str> = WUDAPT_URLS.iter().map(|(_, url)| *url).collect();
+    download_first(&urls, path)
+}
+
+/// Download version `version` (e.g. `"v3"`) of the global LCZ map to `path`
+///
+/// An existing file that passes verification is reused unless `force` is
+/// set. A forced download only replaces the existing file once it has
+/// completed and passed verification.
+///
+/// # Errors
+/// Returns `ClassifierError::InvalidParameter` for versions without a
+/// mirror in `WUDAPT_URLS`, and the last mirror's error if every download
+/// fails.
+pub fn fetch_wudapt(version: &str, path: &Path, force: bool) -> Result<()> {
+    let urls = version_urls(version);
+    if urls.is_empty() {
+        let mut known: Vec<&str> = WUDAPT_URLS
+            .iter()
+            .filter_map(|(name, _)| name.rsplit('-').next())
+            .collect();
+        known.dedup();
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "Unknown WUDAPT map version '{}': expected one of {}",
+                version,
+                known.join(", ")
+            ),
+        });
+    }
+    if !force && path.exists() && verify_geotiff(path).is_ok() {
+        return Ok(());
+    }
+
+    download_first(&urls, path)
+}
+
+/// Download from the first of `urls` that yields a verified GeoTIFF
+///
+/// Downloads are verified before replacing `path`, so a failed forced
+/// download keeps the existing copy.
+fn download_first(urls: &[&str], path: &Path) -> Result<()> {
+    let mut last_error = None;
+    for url in urls {
+        let outcome = with_retries(DOWNLOAD_ATTEMPTS, || {
+            download_file_verified(url, path, verify_geotiff, |_, _| {})
+        });
+        match outcome {
+            Ok(_) => return Ok(()),
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    Err(last_error.expect("urls is not empty"))
+}
+
+impl UrbanClassifier {
+    /// Open the global LCZ map at `default_data_path()`, downloading it first if needed
+    ///
+    /// The first call downloads several gigabytes and blocks until complete;
+    /// later calls reuse the cached file.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::with_auto_download()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn with_auto_download() -> Result<Self> {
+        let path = Self::default_data_path();
+        ensure_wudapt(&path)?;
+        Self::new(path)
+    }
+
+    /// Open the first LCZ map found in the default locations
+    ///
+    /// See `search_paths()` for the search order.
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::MapNotFound` listing every searched path if
+    /// none of them exists.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// std::env::set_var("URBAN_CLASSIFIER_WUDAPT_PATH", "/data/lcz/wudapt_v3.tif");
+    /// let classifier = UrbanClassifier::from_default_locations()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn from_default_locations() -> Result<Self> {
+        let searched = search_paths();
+        match searched.iter().find(|path| path.is_file()) {
+            Some(path) => Self::new(path),
+            None => Err(ClassifierError::MapNotFound {
+                searched: searched
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect(),
+            }),
+        }
+    }
+}
+
+/// Temporary path used while a download is in progress
+fn part_path(output_path: &Path) -> PathBuf {
+    let mut name = output_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    output_path.with_file_name(name)
+}
+
+fn download_error(url: &str, message: impl std::fmt::Display, retryable: bool) -> ClassifierError {
+    ClassifierError::Download {
+        url: url.to_string(),
+        message: message.to_string(),
+        retryable,
+    }
+}
+
+fn invalid_file(path: &Path, message: &str) -> ClassifierError {
+    ClassifierError::UnsupportedRaster {
+        message: format!("{}: {}", path.display(), message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test TIFF signature verification
+    #[test]
+    fn test_verify_geotiff() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let tiff = dir.path().join("lcz.tif");
+        fs::write(&tiff, [0x49, 0x49, 0x2A, 0x00, 0x08]).unwrap();
+        assert!(verify_geotiff(&tiff).is_ok());
+
+        let html = dir.path().join("error.tif");
+        fs::write(&html, b"<html>Not found</html>").unwrap();
+        assert!(verify_geotiff(&html).is_err());
+
+        let empty = dir.path().join("empty.tif");
+        fs::write(&empty, b"").unwrap();
+        assert!(verify_geotiff(&empty).is_err());
+    }
+
+    /// Test that the environment variable is searched first and paths are unique
+    #[test]
+    fn test_search_paths() {
+        std::env::set_var(WUDAPT_PATH_ENV, "/data/custom_lcz.tif");
+        let paths = search_paths();
+        std::env::remove_var(WUDAPT_PATH_ENV);
+
+        assert_eq!(paths[0], PathBuf::from("/data/custom_lcz.tif"));
+        assert!(paths.contains(&UrbanClassifier::default_data_path()));
+        let unique: std::collections::HashSet<_> = paths.iter().collect();
+        assert_eq!(unique.len(), paths.len());
+    }
+
+    /// Test that only retryable errors are retried
+    #[test]
+    fn test_with_retries() {
+        let mut calls = 0;
+        let outcome: Result<()> = with_retries(3, || {
+            calls += 1;
+            Err(download_error("https://example.com", "HTTP 404", false))
+        });
+        assert!(outcome.is_err());
+        assert_eq!(calls, 1);
+
+        let mut calls = 0;
+        let outcome = with_retries(2, || {
+            calls += 1;
+            if calls == 1 {
+                Err(download_error("https://example.com", "HTTP 503", true))
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(outcome.unwrap(), 2);
+    }
+
+    /// Test mirror selection by map version
+    #[test]
+    fn test_fetch_wudapt_versions() {
+        assert_eq!(version_urls("v3").len(), 2);
+        assert_eq!(version_urls("v2"), vec![WUDAPT_URLS[2].1]);
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lcz.tif");
+        assert!(matches!(
+            fetch_wudapt("v9", &path, false),
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+
+        // A verified copy is reused without downloading
+        fs::write(&path, [0x49, 0x49, 0x2A, 0x00, 0x08]).unwrap();
+        assert!(fetch_wudapt("v3", &path, false).is_ok());
+    }
+
+    /// Test that a download failing verification leaves the existing file
+    #[test]
+    fn test_download_file_verified() {
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/lcz.tif", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0; 1024];
+            let _ = stream.read(&mut request).unwrap();
+            let body = b"<html>Not found</html>";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            stream.write_all(body).unwrap();
+        });
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lcz.tif");
+        let existing = [0x49, 0x49, 0x2A, 0x00, 0x08];
+        fs::write(&path, existing).unwrap();
+
+        let outcome = download_file_verified(&url, &path, verify_geotiff, |_, _| {});
+        server.join().unwrap();
+        assert!(matches!(
+            outcome,
+            Err(ClassifierError::UnsupportedRaster { .. })
+        ));
+        assert_eq!(fs::read(&path).unwrap(), existing);
+        assert!(!part_path(&path).exists());
+    }
+
+    /// Test that in-progress downloads use a sibling file
+    #[test]
+    fn test_part_path() {
+        assert_eq!(
+            part_path(Path::new("/data/wudapt_lcz_global.tif")),
+            PathBuf::from("/data/wudapt_lcz_global.tif.part")
+        );
+    }
+}
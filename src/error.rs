@@ -6,18 +6,61 @@
 //! # Error Categories
 //!
 //! - **File Operations**: File not found errors
-//! - **GDAL Operations**: GeoTIFF reading and raster operations
+//! - **GDAL Operations**: GeoTIFF reading and raster operations. Errors raised
+//!   by GDAL keep the original error as their `source()`, and
+//!   `ClassifierError::gdal_error_number` returns its `CPLE_*` number
 //! - **Data Validation**: DataFrame schema and coordinate validation
 //! - **Spatial Operations**: Coordinate transformation and raster sampling
+//! - **Batch Failures**: Every failing station of a collect-all run
 //! - **External Libraries**: Wrapper errors for Polars and I/O operations
+//!
+//! `ClassifierError::is_retryable` separates transient network failures from
+//! permanent errors, for retry policies in services and the download tool.
+//!
+//! # Station Context
+//!
+//! Coordinate, transform, bounds, sampling and unexpected value errors raised
+//! while classifying a DataFrame carry the `station_id` and `row_index` of the
+//! failing station.
+//! The same errors from lower-level functions, such as `spatial::transform_coordinate`,
+//! leave both fields `None`.
+//!
+//! # Error Codes
+//!
+//! `ClassifierError::code` returns a stable machine-readable code for each
+//! variant, such as `E_FILE_NOT_FOUND`. Codes never change between releases,
+//! unlike messages, so services and Python callers should branch on them.
+//! The enum is `#[non_exhaustive]`: new variants come with new codes, and
+//! matches outside this crate need a wildcard arm.
+//!
+//! # Reports
+//!
+//! `ClassifierError::to_report` converts an error into an `ErrorReport`,
+//! which implements `Serialize` so that services and batch jobs can persist
+//! failures as JSON. Data-quality warnings serialize directly, see
+//! `warnings::ClassificationWarnings`.
 
+use crate::columns::suggest_column;
+use crate::failures::StationError;
+use crate::spatial::BoundingBox;
+use serde::Serialize;
 use thiserror::Error;
 
 /// Type alias for Results with ClassifierError
 pub type Result<T> = std::result::Result<T, ClassifierError>;
 
+/// Underlying error kept by `ClassifierError::GdalError`
+#[cfg(feature = "gdal")]
+pub type GdalSourceError = gdal::errors::GdalError;
+
+/// Underlying error kept by `ClassifierError::GdalError`; never present
+/// without the `gdal` feature
+#[cfg(not(feature = "gdal"))]
+pub type GdalSourceError = std::convert::Infallible;
+
 /// Main error type for the urban classifier library
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum ClassifierError {
     /// The specified file path does not exist
     #[error("File not found: {path}")]
@@ -25,26 +68,81 @@ pub enum ClassifierError {
 
     /// GDAL library error during GeoTIFF operations
     #[error("GDAL error: {message}")]
-    GdalError { message: String },
+    GdalError {
+        message: String,
+        /// The original GDAL error, when the error was raised by GDAL
+        #[source]
+        source: Option<GdalSourceError>,
+    },
 
     /// Required column missing from input DataFrame
-    #[error("Column not found in DataFrame: {column}")]
-    ColumnNotFound { column: String },
+    #[error(
+        "Column not found in DataFrame: {column}{}",
+        describe_columns(.column, .available)
+    )]
+    ColumnNotFound {
+        column: String,
+        /// Columns the DataFrame does have
+        available: Vec<String>,
+    },
 
     /// Invalid geographic coordinates (outside valid range)
-    #[error("Invalid coordinate: lon={lon}, lat={lat}")]
-    InvalidCoordinate { lon: f64, lat: f64 },
+    #[error(
+        "Invalid coordinate{}: lon={lon}, lat={lat}",
+        describe_station(.station_id, .row_index)
+    )]
+    InvalidCoordinate {
+        lon: f64,
+        lat: f64,
+        station_id: Option<String>,
+        row_index: Option<usize>,
+    },
 
     /// Failed to transform coordinates between spatial reference systems
-    #[error("Coordinate transformation failed: {message}")]
-    CoordinateTransform { message: String },
+    #[error(
+        "Coordinate transformation failed{}: {message}",
+        describe_station(.station_id, .row_index)
+    )]
+    CoordinateTransform {
+        message: String,
+        station_id: Option<String>,
+        row_index: Option<usize>,
+    },
 
     /// Failed to sample raster value at specified pixel location
-    #[error("Raster sampling failed at pixel ({pixel}, {line}): {message}")]
+    #[error(
+        "Raster sampling failed at pixel ({pixel}, {line}){}: {message}",
+        describe_station(.station_id, .row_index)
+    )]
     RasterSampling {
         pixel: isize,
         line: isize,
         message: String,
+        station_id: Option<String>,
+        row_index: Option<usize>,
+    },
+
+    /// A station was located outside the raster
+    #[error(
+        "Coordinate lon={lon}, lat={lat}{} is outside the raster (pixel ({pixel}, {line})); \
+         the raster covers lon {:.4} to {:.4}, lat {:.4} to {:.4}",
+        describe_station(.station_id, .row_index),
+        .raster_extent.min_lon,
+        .raster_extent.max_lon,
+        .raster_extent.min_lat,
+        .raster_extent.max_lat
+    )]
+    OutOfBounds {
+        /// WGS84 coordinate of the station
+        lon: f64,
+        lat: f64,
+        /// Pixel the station was located at
+        pixel: isize,
+        line: isize,
+        /// WGS84 extent of the raster
+        raster_extent: BoundingBox,
+        station_id: Option<String>,
+        row_index: Option<usize>,
     },
 
     /// DataFrame does not meet required schema specifications
@@ -55,6 +153,61 @@ pub enum ClassifierError {
     #[error("Override application failed for station {station_id}: {message}")]
     OverrideApplication { station_id: String, message: String },
 
+    /// Sampled raster value is not a standard LCZ code (strict validation),
+    /// or not an integer from 0 to 255 in a band of a wider data type
+    #[error(
+        "Unexpected raster value {value}{}: expected an LCZ code 1-17",
+        describe_station(.station_id, .row_index)
+    )]
+    UnexpectedRasterValue {
+        value: f64,
+        station_id: Option<String>,
+        row_index: Option<usize>,
+    },
+
+    /// Raster file uses a layout or CRS the selected backend cannot read
+    #[error("Unsupported raster: {message}")]
+    UnsupportedRaster { message: String },
+
+    /// No LCZ map was found in any of the default locations
+    #[error(
+        "WUDAPT LCZ map not found. Searched: {}. Set URBAN_CLASSIFIER_WUDAPT_PATH or run download_wudapt",
+        .searched.join(", ")
+    )]
+    MapNotFound { searched: Vec<String> },
+
+    /// Downloading the LCZ map failed
+    #[error("Download from {url} failed: {message}")]
+    Download {
+        url: String,
+        message: String,
+        /// Whether the failure looks transient, e.g. a timeout or HTTP 503
+        retryable: bool,
+    },
+
+    /// Configuration file could not be read or is invalid
+    #[error("Invalid configuration: {message}")]
+    Config { message: String },
+
+    /// String coordinates that could not be parsed, as (row, value) pairs
+    #[error(
+        "Could not parse {} value(s) in coordinate column '{column}': {}",
+        .rows.len(),
+        describe_rows(.rows)
+    )]
+    CoordinateParse {
+        column: String,
+        rows: Vec<(usize, String)>,
+    },
+
+    /// A caller-supplied parameter is outside its valid range
+    #[error("Invalid parameter: {message}")]
+    InvalidParameter { message: String },
+
+    /// Several stations could not be classified, each with its own cause
+    #[error("{}", describe_failures(.0))]
+    Multiple(Vec<StationError>),
+
     /// Wrapper for Polars DataFrame errors
     #[error("Polars error: {0}")]
     Polars(#[from] polars::error::PolarsError),
@@ -64,10 +217,362 @@ pub enum ClassifierError {
     Io(#[from] std::io::Error),
 }
 
+/// GDAL error number of failed HTTP requests (`CPLE_HttpResponse`)
+const CPLE_HTTP_RESPONSE: i32 = 11;
+
+/// GDAL error number of other cloud storage failures (`CPLE_AWSError`)
+const CPLE_AWS_ERROR: i32 = 17;
+
+/// Serializable summary of an error, for structured failure reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ErrorReport {
+    /// Stable error code, see `ClassifierError::code`
+    pub code: &'static str,
+    /// Human-readable message
+    pub message: String,
+    /// ID of the station the error occurred for, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub station_id: Option<String>,
+    /// Zero-based input row the error occurred for, if known
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub row_index: Option<usize>,
+    /// Whether retrying may succeed, see `ClassifierError::is_retryable`
+    pub retryable: bool,
+    /// GDAL error number, see `ClassifierError::gdal_error_number`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gdal_error_number: Option<i32>,
+    /// One report per failing station of a `Multiple` error
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<ErrorReport>,
+}
+
+impl ClassifierError {
+    /// Stable machine-readable code of the error variant, e.g. `E_FILE_NOT_FOUND`
+    pub fn code(&self) -> &'static str {
+        match self {
+            ClassifierError::FileNotFound { .. } => "E_FILE_NOT_FOUND",
+            ClassifierError::GdalError { .. } => "E_GDAL",
+            ClassifierError::ColumnNotFound { .. } => "E_COLUMN_NOT_FOUND",
+            ClassifierError::InvalidCoordinate { .. } => "E_INVALID_COORDINATE",
+            ClassifierError::CoordinateTransform { .. } => "E_COORDINATE_TRANSFORM",
+            ClassifierError::RasterSampling { .. } => "E_RASTER_SAMPLING",
+            ClassifierError::OutOfBounds { .. } => "E_OUT_OF_BOUNDS",
+            ClassifierError::SchemaValidation { .. } => "E_SCHEMA_VALIDATION",
+            ClassifierError::OverrideApplication { .. } => "E_OVERRIDE_APPLICATION",
+            ClassifierError::UnexpectedRasterValue { .. } => "E_UNEXPECTED_RASTER_VALUE",
+            ClassifierError::UnsupportedRaster { .. } => "E_UNSUPPORTED_RASTER",
+            ClassifierError::MapNotFound { .. } => "E_MAP_NOT_FOUND",
+            ClassifierError::Download { .. } => "E_DOWNLOAD",
+            ClassifierError::Config { .. } => "E_CONFIG",
+            ClassifierError::CoordinateParse { .. } => "E_COORDINATE_PARSE",
+            ClassifierError::InvalidParameter { .. } => "E_INVALID_PARAMETER",
+            ClassifierError::Multiple(_) => "E_MULTIPLE",
+            ClassifierError::Polars(_) => "E_POLARS",
+            ClassifierError::Io(_) => "E_IO",
+        }
+    }
+
+    /// Attach the failing station and its input row to a coordinate,
+    /// transform or sampling error; other errors are returned unchanged
+    pub fn with_station(mut self, id: &str, row: usize) -> Self {
+        if let Some((station_id, row_index)) = self.station_context_mut() {
+            *station_id = Some(id.to_string());
+            *row_index = Some(row);
+        }
+        self
+    }
+
+    /// Attach the failing input row when the station ID is not known
+    pub fn with_row(mut self, row: usize) -> Self {
+        if let Some((_, row_index)) = self.station_context_mut() {
+            *row_index = Some(row);
+        }
+        self
+    }
+
+    /// Summarise the error as a serializable report
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::ClassifierError;
+    ///
+    /// let error = ClassifierError::ColumnNotFound {
+    ///     column: "lat".to_string(),
+    ///     available: Vec::new(),
+    /// };
+    /// let json = serde_json::to_string(&error.to_report())?;
+    /// assert_eq!(
+    ///     json,
+    ///     r#"{"code":"E_COLUMN_NOT_FOUND","message":"Column not found in DataFrame: lat","retryable":false}"#
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_report(&self) -> ErrorReport {
+        let failures = match self {
+            ClassifierError::Multiple(errors) => {
+                errors.iter().map(StationError::to_report).collect()
+            }
+            _ => Vec::new(),
+        };
+        ErrorReport {
+            code: self.code(),
+            message: self.to_string(),
+            station_id: self.station_id().map(str::to_string),
+            row_index: self.row_index(),
+            retryable: self.is_retryable(),
+            gdal_error_number: self.gdal_error_number(),
+            failures,
+        }
+    }
+
+    /// Whether the error may go away if the operation is retried
+    ///
+    /// True for network failures: transient download errors, HTTP and cloud
+    /// storage errors reported by GDAL for remote rasters, and timeouts or
+    /// dropped connections. Schema, coordinate and data errors are
+    /// permanent. A `Multiple` error is retryable when every failure is.
+    pub fn is_retryable(&self) -> bool {
+        use std::io::ErrorKind;
+
+        match self {
+            ClassifierError::Download { retryable, .. } => *retryable,
+            ClassifierError::GdalError { .. } => matches!(
+                self.gdal_error_number(),
+                Some(CPLE_HTTP_RESPONSE | CPLE_AWS_ERROR)
+            ),
+            ClassifierError::Io(e) => matches!(
+                e.kind(),
+                ErrorKind::TimedOut
+                    | ErrorKind::ConnectionReset
+                    | ErrorKind::ConnectionAborted
+                    | ErrorKind::Interrupted
+                    | ErrorKind::UnexpectedEof
+            ),
+            ClassifierError::Multiple(errors) => {
+                !errors.is_empty() && errors.iter().all(|error| error.cause.is_retryable())
+            }
+            _ => false,
+        }
+    }
+
+    /// GDAL error number (`CPLE_*`) of an error raised by GDAL
+    ///
+    /// Distinguishes, for example, HTTP failures of remote rasters
+    /// (`CPLE_HttpResponse`) from files GDAL cannot parse (`CPLE_AppDefined`).
+    pub fn gdal_error_number(&self) -> Option<i32> {
+        match self {
+            #[cfg(feature = "gdal")]
+            ClassifierError::GdalError {
+                source: Some(gdal::errors::GdalError::CplError { number, .. }),
+                ..
+            } => Some(*number),
+            _ => None,
+        }
+    }
+
+    /// ID of the station the error occurred for, if recorded
+    pub fn station_id(&self) -> Option<&str> {
+        match self {
+            ClassifierError::InvalidCoordinate { station_id, .. }
+            | ClassifierError::CoordinateTransform { station_id, .. }
+            | ClassifierError::RasterSampling { station_id, .. }
+            | ClassifierError::OutOfBounds { station_id, .. }
+            | ClassifierError::UnexpectedRasterValue { station_id, .. } => station_id.as_deref(),
+            ClassifierError::OverrideApplication { station_id, .. } => Some(station_id),
+            _ => None,
+        }
+    }
+
+    /// Zero-based input row the error occurred for, if recorded
+    pub fn row_index(&self) -> Option<usize> {
+        match self {
+            ClassifierError::InvalidCoordinate { row_index, .. }
+            | ClassifierError::CoordinateTransform { row_index, .. }
+            | ClassifierError::RasterSampling { row_index, .. }
+            | ClassifierError::OutOfBounds { row_index, .. }
+            | ClassifierError::UnexpectedRasterValue { row_index, .. } => *row_index,
+            _ => None,
+        }
+    }
+
+    fn station_context_mut(&mut self) -> Option<(&mut Option<String>, &mut Option<usize>)> {
+        match self {
+            ClassifierError::InvalidCoordinate {
+                station_id,
+                row_index,
+                ..
+            }
+            | ClassifierError::CoordinateTransform {
+                station_id,
+                row_index,
+                ..
+            }
+            | ClassifierError::RasterSampling {
+                station_id,
+                row_index,
+                ..
+            }
+            | ClassifierError::OutOfBounds {
+                station_id,
+                row_index,
+                ..
+            }
+            | ClassifierError::UnexpectedRasterValue {
+                station_id,
+                row_index,
+                ..
+            } => Some((station_id, row_index)),
+            _ => None,
+        }
+    }
+}
+
+/// Describe the station of an error, e.g. " for station A (row 3)"
+fn describe_station(station_id: &Option<String>, row_index: &Option<usize>) -> String {
+    match (station_id, row_index) {
+        (Some(id), Some(row)) => format!(" for station {} (row {})", id, row),
+        (Some(id), None) => format!(" for station {}", id),
+        (None, Some(row)) => format!(" for row {}", row),
+        (None, None) => String::new(),
+    }
+}
+
+/// List the available columns of a `ColumnNotFound` error with a suggestion,
+/// e.g. ". Available columns: lat, lng. Did you mean 'lng'?"
+fn describe_columns(column: &str, available: &[String]) -> String {
+    if available.is_empty() {
+        return String::new();
+    }
+    let mut described = format!(". Available columns: {}", available.join(", "));
+    if let Some(suggestion) = suggest_column(column, available) {
+        described.push_str(&format!(". Did you mean '{}'?", suggestion));
+    }
+    described
+}
+
+/// Count the failing stations of a `Multiple` error and list each on its own line
+pub(crate) fn describe_failures(errors: &[StationError]) -> String {
+    let mut described = format!("{} station(s) could not be classified", errors.len());
+    for error in errors {
+        described.push_str(&format!("\n  {}", error));
+    }
+    described
+}
+
+/// List the first few failing rows of a `CoordinateParse` error
+fn describe_rows(rows: &[(usize, String)]) -> String {
+    const SHOWN: usize = 5;
+    let mut described: Vec<String> = rows
+        .iter()
+        .take(SHOWN)
+        .map(|(row, value)| format!("row {} ({:?})", row, value))
+        .collect();
+    if rows.len() > SHOWN {
+        described.push(format!("and {} more", rows.len() - SHOWN));
+    }
+    described.join(", ")
+}
+
+#[cfg(feature = "gdal")]
 impl From<gdal::errors::GdalError> for ClassifierError {
     fn from(err: gdal::errors::GdalError) -> Self {
         ClassifierError::GdalError {
             message: err.to_string(),
+            source: Some(err),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that codes are distinct and independent of the error's fields
+    #[test]
+    fn test_error_codes() {
+        let errors = [
+            ClassifierError::FileNotFound {
+                path: "a.tif".to_string(),
+            },
+            ClassifierError::ColumnNotFound {
+                column: "lon".to_string(),
+                available: vec!["lng".to_string()],
+            },
+            ClassifierError::InvalidCoordinate {
+                lon: 200.0,
+                lat: 0.0,
+                station_id: None,
+                row_index: None,
+            },
+            ClassifierError::InvalidCoordinate {
+                lon: 0.0,
+                lat: 100.0,
+                station_id: Some("A".to_string()),
+                row_index: Some(3),
+            },
+            ClassifierError::Multiple(Vec::new()),
+        ];
+        let codes: Vec<&str> = errors.iter().map(ClassifierError::code).collect();
+        assert_eq!(
+            codes,
+            vec![
+                "E_FILE_NOT_FOUND",
+                "E_COLUMN_NOT_FOUND",
+                "E_INVALID_COORDINATE",
+                "E_INVALID_COORDINATE",
+                "E_MULTIPLE",
+            ]
+        );
+    }
+
+    /// Test that GDAL errors keep their source and error number
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_gdal_error_source() {
+        use std::error::Error as _;
+
+        let error = ClassifierError::from(gdal::errors::GdalError::CplError {
+            class: gdal_sys::CPLErr::CE_Failure,
+            number: 11,
+            msg: "HTTP response code: 503".to_string(),
+        });
+        assert_eq!(error.gdal_error_number(), Some(11));
+        assert!(error.source().is_some());
+        assert!(error.is_retryable());
+        assert_eq!(error.to_report().gdal_error_number, Some(11));
+
+        let error = ClassifierError::GdalError {
+            message: "GeoTIFF file contains no raster bands".to_string(),
+            source: None,
+        };
+        assert_eq!(error.gdal_error_number(), None);
+        assert!(error.source().is_none());
+        assert!(!error.is_retryable());
+    }
+
+    /// Test that reports carry station context and nest the failures of `Multiple`
+    #[test]
+    fn test_error_report() {
+        let failure = StationError {
+            station_id: "B".to_string(),
+            row: 2,
+            cause: ClassifierError::InvalidCoordinate {
+                lon: 540.0,
+                lat: 51.5,
+                station_id: None,
+                row_index: None,
+            },
+        };
+        let report = ClassifierError::Multiple(vec![failure]).to_report();
+        assert_eq!(report.code, "E_MULTIPLE");
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].code, "E_INVALID_COORDINATE");
+        assert_eq!(report.failures[0].station_id.as_deref(), Some("B"));
+        assert_eq!(report.failures[0].row_index, Some(2));
+        assert!(!report.retryable);
+
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["failures"][0]["station_id"], "B");
+        assert!(json.get("station_id").is_none());
+    }
+}
@@ -0,0 +1,99 @@
+//! Region Export
+//!
+//! The global LCZ map is several gigabytes, which makes it awkward to share the
+//! exact data behind a study. `UrbanClassifier::export_region` writes the part
+//! of the map covering a WGS84 bounding box to a small GeoTIFF in the raster's
+//! own CRS and resolution, so an extract can accompany a paper or a bug report
+//! and be opened with `UrbanClassifier::new` like the full map.
+//!
+//! Raw values are written unchanged: the code mapping and nodata override of
+//! the classifier are not applied, but the effective nodata value is recorded
+//! in the output file.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::source::PixelLocator;
+use crate::spatial::BoundingBox;
+use gdal::raster::{Buffer, RasterCreationOption};
+use gdal::DriverManager;
+use std::path::Path;
+
+/// Creation options of exported GeoTIFFs: tiled and losslessly compressed
+const CREATION_OPTIONS: [RasterCreationOption<'static>; 2] = [
+    RasterCreationOption {
+        key: "COMPRESS",
+        value: "DEFLATE",
+    },
+    RasterCreationOption {
+        key: "TILED",
+        value: "YES",
+    },
+];
+
+impl UrbanClassifier {
+    /// Write the part of the LCZ map covering `bbox` to a GeoTIFF at `path`
+    ///
+    /// The exported window is the pixel window covering the box, clipped to
+    /// the raster extent, so every pixel of the source whose area overlaps the
+    /// box is included. Works for classifiers created from any raster source.
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::InvalidParameter` if the box does not overlap
+    /// the raster, and a GDAL error if the file cannot be written.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::spatial::BoundingBox;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let greater_london = BoundingBox::new(-0.51, 51.28, 0.33, 51.69)?;
+    /// classifier.export_region(&greater_london, "london_lcz.tif")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn export_region<P: AsRef<Path>>(&self, bbox: &BoundingBox, path: P) -> Result<()> {
+        let metadata = self.source_metadata();
+        let (pixel, line, width, height) = PixelLocator::new(metadata)?.bbox_window(bbox)?;
+        let window = self.read_window(pixel, line, width, height)?;
+        if window.data.is_empty() {
+            return Err(ClassifierError::InvalidParameter {
+                message: format!(
+                    "Bounding box ({}, {}, {}, {}) does not overlap the raster",
+                    bbox.min_lon, bbox.min_lat, bbox.max_lon, bbox.max_lat
+                ),
+            });
+        }
+
+        // Shift the origin to the top-left corner of the window
+        let gt = metadata.geo_transform;
+        let (col, row) = (window.x_off as f64, window.y_off as f64);
+        let geo_transform = [
+            gt[0] + col * gt[1] + row * gt[2],
+            gt[1],
+            gt[2],
+            gt[3] + col * gt[4] + row * gt[5],
+            gt[4],
+            gt[5],
+        ];
+
+        let driver = DriverManager::get_driver_by_name("GTiff")?;
+        let mut dataset = driver.create_with_band_type_with_options::<u8, _>(
+            path.as_ref(),
+            window.width as isize,
+            window.height as isize,
+            1,
+            &CREATION_OPTIONS,
+        )?;
+        dataset.set_geo_transform(&geo_transform)?;
+        dataset.set_spatial_ref(&self.raster_spatial_ref()?)?;
+
+        let size = (window.width, window.height);
+        let mut band = dataset.rasterband(1)?;
+        if let Some(nodata) = self.nodata_value() {
+            band.set_no_data_value(Some(f64::from(nodata)))?;
+        }
+        band.write((0, 0), size, &Buffer::new(size, window.data))?;
+
+        Ok(())
+    }
+}
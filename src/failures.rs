@@ -0,0 +1,267 @@
+//! Per-Station Failure Reports
+//!
+//! By default a classification run stops at the first station that cannot be
+//! classified. With `FailureMode::CollectAll`,
+//! `UrbanClassifier::run_classification_with_failures` instead classifies
+//! every other station, leaves the failing rows null and returns a
+//! `ClassificationErrors` report listing each failure with its station ID,
+//! row and cause.
+//!
+//! When failing fast, the returned error carries the station ID and row in its
+//! `station_id` and `row_index` fields where the error type has them.
+//!
+//! `ClassificationErrors::into_result` turns a non-empty report into a
+//! `ClassifierError::Multiple`, so batch tools can collect every failure and
+//! still propagate them with `?`, and `ClassificationErrors::to_reports`
+//! gives serializable reports of them for storing as JSON.
+//!
+//! Per-station failures are coordinates that cannot be located on the
+//! raster, raster reads that fail, and (with `ValidationMode::Strict`)
+//! unexpected codes and invalid overrides. Errors affecting the whole run,
+//! such as a missing column, are still returned immediately.
+
+use crate::error::{describe_failures, ClassifierError, ErrorReport, Result};
+use std::fmt;
+
+/// How a classification run reacts to a station that cannot be classified
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FailureMode {
+    /// Return the first station's error and discard the run
+    #[default]
+    FailFast,
+    /// Record every failing station and return null results for them
+    CollectAll,
+}
+
+/// A station that could not be classified
+#[derive(Debug)]
+pub struct StationError {
+    /// ID of the failing station
+    pub station_id: String,
+    /// Zero-based row of the station in the input DataFrame
+    pub row: usize,
+    /// Why the station could not be classified
+    pub cause: ClassifierError,
+}
+
+impl StationError {
+    /// Summarise the failure as a serializable report of its cause, with the
+    /// station's ID and row
+    pub fn to_report(&self) -> ErrorReport {
+        ErrorReport {
+            station_id: Some(self.station_id.clone()),
+            row_index: Some(self.row),
+            ..self.cause.to_report()
+        }
+    }
+}
+
+impl fmt::Display for StationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "station {} (row {}): {}",
+            self.station_id, self.row, self.cause
+        )
+    }
+}
+
+impl std::error::Error for StationError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+/// Every station that failed during one classification run, in row order
+#[derive(Debug, Default)]
+pub struct ClassificationErrors {
+    errors: Vec<StationError>,
+}
+
+impl ClassificationErrors {
+    /// True when every station was classified
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Number of failing stations
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// Iterate over the failures in row order
+    pub fn iter(&self) -> std::slice::Iter<'_, StationError> {
+        self.errors.iter()
+    }
+
+    /// The failures in row order
+    pub fn errors(&self) -> &[StationError] {
+        &self.errors
+    }
+
+    /// Take ownership of the failures
+    pub fn into_vec(self) -> Vec<StationError> {
+        self.errors
+    }
+
+    /// Serializable reports of the failures in row order
+    pub fn to_reports(&self) -> Vec<ErrorReport> {
+        self.errors.iter().map(StationError::to_report).collect()
+    }
+
+    /// `Ok` when every station was classified, otherwise every failure as
+    /// one `ClassifierError::Multiple`
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::failures::FailureMode;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.12, 540.0],
+    ///     "latitude" => [51.5, 51.47],
+    /// }?;
+    /// let (result, errors) = classifier.run_classification_with_failures(
+    ///     &stations, "station_id", "longitude", "latitude", None, FailureMode::CollectAll,
+    /// )?;
+    /// errors.into_result()?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn into_result(self) -> Result<()> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.into())
+        }
+    }
+}
+
+impl From<ClassificationErrors> for ClassifierError {
+    fn from(errors: ClassificationErrors) -> Self {
+        ClassifierError::Multiple(errors.errors)
+    }
+}
+
+impl fmt::Display for ClassificationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&describe_failures(&self.errors))
+    }
+}
+
+impl std::error::Error for ClassificationErrors {}
+
+impl IntoIterator for ClassificationErrors {
+    type Item = StationError;
+    type IntoIter = std::vec::IntoIter<StationError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ClassificationErrors {
+    type Item = &'a StationError;
+    type IntoIter = std::slice::Iter<'a, StationError>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+/// Collects station failures during a run according to a `FailureMode`
+pub(crate) struct FailureSink {
+    mode: FailureMode,
+    errors: ClassificationErrors,
+}
+
+impl FailureSink {
+    pub(crate) fn new(mode: FailureMode) -> Self {
+        FailureSink {
+            mode,
+            errors: ClassificationErrors::default(),
+        }
+    }
+
+    /// Record a failing station, or return its error when failing fast
+    pub(crate) fn record(
+        &mut self,
+        station_id: &str,
+        row: usize,
+        cause: ClassifierError,
+    ) -> Result<()> {
+        match self.mode {
+            FailureMode::FailFast => Err(cause.with_station(station_id, row)),
+            FailureMode::CollectAll => {
+                self.errors.errors.push(StationError {
+                    station_id: station_id.to_string(),
+                    row,
+                    cause,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// Failures recorded so far, sorted by row
+    pub(crate) fn finish(mut self) -> ClassificationErrors {
+        self.errors.errors.sort_by_key(|error| error.row);
+        self.errors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out_of_bounds() -> ClassifierError {
+        ClassifierError::RasterSampling {
+            pixel: -1,
+            line: 0,
+            message: "out of bounds".to_string(),
+            station_id: None,
+            row_index: None,
+        }
+    }
+
+    /// Test that fail-fast returns the cause and collect-all records it
+    #[test]
+    fn test_failure_sink_modes() {
+        let mut sink = FailureSink::new(FailureMode::FailFast);
+        let error = sink.record("A", 4, out_of_bounds()).unwrap_err();
+        assert_eq!(
+            (error.station_id(), error.row_index()),
+            (Some("A"), Some(4))
+        );
+        assert_eq!(
+            error.to_string(),
+            "Raster sampling failed at pixel (-1, 0) for station A (row 4): out of bounds"
+        );
+        assert!(sink.finish().is_empty());
+
+        let mut sink = FailureSink::new(FailureMode::CollectAll);
+        sink.record("B", 3, out_of_bounds()).unwrap();
+        sink.record("A", 1, out_of_bounds()).unwrap();
+        let errors = sink.finish();
+        assert_eq!(errors.len(), 2);
+        let rows: Vec<usize> = errors.iter().map(|e| e.row).collect();
+        assert_eq!(rows, vec![1, 3]);
+        assert!(errors
+            .to_string()
+            .starts_with("2 station(s) could not be classified"));
+
+        let message = errors.to_string();
+        match errors.into_result() {
+            Err(ClassifierError::Multiple(failures)) => {
+                assert_eq!(failures.len(), 2);
+                assert_eq!(failures[0].station_id, "A");
+                assert_eq!(ClassifierError::Multiple(failures).to_string(), message);
+            }
+            other => panic!("expected Multiple, got {:?}", other),
+        }
+        assert!(ClassificationErrors::default().into_result().is_ok());
+    }
+}
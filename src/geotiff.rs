@@ -0,0 +1,461 @@
+//! Pure-Rust GeoTIFF Backend (`no-gdal` feature)
+//!
+//! Reads simple LCZ GeoTIFFs, including cloud-optimised GeoTIFFs, with the
+//! `tiff` crate and transforms coordinates with `proj4rs`, so classification
+//! does not go through GDAL at all. Open a classifier with
+//! `UrbanClassifier::from_geotiff`.
+//!
+//! # Supported Files
+//!
+//! - A single unsigned 8-bit band holding the LCZ codes, tiled or stripped
+//! - North-up georeferencing (`ModelPixelScale` + `ModelTiepoint`, or an
+//!   unrotated `ModelTransformation`)
+//! - EPSG:4326, WGS84 UTM zones (EPSG:32601–32660, 32701–32760), Web
+//!   Mercator (EPSG:3857) and ETRS89-LAEA Europe (EPSG:3035)
+//!
+//! Only the full-resolution image is read; overviews are ignored. Operations
+//! that need GDAL (overviews, additional bands, H3 aggregation, raster info)
+//! return an error for classifiers opened this way.
+//!
+//! GDAL itself is the default `gdal` feature. Build with
+//! `--no-default-features --features no-gdal` to drop it, and libgdal with it,
+//! entirely; the GDAL-only operations are then not compiled at all.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::source::{RasterCrs, RasterMetadata, RasterSource};
+use crate::spatial::{nodata_to_u8, PixelWindow};
+use std::fs::File;
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use tiff::decoder::{Decoder, DecodingResult};
+use tiff::tags::Tag;
+use tiff::ColorType;
+
+// GeoTIFF key IDs and values (OGC GeoTIFF 1.1, section 7)
+const GT_MODEL_TYPE_KEY: u16 = 1024;
+const GT_RASTER_TYPE_KEY: u16 = 1025;
+const GEOGRAPHIC_TYPE_KEY: u16 = 2048;
+const PROJECTED_CS_TYPE_KEY: u16 = 3072;
+const MODEL_TYPE_PROJECTED: u16 = 1;
+const MODEL_TYPE_GEOGRAPHIC: u16 = 2;
+const RASTER_PIXEL_IS_POINT: u16 = 2;
+
+/// GDAL's private TIFF tag holding the nodata value as ASCII text
+const GDAL_NODATA_TAG: u16 = 42113;
+
+type TiffDecoder = Decoder<BufReader<File>>;
+
+/// Raster source reading the LCZ band of a GeoTIFF without GDAL
+pub struct GeoTiffSource {
+    decoder: Mutex<TiffDecoder>,
+    metadata: RasterMetadata,
+}
+
+impl GeoTiffSource {
+    /// Open a GeoTIFF and read its georeferencing
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::UnsupportedRaster` if the file is not a
+    /// single-band 8-bit north-up GeoTIFF in one of the supported CRSs.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Err(ClassifierError::FileNotFound {
+                path: path.display().to_string(),
+            });
+        }
+
+        let mut decoder = Decoder::new(BufReader::new(File::open(path)?)).map_err(tiff_error)?;
+
+        match decoder.colortype().map_err(tiff_error)? {
+            ColorType::Gray(8) => {}
+            other => {
+                return Err(unsupported(format!(
+                    "expected a single 8-bit band, found {:?}",
+                    other
+                )))
+            }
+        }
+
+        let (width, height) = decoder.dimensions().map_err(tiff_error)?;
+        let keys = match decoder
+            .find_tag(Tag::GeoKeyDirectoryTag)
+            .map_err(tiff_error)?
+        {
+            Some(value) => value.into_u16_vec().map_err(tiff_error)?,
+            None => return Err(unsupported("missing GeoKeyDirectory tag".to_string())),
+        };
+
+        let mut geo_transform = read_geo_transform(&mut decoder)?;
+        if geo_key(&keys, GT_RASTER_TYPE_KEY) == Some(RASTER_PIXEL_IS_POINT) {
+            // Tiepoints refer to pixel centres; shift to the top-left corner
+            geo_transform[0] -= geo_transform[1] / 2.0;
+            geo_transform[3] -= geo_transform[5] / 2.0;
+        }
+
+        let (chunk_width, chunk_height) = decoder.chunk_dimensions();
+
+        Ok(GeoTiffSource {
+            metadata: RasterMetadata {
+                width: width as usize,
+                height: height as usize,
+                geo_transform,
+                crs: crs_from_geo_keys(&keys)?,
+                block_size: (chunk_width as usize, chunk_height as usize),
+                nodata: read_nodata(&mut decoder)?,
+            },
+            decoder: Mutex::new(decoder),
+        })
+    }
+
+    /// Lock the decoder, recovering from poisoning
+    ///
+    /// Every chunk read seeks to its own offset, so a panic during a previous
+    /// read cannot affect the next one.
+    fn lock_decoder(&self) -> MutexGuard<'_, TiffDecoder> {
+        self.decoder
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl RasterSource for GeoTiffSource {
+    fn metadata(&self) -> Result<RasterMetadata> {
+        Ok(self.metadata.clone())
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> Result<u8> {
+        let (width, height) = (self.metadata.width, self.metadata.height);
+        if pixel < 0 || line < 0 || pixel >= width as isize || line >= height as isize {
+            return Err(ClassifierError::RasterSampling {
+                pixel,
+                line,
+                message: format!(
+                    "Coordinates out of bounds. Raster size: {}x{}, requested: ({}, {})",
+                    width, height, pixel, line
+                ),
+                station_id: None,
+                row_index: None,
+            });
+        }
+
+        Ok(self.read_window(pixel, line, 1, 1)?.data[0])
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        let (raster_width, raster_height) = (self.metadata.width, self.metadata.height);
+
+        // Clip the request to the raster extent
+        let x_start = pixel.clamp(0, raster_width as isize) as usize;
+        let y_start = line.clamp(0, raster_height as isize) as usize;
+        let x_end =
+            (pixel + width as isize).clamp(x_start as isize, raster_width as isize) as usize;
+        let y_end =
+            (line + height as isize).clamp(y_start as isize, raster_height as isize) as usize;
+
+        let out_width = x_end - x_start;
+        let out_height = y_end - y_start;
+        let mut data = vec![0u8; out_width * out_height];
+
+        if out_width > 0 && out_height > 0 {
+            let (chunk_width, chunk_height) = self.metadata.block_size;
+            let chunks_across = raster_width.div_ceil(chunk_width);
+            let mut decoder = self.lock_decoder();
+
+            for chunk_row in y_start / chunk_height..=(y_end - 1) / chunk_height {
+                for chunk_col in x_start / chunk_width..=(x_end - 1) / chunk_width {
+                    let index = (chunk_row * chunks_across + chunk_col) as u32;
+                    let chunk = read_chunk(&mut decoder, index)?;
+                    let (data_width, data_height) = decoder.chunk_data_dimensions(index);
+
+                    // Intersection of the chunk with the clipped request
+                    let chunk_x = chunk_col * chunk_width;
+                    let chunk_y = chunk_row * chunk_height;
+                    let cols = x_start.max(chunk_x)..x_end.min(chunk_x + data_width as usize);
+                    let rows = y_start.max(chunk_y)..y_end.min(chunk_y + data_height as usize);
+
+                    for row in rows {
+                        let src = (row - chunk_y) * data_width as usize + (cols.start - chunk_x);
+                        let dst = (row - y_start) * out_width + (cols.start - x_start);
+                        data[dst..dst + cols.len()].copy_from_slice(&chunk[src..src + cols.len()]);
+                    }
+                }
+            }
+        }
+
+        Ok(PixelWindow {
+            x_off: x_start as isize,
+            y_off: y_start as isize,
+            width: out_width,
+            height: out_height,
+            data,
+        })
+    }
+}
+
+impl UrbanClassifier {
+    /// Create a classifier reading a GeoTIFF with the pure-Rust backend
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::from_geotiff("path/to/lcz_europe.tif")?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_geotiff<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::from_source(GeoTiffSource::open(path)?)
+    }
+}
+
+/// Decode one chunk (tile or strip) of 8-bit values
+fn read_chunk(decoder: &mut TiffDecoder, index: u32) -> Result<Vec<u8>> {
+    match decoder.read_chunk(index).map_err(tiff_error)? {
+        DecodingResult::U8(data) => Ok(data),
+        _ => Err(unsupported("expected unsigned 8-bit samples".to_string())),
+    }
+}
+
+/// Build a GDAL-style geotransform from the GeoTIFF model tags
+fn read_geo_transform(decoder: &mut TiffDecoder) -> Result<[f64; 6]> {
+    if let Some(matrix) = find_f64_tag(decoder, Tag::ModelTransformationTag)? {
+        if matrix.len() < 8 {
+            return Err(unsupported("malformed ModelTransformation tag".to_string()));
+        }
+        if matrix[1] != 0.0 || matrix[4] != 0.0 {
+            return Err(unsupported(
+                "rotated rasters require the GDAL backend".to_string(),
+            ));
+        }
+        return Ok([matrix[3], matrix[0], 0.0, matrix[7], 0.0, matrix[5]]);
+    }
+
+    let scale = find_f64_tag(decoder, Tag::ModelPixelScaleTag)?.unwrap_or_default();
+    let tiepoint = find_f64_tag(decoder, Tag::ModelTiepointTag)?.unwrap_or_default();
+    if scale.len() < 2 || tiepoint.len() < 6 {
+        return Err(unsupported(
+            "missing ModelPixelScale/ModelTiepoint georeferencing".to_string(),
+        ));
+    }
+
+    let (pixel, line, x, y) = (tiepoint[0], tiepoint[1], tiepoint[3], tiepoint[4]);
+    Ok([
+        x - pixel * scale[0],
+        scale[0],
+        0.0,
+        y + line * scale[1],
+        0.0,
+        -scale[1],
+    ])
+}
+
+/// Read the nodata value from the GDAL_NODATA tag, if it fits in a byte
+fn read_nodata(decoder: &mut TiffDecoder) -> Result<Option<u8>> {
+    let Some(value) = decoder
+        .find_tag(Tag::Unknown(GDAL_NODATA_TAG))
+        .map_err(tiff_error)?
+    else {
+        return Ok(None);
+    };
+    let text = value.into_string().map_err(tiff_error)?;
+    Ok(text
+        .trim_end_matches('\0')
+        .trim()
+        .parse()
+        .ok()
+        .and_then(nodata_to_u8))
+}
+
+/// Read an optional tag as a vector of doubles
+fn find_f64_tag(decoder: &mut TiffDecoder, tag: Tag) -> Result<Option<Vec<f64>>> {
+    decoder
+        .find_tag(tag)
+        .map_err(tiff_error)?
+        .map(|value| value.into_f64_vec().map_err(tiff_error))
+        .transpose()
+}
+
+/// Look up a short-valued key in a GeoKeyDirectory
+///
+/// Keys stored in other tags (doubles, ASCII) are not needed here and are
+/// reported as absent.
+fn geo_key(keys: &[u16], id: u16) -> Option<u16> {
+    keys.get(4..)?
+        .chunks_exact(4)
+        .find(|entry| entry[0] == id && entry[1] == 0)
+        .map(|entry| entry[3])
+}
+
+/// Determine the raster CRS from its GeoKeys
+fn crs_from_geo_keys(keys: &[u16]) -> Result<RasterCrs> {
+    let code = match geo_key(keys, GT_MODEL_TYPE_KEY) {
+        Some(MODEL_TYPE_PROJECTED) => geo_key(keys, PROJECTED_CS_TYPE_KEY),
+        Some(MODEL_TYPE_GEOGRAPHIC) => geo_key(keys, GEOGRAPHIC_TYPE_KEY),
+        _ => None,
+    };
+
+    match code {
+        Some(code) => crs_from_epsg(code),
+        None => Err(unsupported(
+            "CRS is not identified by an EPSG code".to_string(),
+        )),
+    }
+}
+
+/// Map a supported EPSG code to a CRS definition
+fn crs_from_epsg(code: u16) -> Result<RasterCrs> {
+    let definition = match code {
+        4326 => return Ok(RasterCrs::Wgs84),
+        32601..=32660 => format!(
+            "+proj=utm +zone={} +datum=WGS84 +units=m +no_defs",
+            code - 32600
+        ),
+        32701..=32760 => format!(
+            "+proj=utm +zone={} +south +datum=WGS84 +units=m +no_defs",
+            code - 32700
+        ),
+        3857 => "+proj=merc +a=6378137 +b=6378137 +lat_ts=0 +lon_0=0 +x_0=0 +y_0=0 +k=1 \
+                 +units=m +no_defs"
+            .to_string(),
+        3035 => "+proj=laea +lat_0=52 +lon_0=10 +x_0=4321000 +y_0=3210000 +ellps=GRS80 \
+                 +units=m +no_defs"
+            .to_string(),
+        _ => {
+            return Err(unsupported(format!(
+                "EPSG:{} is not supported without GDAL",
+                code
+            )))
+        }
+    };
+    Ok(RasterCrs::Proj4(definition))
+}
+
+fn unsupported(message: String) -> ClassifierError {
+    ClassifierError::UnsupportedRaster { message }
+}
+
+fn tiff_error(e: tiff::TiffError) -> ClassifierError {
+    unsupported(format!("failed to read GeoTIFF: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tiff::encoder::{colortype, TiffEncoder};
+
+    /// Write a 20x10 EPSG:4326 GeoTIFF with 3-row strips; value = column + 1
+    fn write_test_tiff(path: &Path) {
+        let mut encoder = TiffEncoder::new(File::create(path).unwrap()).unwrap();
+        let mut image = encoder.new_image::<colortype::Gray8>(20, 10).unwrap();
+        image.rows_per_strip(3).unwrap();
+
+        let keys: [u16; 12] = [
+            1,
+            1,
+            0,
+            2,
+            GT_MODEL_TYPE_KEY,
+            0,
+            1,
+            MODEL_TYPE_GEOGRAPHIC,
+            GEOGRAPHIC_TYPE_KEY,
+            0,
+            1,
+            4326,
+        ];
+        let dir = image.encoder();
+        dir.write_tag(Tag::ModelPixelScaleTag, &[0.5, 0.5, 0.0][..])
+            .unwrap();
+        dir.write_tag(Tag::ModelTiepointTag, &[0.0, 0.0, 0.0, -1.0, 52.0, 0.0][..])
+            .unwrap();
+        dir.write_tag(Tag::GeoKeyDirectoryTag, &keys[..]).unwrap();
+
+        let data: Vec<u8> = (0..10).flat_map(|_| 1..=20).collect();
+        image.write_data(&data).unwrap();
+    }
+
+    /// Test georeferencing and windowed reads across strip boundaries
+    #[test]
+    fn test_read_geotiff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lcz.tif");
+        write_test_tiff(&path);
+
+        let source = GeoTiffSource::open(&path).unwrap();
+        let metadata = source.metadata().unwrap();
+        assert_eq!((metadata.width, metadata.height), (20, 10));
+        assert_eq!(metadata.geo_transform, [-1.0, 0.5, 0.0, 52.0, 0.0, -0.5]);
+        assert_eq!(metadata.crs, RasterCrs::Wgs84);
+
+        let window = source.read_window(17, 2, 5, 3).unwrap();
+        assert_eq!((window.x_off, window.width, window.height), (17, 3, 3));
+        assert_eq!(window.data, vec![18, 19, 20, 18, 19, 20, 18, 19, 20]);
+
+        assert_eq!(source.sample(4, 9).unwrap(), 5);
+        assert!(source.sample(20, 0).is_err());
+        assert!(source.read_window(-5, 0, 3, 3).unwrap().data.is_empty());
+    }
+
+    /// Test GeoKey lookup and EPSG mapping
+    #[test]
+    fn test_crs_from_geo_keys() {
+        let keys = [
+            1,
+            1,
+            0,
+            2,
+            GT_MODEL_TYPE_KEY,
+            0,
+            1,
+            MODEL_TYPE_PROJECTED,
+            PROJECTED_CS_TYPE_KEY,
+            0,
+            1,
+            32630,
+        ];
+        assert_eq!(
+            crs_from_geo_keys(&keys).unwrap(),
+            RasterCrs::Proj4("+proj=utm +zone=30 +datum=WGS84 +units=m +no_defs".to_string())
+        );
+        assert_eq!(geo_key(&keys, GT_RASTER_TYPE_KEY), None);
+
+        assert!(matches!(
+            crs_from_epsg(27700),
+            Err(ClassifierError::UnsupportedRaster { .. })
+        ));
+    }
+
+    /// Test that projected coordinates are located through proj4rs
+    #[test]
+    fn test_utm_locator() {
+        let metadata = RasterMetadata {
+            width: 1_000_000,
+            height: 1_000_000,
+            geo_transform: [499_950.0, 100.0, 0.0, 50.0, 0.0, -100.0],
+            crs: crs_from_epsg(32630).unwrap(),
+            block_size: (256, 256),
+            nodata: None,
+        };
+        let locator = crate::source::PixelLocator::new(&metadata).unwrap();
+
+        // The central meridian of zone 30 (3°W) maps to the false easting,
+        // in the middle of the first pixel
+        assert_eq!(locator.locate(-3.0, 0.0).unwrap(), (0, 0));
+        assert_eq!(locator.locate(-3.0, -0.001).unwrap(), (0, 1));
+    }
+
+    /// Test that the source can be shared between threads
+    #[test]
+    fn test_source_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<GeoTiffSource>();
+    }
+}
@@ -0,0 +1,389 @@
+//! H3 Cell Classification and Aggregation
+//!
+//! This module assigns LCZ classes to cells of the H3 hexagonal grid so that
+//! classification output can be joined with datasets keyed on H3 indices
+//! (mobility, energy, census aggregates). It is enabled by the `h3` feature.
+//!
+//! # Output
+//!
+//! Each classified cell produces one row with:
+//! - `h3_index`: Hexadecimal H3 cell index
+//! - `pixel_count`: Number of raster pixels with a valid LCZ code inside the cell
+//! - `lcz_mode`: Dominant LCZ code (ties resolve to the lowest code)
+//! - `lcz_1_frac` to `lcz_17_frac`: Fraction of valid pixels in each class
+//!
+//! A pixel belongs to a cell when its centre falls inside the cell. Cells smaller
+//! than a raster pixel fall back to the pixel under the cell centre.
+
+use crate::classifier::UrbanClassifier;
+use crate::composition::{class_percentages, ClassCounts, LCZ_CLASS_COUNT};
+use crate::error::{ClassifierError, Result};
+use crate::source::{PixelLocator, RasterMetadata};
+use crate::spatial::{pixel_center_to_geo, BoundingBox, PixelWindow, EARTH_RADIUS_M};
+
+use h3o::{CellIndex, LatLng, Resolution};
+use polars::prelude::*;
+
+/// Raster state shared while classifying a batch of cells
+struct CellSampler<'a> {
+    classifier: &'a UrbanClassifier,
+    metadata: &'a RasterMetadata,
+    locator: PixelLocator,
+}
+
+impl UrbanClassifier {
+    /// Classify H3 cells by their dominant LCZ class and class mix
+    ///
+    /// # Arguments
+    /// * `cells` - H3 cells to classify (any mix of resolutions)
+    ///
+    /// # Returns
+    /// One row per cell, with the columns described in the module documentation
+    pub fn classify_h3_cells(&self, cells: &[CellIndex]) -> Result<DataFrame> {
+        let metadata = self.source_metadata();
+        let sampler = CellSampler {
+            classifier: self,
+            metadata,
+            locator: PixelLocator::new(metadata)?,
+        };
+
+        let counts = cells
+            .iter()
+            .map(|&cell| sampler.cell_class_counts(cell))
+            .collect::<Result<Vec<_>>>()?;
+
+        h3_dataframe(cells, &counts)
+    }
+
+    /// Classify all H3 cells at `resolution` that intersect a WGS84 bounding box
+    pub fn classify_h3_bbox(
+        &self,
+        bbox: &BoundingBox,
+        resolution: Resolution,
+    ) -> Result<DataFrame> {
+        self.classify_h3_cells(&cells_covering_bbox(bbox, resolution))
+    }
+
+    /// Classify the H3 cells at `resolution` that contain the given stations
+    ///
+    /// Each distinct cell is reported once, in order of first appearance.
+    pub fn classify_h3_stations(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        resolution: Resolution,
+    ) -> Result<DataFrame> {
+        self.validate_dataframe_schema(stations_df, station_id_col, lon_col, lat_col)?;
+        let (_, coordinates) =
+            self.extract_coordinates(stations_df, station_id_col, lon_col, lat_col)?;
+
+        let mut cells: Vec<CellIndex> = Vec::new();
+        for (lon, lat) in coordinates {
+            let cell = LatLng::new(lat, lon)
+                .map_err(|_| ClassifierError::InvalidCoordinate {
+                    lon,
+                    lat,
+                    station_id: None,
+                    row_index: None,
+                })?
+                .to_cell(resolution);
+            if !cells.contains(&cell) {
+                cells.push(cell);
+            }
+        }
+
+        self.classify_h3_cells(&cells)
+    }
+}
+
+impl CellSampler<'_> {
+    /// Count the LCZ classes of pixels whose centres fall inside a cell
+    fn cell_class_counts(&self, cell: CellIndex) -> Result<ClassCounts> {
+        let mut counts = [0u64; LCZ_CLASS_COUNT];
+
+        // Pixel windows covering the cell's vertices
+        let mut windows: Vec<PixelWindow> = Vec::new();
+        for bbox in cell_bboxes(cell) {
+            let (pixel, line, width, height) = self.locator.bbox_window(&bbox)?;
+            let window = self
+                .classifier
+                .map_window(self.classifier.read_window(pixel, line, width, height)?);
+            self.count_window(cell, &window, &windows, &mut counts)?;
+            windows.push(window);
+        }
+
+        // Cells smaller than a pixel: use the pixel under the cell centre
+        if counts.iter().all(|&c| c == 0) {
+            let center = LatLng::from(cell);
+            let (pixel, line) = self.locator.locate(center.lng(), center.lat())?;
+            if let Ok(Some(code)) = self.classifier.sample_code(pixel, line) {
+                if (1..=LCZ_CLASS_COUNT as u8).contains(&code) {
+                    counts[code as usize - 1] += 1;
+                }
+            }
+        }
+
+        Ok(counts)
+    }
+
+    /// Add the pixels of a window whose centres fall inside a cell to `counts`,
+    /// skipping pixels already counted from an earlier window
+    fn count_window(
+        &self,
+        cell: CellIndex,
+        window: &PixelWindow,
+        counted: &[PixelWindow],
+        counts: &mut ClassCounts,
+    ) -> Result<()> {
+        if window.data.is_empty() {
+            return Ok(());
+        }
+
+        let mut xs = Vec::with_capacity(window.data.len());
+        let mut ys = Vec::with_capacity(window.data.len());
+        for row in 0..window.height as isize {
+            for col in 0..window.width as isize {
+                let (x, y) = pixel_center_to_geo(
+                    window.x_off + col,
+                    window.y_off + row,
+                    &self.metadata.geo_transform,
+                );
+                xs.push(x);
+                ys.push(y);
+            }
+        }
+        self.metadata.crs.transform_to_wgs84(&mut xs, &mut ys)?;
+
+        let resolution = cell.resolution();
+        for (i, &code) in window.data.iter().enumerate() {
+            let pixel = window.x_off + (i % window.width) as isize;
+            let line = window.y_off + (i / window.width) as isize;
+            if counted.iter().any(|w| w.get(pixel, line).is_some()) {
+                continue;
+            }
+            let inside = LatLng::new(ys[i], xs[i])
+                .map(|ll| ll.to_cell(resolution) == cell)
+                .unwrap_or(false);
+            if inside && (1..=LCZ_CLASS_COUNT as u8).contains(&code) {
+                counts[code as usize - 1] += 1;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// WGS84 bounding boxes covering the vertices of a cell
+///
+/// A cell crossing the antimeridian has vertices near both +180 and -180
+/// degrees, so a single box around them would span the whole globe. Such a
+/// cell is covered by one box on each side of the antimeridian instead.
+fn cell_bboxes(cell: CellIndex) -> Vec<BoundingBox> {
+    let boundary = cell.boundary();
+    let mut bbox = BoundingBox {
+        min_lon: f64::INFINITY,
+        min_lat: f64::INFINITY,
+        max_lon: f64::NEG_INFINITY,
+        max_lat: f64::NEG_INFINITY,
+    };
+    for vertex in boundary.iter() {
+        bbox.min_lon = bbox.min_lon.min(vertex.lng());
+        bbox.min_lat = bbox.min_lat.min(vertex.lat());
+        bbox.max_lon = bbox.max_lon.max(vertex.lng());
+        bbox.max_lat = bbox.max_lat.max(vertex.lat());
+    }
+    if bbox.max_lon - bbox.min_lon <= 180.0 {
+        return vec![bbox];
+    }
+
+    // Keep the full latitude range on both sides, as the edges crossing the
+    // antimeridian lie between the latitudes of their end vertices
+    let lngs = || boundary.iter().map(|vertex| vertex.lng());
+    let east_min = lngs()
+        .filter(|&lng| lng >= 0.0)
+        .fold(f64::INFINITY, f64::min);
+    let west_max = lngs()
+        .filter(|&lng| lng < 0.0)
+        .fold(f64::NEG_INFINITY, f64::max);
+    vec![
+        BoundingBox {
+            min_lon: east_min,
+            max_lon: 180.0,
+            ..bbox
+        },
+        BoundingBox {
+            min_lon: -180.0,
+            max_lon: west_max,
+            ..bbox
+        },
+    ]
+}
+
+/// Find the H3 cells at `resolution` that intersect a bounding box
+///
+/// The box is sampled on a regular grid finer than the cell edge length, so
+/// every cell overlapping the box interior is found.
+pub fn cells_covering_bbox(bbox: &BoundingBox, resolution: Resolution) -> Vec<CellIndex> {
+    let metres_per_degree = EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
+    let step_m = resolution.edge_length_m() / 2.0;
+
+    // Longitude degrees are longest nearest the equator, so size the step there
+    let min_abs_lat = if bbox.min_lat <= 0.0 && bbox.max_lat >= 0.0 {
+        0.0
+    } else {
+        bbox.min_lat.abs().min(bbox.max_lat.abs())
+    };
+    let lat_step = step_m / metres_per_degree;
+    let lon_step = lat_step / min_abs_lat.to_radians().cos();
+
+    let n_lat = ((bbox.max_lat - bbox.min_lat) / lat_step).ceil() as usize + 1;
+    let n_lon = ((bbox.max_lon - bbox.min_lon) / lon_step).ceil() as usize + 1;
+
+    let mut cells = Vec::new();
+    for i in 0..n_lat {
+        let lat = (bbox.min_lat + i as f64 * lat_step).min(bbox.max_lat);
+        for j in 0..n_lon {
+            let lon = (bbox.min_lon + j as f64 * lon_step).min(bbox.max_lon);
+            if let Ok(ll) = LatLng::new(lat, lon) {
+                cells.push(ll.to_cell(resolution));
+            }
+        }
+    }
+
+    cells.sort_unstable();
+    cells.dedup();
+    cells
+}
+
+/// Build the H3 output DataFrame from per-cell class counts
+fn h3_dataframe(cells: &[CellIndex], counts: &[ClassCounts]) -> Result<DataFrame> {
+    let indices: Vec<String> = cells.iter().map(|c| c.to_string()).collect();
+    let pixel_counts: Vec<u64> = counts.iter().map(|c| c.iter().sum()).collect();
+    let modes: Vec<Option<u32>> = counts.iter().map(dominant_class).collect();
+    let fractions: Vec<Option<[f64; LCZ_CLASS_COUNT]>> =
+        counts.iter().map(class_percentages).collect();
+
+    let mut columns = vec![
+        Series::new("h3_index", indices),
+        Series::new("pixel_count", pixel_counts),
+        Series::new("lcz_mode", modes),
+    ];
+    for i in 0..LCZ_CLASS_COUNT {
+        let values: Vec<Option<f64>> = fractions
+            .iter()
+            .map(|cell| cell.map(|pct| pct[i] / 100.0))
+            .collect();
+        columns.push(Series::new(&format!("lcz_{}_frac", i + 1), values));
+    }
+
+    Ok(DataFrame::new(columns)?)
+}
+
+/// Most frequent LCZ code in a histogram (lowest code wins ties)
+fn dominant_class(counts: &ClassCounts) -> Option<u32> {
+    let (index, &count) = counts
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.cmp(b.1).then(b.0.cmp(&a.0)))?;
+    (count > 0).then_some(index as u32 + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that bbox covering finds the cells of points inside the box
+    #[test]
+    fn test_cells_covering_bbox() {
+        let bbox = BoundingBox::new(-0.2, 51.45, 0.0, 51.55).unwrap();
+        let cells = cells_covering_bbox(&bbox, Resolution::Seven);
+
+        assert!(!cells.is_empty());
+        assert!(cells.iter().all(|c| c.resolution() == Resolution::Seven));
+
+        for (lon, lat) in [(-0.1278, 51.5074), (-0.2, 51.45), (0.0, 51.55)] {
+            let cell = LatLng::new(lat, lon).unwrap().to_cell(Resolution::Seven);
+            assert!(cells.contains(&cell));
+        }
+    }
+
+    /// Test dominant class selection and tie-breaking
+    #[test]
+    fn test_dominant_class() {
+        let mut counts = [0u64; LCZ_CLASS_COUNT];
+        assert_eq!(dominant_class(&counts), None);
+
+        counts[5] = 3;
+        counts[13] = 3;
+        counts[10] = 1;
+        assert_eq!(dominant_class(&counts), Some(6));
+    }
+
+    /// Test that a cell crossing the antimeridian gets a narrow box on each side
+    #[test]
+    fn test_cell_bboxes_antimeridian() {
+        let cell = LatLng::new(0.0, 180.0).unwrap().to_cell(Resolution::Four);
+        let boxes = cell_bboxes(cell);
+        assert_eq!(boxes.len(), 2);
+        assert_eq!(boxes[0].max_lon, 180.0);
+        assert_eq!(boxes[1].min_lon, -180.0);
+        assert!(boxes.iter().all(|b| b.max_lon - b.min_lon < 1.0));
+
+        let cell = LatLng::new(51.5, -0.12).unwrap().to_cell(Resolution::Four);
+        assert_eq!(cell_bboxes(cell).len(), 1);
+    }
+
+    /// Test that pixels on both sides of the antimeridian are counted
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_classify_antimeridian_cell() {
+        use crate::lcz::Lcz;
+        use crate::test_utils::MockClassifier;
+
+        let classifier = MockClassifier::from_fn(|lon, _| {
+            if lon > 0.0 {
+                Lcz::CompactHighRise
+            } else {
+                Lcz::Water
+            }
+        })
+        .with_resolution(0.05)
+        .build()
+        .unwrap();
+        let cell = LatLng::new(0.0, 180.0).unwrap().to_cell(Resolution::Four);
+
+        let df = classifier.classify_h3_cells(&[cell]).unwrap();
+        let pixel_count = df.column("pixel_count").unwrap().u64().unwrap().get(0);
+        assert!(pixel_count.unwrap() > 1);
+        let fraction = |column: &str| df.column(column).unwrap().f64().unwrap().get(0).unwrap();
+        assert!(fraction("lcz_1_frac") > 0.0);
+        assert!(fraction("lcz_17_frac") > 0.0);
+        assert!((fraction("lcz_1_frac") + fraction("lcz_17_frac") - 1.0).abs() < 1e-9);
+    }
+
+    /// Test output columns for classified cells
+    #[test]
+    fn test_h3_dataframe() {
+        let cell = LatLng::new(51.5, -0.12).unwrap().to_cell(Resolution::Eight);
+        let mut counts = [0u64; LCZ_CLASS_COUNT];
+        counts[1] = 3;
+        counts[16] = 1;
+
+        let df = h3_dataframe(&[cell], &[counts]).unwrap();
+        assert_eq!(df.height(), 1);
+        assert_eq!(df.width(), 3 + LCZ_CLASS_COUNT);
+
+        let index = df.column("h3_index").unwrap().str().unwrap();
+        assert_eq!(index.get(0), Some(cell.to_string().as_str()));
+        assert_eq!(
+            df.column("lcz_mode").unwrap().u32().unwrap().get(0),
+            Some(2)
+        );
+        assert_eq!(
+            df.column("lcz_2_frac").unwrap().f64().unwrap().get(0),
+            Some(0.75)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+//! Raster Metadata
+//!
+//! This module exposes the georeferencing and encoding of the loaded LCZ map,
+//! so callers can sanity-check that the expected file was loaded and decide
+//! which stations it can cover before sampling.
+//!
+//! # Extents
+//!
+//! The native extent is the axis-aligned envelope of the raster in its own CRS.
+//! The WGS84 extent is computed by transforming densified raster edges, so it
+//! also encloses rasters whose edges are curved in geographic coordinates.
+//! Both are available from `raster_bounds` for any raster source, with
+//! containment and intersection tests for coverage checks.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+#[cfg(feature = "gdal")]
+use crate::spatial::pixel_size;
+use crate::spatial::BoundingBox;
+
+/// Number of points sampled along each raster edge for the WGS84 extent
+const EDGE_POINTS: usize = 21;
+
+/// Axis-aligned extent in the raster's native CRS units
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extent {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+/// Extent of the loaded raster in its native CRS and in WGS84
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RasterBounds {
+    /// Extent in the native CRS
+    pub native: Extent,
+    /// Extent in WGS84 degrees
+    pub wgs84: BoundingBox,
+}
+
+impl RasterBounds {
+    /// Check whether a WGS84 coordinate lies inside the raster's WGS84 extent
+    ///
+    /// The WGS84 extent is an envelope, so coordinates near the corners of
+    /// projected rasters may pass this check and still fall outside the raster.
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        self.wgs84.contains(lon, lat)
+    }
+
+    /// Overlap of the raster's WGS84 extent with `bbox`, or `None` if they
+    /// are disjoint
+    pub fn intersection(&self, bbox: &BoundingBox) -> Option<BoundingBox> {
+        let overlap = BoundingBox {
+            min_lon: self.wgs84.min_lon.max(bbox.min_lon),
+            min_lat: self.wgs84.min_lat.max(bbox.min_lat),
+            max_lon: self.wgs84.max_lon.min(bbox.max_lon),
+            max_lat: self.wgs84.max_lat.min(bbox.max_lat),
+        };
+        (overlap.min_lon <= overlap.max_lon && overlap.min_lat <= overlap.max_lat)
+            .then_some(overlap)
+    }
+}
+
+/// Georeferencing and encoding metadata of the loaded LCZ raster
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterInfo {
+    /// Raster width in pixels
+    pub width: usize,
+    /// Raster height in pixels
+    pub height: usize,
+    /// Number of raster bands
+    pub band_count: usize,
+    /// Absolute pixel size (x, y) in native CRS units
+    pub pixel_size: (f64, f64),
+    /// GDAL geotransform of the raster
+    pub geo_transform: [f64; 6],
+    /// Extent in the native CRS
+    pub native_extent: Extent,
+    /// Extent in WGS84 degrees
+    pub wgs84_extent: BoundingBox,
+    /// Native CRS as WKT
+    pub crs_wkt: String,
+    /// EPSG code of the native CRS, when it is identified as one
+    pub epsg: Option<u32>,
+    /// Nodata value of the LCZ band, if set
+    pub nodata: Option<f64>,
+}
+
+impl UrbanClassifier {
+    /// Compute the extent of the loaded raster in its native CRS and in WGS84
+    ///
+    /// Unlike `raster_info`, this works for classifiers created from any
+    /// raster source.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::spatial::BoundingBox;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let bounds = classifier.raster_bounds()?;
+    /// let study_area = BoundingBox::new(-0.6, 51.2, 0.4, 51.8)?;
+    /// if bounds.intersection(&study_area).is_none() {
+    ///     println!("The map does not cover the study area");
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn raster_bounds(&self) -> Result<RasterBounds> {
+        let metadata = self.source_metadata();
+        let (geo_transform, width, height) =
+            (&metadata.geo_transform, metadata.width, metadata.height);
+
+        let (mut xs, mut ys): (Vec<f64>, Vec<f64>) = edge_points(geo_transform, width, height)
+            .into_iter()
+            .unzip();
+        metadata.crs.transform_to_wgs84(&mut xs, &mut ys)?;
+        let wgs84 = envelope(xs.into_iter().zip(ys));
+
+        Ok(RasterBounds {
+            native: native_extent(geo_transform, width, height),
+            wgs84: BoundingBox {
+                min_lon: wgs84.min_x.max(-180.0),
+                min_lat: wgs84.min_y.max(-90.0),
+                max_lon: wgs84.max_x.min(180.0),
+                max_lat: wgs84.max_y.min(90.0),
+            },
+        })
+    }
+
+    /// Error for a station at (`lon`, `lat`) located at a pixel outside the raster
+    ///
+    /// The raster's WGS84 extent is computed on first use and kept in
+    /// `extent` for the other stations of a run.
+    pub(crate) fn out_of_bounds_error(
+        &self,
+        (lon, lat): (f64, f64),
+        (pixel, line): (isize, isize),
+        extent: &mut Option<BoundingBox>,
+    ) -> Result<ClassifierError> {
+        let raster_extent = match extent {
+            Some(extent) => *extent,
+            None => *extent.insert(self.raster_bounds()?.wgs84),
+        };
+        Ok(ClassifierError::OutOfBounds {
+            lon,
+            lat,
+            pixel,
+            line,
+            raster_extent,
+            station_id: None,
+            row_index: None,
+        })
+    }
+
+    /// Describe the extent, resolution, CRS and encoding of the loaded raster
+    ///
+    /// Requires the `gdal` feature and a raster opened through GDAL.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let info = classifier.raster_info()?;
+    /// println!("{}x{} pixels, EPSG:{:?}", info.width, info.height, info.epsg);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn raster_info(&self) -> Result<RasterInfo> {
+        let dataset = self.dataset()?;
+        let (width, height) = dataset.raster_size();
+        let geo_transform = dataset.geo_transform()?;
+        let raster_srs = self.raster_spatial_ref()?;
+        let band = dataset.rasterband(self.lcz_band())?;
+
+        let epsg = match raster_srs.auth_name() {
+            Ok(name) if name.eq_ignore_ascii_case("EPSG") => raster_srs
+                .auth_code()
+                .ok()
+                .and_then(|code| u32::try_from(code).ok()),
+            _ => None,
+        };
+
+        let bounds = self.raster_bounds()?;
+
+        Ok(RasterInfo {
+            width,
+            height,
+            band_count: dataset.raster_count() as usize,
+            pixel_size: pixel_size(&geo_transform),
+            geo_transform,
+            native_extent: bounds.native,
+            wgs84_extent: bounds.wgs84,
+            crs_wkt: raster_srs.to_wkt()?,
+            epsg,
+            nodata: band.no_data_value(),
+        })
+    }
+}
+
+/// Compute the native extent of a raster from its geotransform
+pub fn native_extent(geo_transform: &[f64; 6], width: usize, height: usize) -> Extent {
+    let (w, h) = (width as f64, height as f64);
+    let corners = [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)]
+        .map(|(col, row)| pixel_edge_to_geo(col, row, geo_transform));
+    envelope(corners)
+}
+
+/// Points along the raster's outer edges, in native coordinates
+fn edge_points(geo_transform: &[f64; 6], width: usize, height: usize) -> Vec<(f64, f64)> {
+    let (w, h) = (width as f64, height as f64);
+    let mut points = Vec::with_capacity(4 * EDGE_POINTS);
+    for i in 0..EDGE_POINTS {
+        let t = i as f64 / (EDGE_POINTS - 1) as f64;
+        for (col, row) in [(t * w, 0.0), (t * w, h), (0.0, t * h), (w, t * h)] {
+            points.push(pixel_edge_to_geo(col, row, geo_transform));
+        }
+    }
+    points
+}
+
+/// Apply the geotransform to fractional pixel coordinates
+fn pixel_edge_to_geo(col: f64, row: f64, geo_transform: &[f64; 6]) -> (f64, f64) {
+    (
+        geo_transform[0] + col * geo_transform[1] + row * geo_transform[2],
+        geo_transform[3] + col * geo_transform[4] + row * geo_transform[5],
+    )
+}
+
+/// Axis-aligned envelope of a set of points, ignoring non-finite ones
+fn envelope(points: impl IntoIterator<Item = (f64, f64)>) -> Extent {
+    let mut extent = Extent {
+        min_x: f64::INFINITY,
+        min_y: f64::INFINITY,
+        max_x: f64::NEG_INFINITY,
+        max_y: f64::NEG_INFINITY,
+    };
+    for (x, y) in points {
+        if !x.is_finite() || !y.is_finite() {
+            continue;
+        }
+        extent.min_x = extent.min_x.min(x);
+        extent.min_y = extent.min_y.min(y);
+        extent.max_x = extent.max_x.max(x);
+        extent.max_y = extent.max_y.max(y);
+    }
+    extent
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the native extent of a north-up global raster
+    #[test]
+    fn test_native_extent_north_up() {
+        let gt = [-180.0, 0.5, 0.0, 90.0, 0.0, -0.5];
+        let extent = native_extent(&gt, 720, 360);
+
+        assert_eq!(
+            extent,
+            Extent {
+                min_x: -180.0,
+                min_y: -90.0,
+                max_x: 180.0,
+                max_y: 90.0,
+            }
+        );
+    }
+
+    /// Test that rotated geotransforms use all four corners
+    #[test]
+    fn test_native_extent_rotated() {
+        let gt = [0.0, 1.0, 1.0, 0.0, 1.0, -1.0];
+        let extent = native_extent(&gt, 10, 10);
+
+        assert_eq!(extent.min_x, 0.0);
+        assert_eq!(extent.max_x, 20.0);
+        assert_eq!(extent.min_y, -10.0);
+        assert_eq!(extent.max_y, 10.0);
+    }
+
+    /// Test containment and intersection against the WGS84 extent
+    #[test]
+    fn test_raster_bounds() {
+        let bounds = RasterBounds {
+            native: native_extent(&[-10.0, 1.0, 0.0, 60.0, 0.0, -1.0], 20, 20),
+            wgs84: BoundingBox::new(-10.0, 40.0, 10.0, 60.0).unwrap(),
+        };
+        assert!(bounds.contains(0.0, 50.0));
+        assert!(!bounds.contains(0.0, 30.0));
+
+        let overlap = bounds
+            .intersection(&BoundingBox::new(5.0, 55.0, 20.0, 70.0).unwrap())
+            .unwrap();
+        assert_eq!(overlap, BoundingBox::new(5.0, 55.0, 10.0, 60.0).unwrap());
+        assert_eq!(
+            bounds.intersection(&BoundingBox::new(11.0, 40.0, 20.0, 60.0).unwrap()),
+            None
+        );
+    }
+
+    /// Test that edge densification covers all four edges
+    #[test]
+    fn test_edge_points() {
+        let gt = [100.0, 10.0, 0.0, 200.0, 0.0, -10.0];
+        let points = edge_points(&gt, 4, 2);
+
+        assert_eq!(points.len(), 4 * EDGE_POINTS);
+        assert_eq!(envelope(points), native_extent(&gt, 4, 2));
+    }
+}
@@ -22,11 +22,36 @@
 //! - **Urban**: Classes 1-6 (compact and open built areas)
 //! - **Suburban**: Classes 7-10 (sparse built and industrial)
 //! - **Rural**: Classes 11-17 (natural land cover)
+//!
+//! # Text Form
+//!
+//! Classes display as `LCZ 6 — Open low-rise` and parse from a code (`6`,
+//! `LCZ 6`), a natural-class letter (`D`) or a name (`Low plants`), so
+//! configuration files and command lines can name classes naturally.
+//! Categories display and parse as their names, e.g. `Urban`.
+//!
+//! # Serde
+//!
+//! `Lcz` serializes as its variant name by default. The `serde_code`,
+//! `serde_letter` and `serde_name` modules serialize it as the code (`14`),
+//! label (`"D"`) or full name (`"Low plants"`) instead, for use with
+//! `#[serde(with = "...")]` on fields matching a downstream schema.
+//!
+//! # Legend
+//!
+//! `legend()` lists the code, label, name, category and standard WUDAPT map
+//! colour of each class, with `legend_to_json` and `legend_to_csv` for
+//! plotting and web tools that need a single source of legend data.
 
 use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
 
 /// Local Climate Zone classification
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Classes compare and hash as values and order by canonical code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Lcz {
     // Urban types (1-10)
     CompactHighRise,    // 1
@@ -54,7 +79,10 @@ pub enum Lcz {
 }
 
 /// Simplified urban/rural classification categories
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+///
+/// Categories display, parse (ignoring case) and serialize as their names,
+/// e.g. `Urban`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum LczCategory {
     /// Dense urban areas (LCZ 1-6)
     Urban,
@@ -62,9 +90,45 @@ pub enum LczCategory {
     Suburban,
     /// Natural/vegetated areas (LCZ 11-17)
     Rural,
+    /// Codes outside the 17 classes, when a `CategoryScheme` keeps them apart
+    Unknown,
 }
 
 impl Lcz {
+    /// The 17 standard classes in code order
+    pub const ALL: [Lcz; 17] = [
+        Lcz::CompactHighRise,
+        Lcz::CompactMidRise,
+        Lcz::CompactLowRise,
+        Lcz::OpenHighRise,
+        Lcz::OpenMidRise,
+        Lcz::OpenLowRise,
+        Lcz::LightweightLowRise,
+        Lcz::LargeLowRise,
+        Lcz::SparselyBuilt,
+        Lcz::HeavyIndustry,
+        Lcz::DenseTrees,
+        Lcz::ScatteredTrees,
+        Lcz::BushScrub,
+        Lcz::LowPlants,
+        Lcz::BareRockPaved,
+        Lcz::BareSoilSand,
+        Lcz::Water,
+    ];
+
+    /// Iterate over the 17 standard classes in code order
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// let natural: Vec<&str> = Lcz::iter().filter_map(|lcz| lcz.letter()).skip(10).collect();
+    /// assert_eq!(natural, ["A", "B", "C", "D", "E", "F", "G"]);
+    /// ```
+    pub fn iter() -> impl Iterator<Item = Lcz> {
+        Self::ALL.into_iter()
+    }
+
     /// Convert a numeric LCZ code (1-17) to the corresponding enum variant
     pub fn from_code(code: u8) -> Self {
         match code {
@@ -89,6 +153,25 @@ impl Lcz {
         }
     }
 
+    /// Convert a numeric LCZ code to its class, rejecting codes outside 1-17
+    ///
+    /// Unlike `from_code`, which wraps any other byte in `Lcz::Unknown`, this
+    /// validates the code.
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// assert_eq!(Lcz::try_from_code(14), Ok(Lcz::LowPlants));
+    /// assert!(Lcz::try_from_code(0).is_err());
+    /// ```
+    pub fn try_from_code(code: u8) -> Result<Self, InvalidLczCode> {
+        match Lcz::from_code(code) {
+            Lcz::Unknown(code) => Err(InvalidLczCode { code }),
+            lcz => Ok(lcz),
+        }
+    }
+
     /// Convert the enum variant back to its numeric LCZ code
     pub fn to_code(&self) -> u8 {
         match self {
@@ -113,6 +196,57 @@ impl Lcz {
         }
     }
 
+    /// Get the standard WUDAPT map colour as `#rrggbb`, or `None` for
+    /// unknown codes
+    pub fn color(&self) -> Option<&'static str> {
+        const COLORS: [&str; 17] = [
+            "#8c0000", "#d10000", "#ff0000", "#bf4d00", "#ff6600", "#ff9955", "#faee05", "#bcbcbc",
+            "#ffccaa", "#555555", "#006a00", "#00aa00", "#648525", "#b9db79", "#000000", "#fbf7ae",
+            "#6a6aff",
+        ];
+        match self {
+            Lcz::Unknown(_) => None,
+            lcz => Some(COLORS[usize::from(lcz.to_code()) - 1]),
+        }
+    }
+
+    /// Get the class label used in WUDAPT publications
+    ///
+    /// Built classes are labelled `1` to `10` and natural classes `A` to `G`.
+    /// Returns `None` for unknown codes.
+    pub fn letter(&self) -> Option<&'static str> {
+        const LABELS: [&str; 17] = [
+            "1", "2", "3", "4", "5", "6", "7", "8", "9", "10", "A", "B", "C", "D", "E", "F", "G",
+        ];
+        match self {
+            Lcz::Unknown(_) => None,
+            lcz => Some(LABELS[usize::from(lcz.to_code()) - 1]),
+        }
+    }
+
+    /// Look up a class by its WUDAPT label (`1`-`10` or `A`-`G`, any case)
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// assert_eq!(Lcz::from_letter("d"), Some(Lcz::LowPlants));
+    /// assert_eq!(Lcz::from_letter("10"), Some(Lcz::HeavyIndustry));
+    /// assert_eq!(Lcz::from_letter("11"), None);
+    /// ```
+    pub fn from_letter(letter: &str) -> Option<Self> {
+        let letter = letter.trim();
+        Lcz::iter().find(|lcz| {
+            lcz.letter()
+                .is_some_and(|label| label.eq_ignore_ascii_case(letter))
+        })
+    }
+
+    /// Check whether this is one of the 17 standard LCZ classes
+    pub fn is_standard(&self) -> bool {
+        !matches!(self, Lcz::Unknown(_))
+    }
+
     /// Get the human-readable full name of the LCZ class
     pub fn full_name(&self) -> &'static str {
         match self {
@@ -169,16 +303,300 @@ impl Lcz {
     }
 }
 
+/// Classes order by canonical code, so legends and group-bys sort as
+/// LCZ 1-10 then A-G, with unknown codes placed by their value
+impl Ord for Lcz {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        // A hand-built `Unknown` holding a standard code sorts after its class
+        let key = |lcz: &Lcz| (lcz.to_code(), !lcz.is_standard());
+        key(self).cmp(&key(other))
+    }
+}
+
+impl PartialOrd for Lcz {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Lcz {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "LCZ {} — {}", self.to_code(), self.full_name())
+    }
+}
+
+/// Error returned when a numeric code is not one of the 17 LCZ classes
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("Invalid LCZ code {code}: expected 1-17")]
+pub struct InvalidLczCode {
+    /// The rejected code
+    pub code: u8,
+}
+
+impl TryFrom<u8> for Lcz {
+    type Error = InvalidLczCode;
+
+    fn try_from(code: u8) -> Result<Self, Self::Error> {
+        Lcz::try_from_code(code)
+    }
+}
+
+/// Error returned when a string does not name one of the 17 LCZ classes
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unrecognised LCZ class '{input}': expected a code 1-17, a letter A-G or a class name")]
+pub struct ParseLczError {
+    /// The string that failed to parse
+    pub input: String,
+}
+
+impl FromStr for Lcz {
+    type Err = ParseLczError;
+
+    /// Parse a code, letter or name, ignoring case, an `LCZ` prefix and
+    /// punctuation in names
+    ///
+    /// The `Display` form, e.g. `LCZ 6 — Open low-rise`, parses back to its class.
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// assert_eq!("6".parse(), Ok(Lcz::OpenLowRise));
+    /// assert_eq!("LCZ D".parse(), Ok(Lcz::LowPlants));
+    /// assert_eq!("bush scrub".parse(), Ok(Lcz::BushScrub));
+    /// assert!("18".parse::<Lcz>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseLczError {
+            input: s.to_string(),
+        };
+
+        let label = s.split('—').next().unwrap_or_default().trim();
+        let label = match label.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("lcz") => label[3..].trim_start(),
+            _ => label,
+        };
+
+        if let Ok(code) = label.parse::<u8>() {
+            return Lcz::try_from_code(code).map_err(|_| error());
+        }
+        if let Some(lcz) = Lcz::from_letter(label) {
+            return Ok(lcz);
+        }
+
+        let normalize = |name: &str| -> String {
+            name.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        };
+        let wanted = normalize(label);
+        Lcz::iter()
+            .find(|lcz| normalize(lcz.full_name()) == wanted)
+            .ok_or_else(error)
+    }
+}
+
+impl LczCategory {
+    /// Every category, in the order Urban, Suburban, Rural, Unknown
+    pub const ALL: [LczCategory; 4] = [
+        LczCategory::Urban,
+        LczCategory::Suburban,
+        LczCategory::Rural,
+        LczCategory::Unknown,
+    ];
+
+    /// Iterate over every category
+    pub fn iter() -> impl Iterator<Item = LczCategory> {
+        Self::ALL.into_iter()
+    }
+}
+
+impl fmt::Display for LczCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_ref())
+    }
+}
+
+/// Error returned when a string does not name an `LczCategory`
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unrecognised LCZ category '{input}': expected Urban, Suburban, Rural or Unknown")]
+pub struct ParseLczCategoryError {
+    /// The string that failed to parse
+    pub input: String,
+}
+
+impl FromStr for LczCategory {
+    type Err = ParseLczCategoryError;
+
+    /// Parse a category name, ignoring case and surrounding whitespace
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::LczCategory;
+    ///
+    /// assert_eq!("rural".parse(), Ok(LczCategory::Rural));
+    /// assert!("Peri-urban".parse::<LczCategory>().is_err());
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let name = s.trim();
+        LczCategory::iter()
+            .find(|category| category.as_ref().eq_ignore_ascii_case(name))
+            .ok_or_else(|| ParseLczCategoryError {
+                input: s.to_string(),
+            })
+    }
+}
+
 impl AsRef<str> for LczCategory {
     fn as_ref(&self) -> &str {
         match self {
             LczCategory::Urban => "Urban",
             LczCategory::Suburban => "Suburban",
             LczCategory::Rural => "Rural",
+            LczCategory::Unknown => "Unknown",
         }
     }
 }
 
+/// Serde helpers representing `Lcz` as its numeric code, e.g. `6`
+///
+/// Use with `#[serde(with = "urban_classifier::lcz::serde_code")]`. Codes
+/// outside 1-17 round-trip as `Lcz::Unknown`, as with `Lcz::from_code`.
+pub mod serde_code {
+    use super::Lcz;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    /// Serialize a class as its code
+    pub fn serialize<S: Serializer>(lcz: &Lcz, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(lcz.to_code())
+    }
+
+    /// Deserialize a class from its code
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lcz, D::Error> {
+        u8::deserialize(deserializer).map(Lcz::from_code)
+    }
+}
+
+/// Serde helpers representing `Lcz` as its WUDAPT label, e.g. `"6"` or `"D"`
+///
+/// Use with `#[serde(with = "urban_classifier::lcz::serde_letter")]`.
+/// Unknown classes have no label and fail to serialize.
+pub mod serde_letter {
+    use super::Lcz;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    /// Serialize a class as its label
+    pub fn serialize<S: Serializer>(lcz: &Lcz, serializer: S) -> Result<S::Ok, S::Error> {
+        let letter = lcz.letter().ok_or_else(|| {
+            ser::Error::custom(format!("LCZ code {} has no label", lcz.to_code()))
+        })?;
+        serializer.serialize_str(letter)
+    }
+
+    /// Deserialize a class from its label, ignoring case
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lcz, D::Error> {
+        let letter = String::deserialize(deserializer)?;
+        Lcz::from_letter(&letter)
+            .ok_or_else(|| de::Error::custom(format!("Unrecognised LCZ label '{}'", letter)))
+    }
+}
+
+/// Serde helpers representing `Lcz` as its full name, e.g. `"Low plants"`
+///
+/// Use with `#[serde(with = "urban_classifier::lcz::serde_name")]`.
+/// Unknown classes fail to serialize.
+pub mod serde_name {
+    use super::Lcz;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    /// Serialize a class as its full name
+    pub fn serialize<S: Serializer>(lcz: &Lcz, serializer: S) -> Result<S::Ok, S::Error> {
+        if !lcz.is_standard() {
+            return Err(ser::Error::custom(format!(
+                "LCZ code {} has no name",
+                lcz.to_code()
+            )));
+        }
+        serializer.serialize_str(lcz.full_name())
+    }
+
+    /// Deserialize a class from its full name, ignoring case
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Lcz, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Lcz::iter()
+            .find(|lcz| lcz.full_name().eq_ignore_ascii_case(name.trim()))
+            .ok_or_else(|| de::Error::custom(format!("Unrecognised LCZ name '{}'", name)))
+    }
+}
+
+/// One row of the standard LCZ map legend
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LegendEntry {
+    /// Numeric code (1-17)
+    pub code: u8,
+    /// WUDAPT label (`1`-`10`, `A`-`G`)
+    pub letter: &'static str,
+    /// Full class name
+    pub name: &'static str,
+    /// Simplified category
+    pub category: LczCategory,
+    /// WUDAPT map colour as `#rrggbb`
+    pub color: &'static str,
+}
+
+/// Legend entries of the 17 standard classes in code order
+///
+/// # Examples
+/// ```
+/// use urban_classifier::lcz::{legend, legend_to_csv};
+///
+/// let entries = legend();
+/// assert_eq!(entries[13].letter, "D");
+/// assert!(legend_to_csv(&entries).starts_with("code,letter,name,category,color\n"));
+/// ```
+pub fn legend() -> Vec<LegendEntry> {
+    Lcz::iter()
+        .map(|lcz| LegendEntry {
+            code: lcz.to_code(),
+            letter: lcz.letter().expect("standard classes have a label"),
+            name: lcz.full_name(),
+            category: lcz.simple_category(),
+            color: lcz.color().expect("standard classes have a colour"),
+        })
+        .collect()
+}
+
+/// Serialize legend entries as a JSON array of objects
+pub fn legend_to_json(entries: &[LegendEntry]) -> String {
+    serde_json::to_string_pretty(entries).expect("legend entries always serialize")
+}
+
+/// Serialize legend entries as CSV with a header row
+///
+/// Fields containing commas or quotes, such as `Bush, scrub`, are quoted.
+pub fn legend_to_csv(entries: &[LegendEntry]) -> String {
+    let quote = |field: &str| {
+        if field.contains([',', '"', '\n']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    };
+    let mut csv = String::from("code,letter,name,category,color\n");
+    for entry in entries {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            entry.code,
+            quote(entry.letter),
+            quote(entry.name),
+            entry.category,
+            entry.color
+        ));
+    }
+    csv
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -198,6 +616,16 @@ mod tests {
         }
     }
 
+    /// Test that `ALL` lists every standard class once, in code order
+    #[test]
+    fn test_lcz_all() {
+        assert_eq!(Lcz::iter().count(), 17);
+        for (index, lcz) in Lcz::iter().enumerate() {
+            assert_eq!(usize::from(lcz.to_code()), index + 1);
+            assert!(lcz.is_standard());
+        }
+    }
+
     /// Test handling of invalid/unknown LCZ codes
     #[test]
     fn test_unknown_lcz() {
@@ -205,6 +633,50 @@ mod tests {
         assert_eq!(unknown, Lcz::Unknown(99));
         assert_eq!(unknown.to_code(), 99);
         assert_eq!(unknown.full_name(), "Unknown");
+        assert!(!unknown.is_standard());
+        assert!(Lcz::from_code(17).is_standard());
+    }
+
+    /// Test that classes sort by code and work as map keys
+    #[test]
+    fn test_lcz_ordering() {
+        let mut classes = vec![
+            Lcz::Water,
+            Lcz::Unknown(0),
+            Lcz::HeavyIndustry,
+            Lcz::DenseTrees,
+        ];
+        classes.sort();
+        assert_eq!(
+            classes,
+            vec![
+                Lcz::Unknown(0),
+                Lcz::HeavyIndustry,
+                Lcz::DenseTrees,
+                Lcz::Water
+            ]
+        );
+        assert!(Lcz::Water < Lcz::Unknown(99));
+        assert!(Lcz::OpenMidRise < Lcz::Unknown(5));
+
+        let counts: std::collections::BTreeMap<Lcz, usize> =
+            [(Lcz::LowPlants, 2), (Lcz::CompactHighRise, 1)].into();
+        assert_eq!(counts.keys().next(), Some(&Lcz::CompactHighRise));
+    }
+
+    /// Test strict conversion of valid and invalid codes
+    #[test]
+    fn test_lcz_try_from_code() {
+        for lcz in Lcz::iter() {
+            assert_eq!(Lcz::try_from(lcz.to_code()), Ok(lcz));
+        }
+        for code in [0, 18, 255] {
+            assert_eq!(Lcz::try_from_code(code), Err(InvalidLczCode { code }));
+        }
+        assert_eq!(
+            InvalidLczCode { code: 0 }.to_string(),
+            "Invalid LCZ code 0: expected 1-17"
+        );
     }
 
     /// Test correct assignment of LCZ classes to simplified categories
@@ -226,6 +698,48 @@ mod tests {
         assert_eq!(Lcz::Water.simple_category(), LczCategory::Rural);
     }
 
+    /// Test WUDAPT labels of built and natural classes
+    #[test]
+    fn test_lcz_letter() {
+        assert_eq!(Lcz::CompactHighRise.letter(), Some("1"));
+        assert_eq!(Lcz::HeavyIndustry.letter(), Some("10"));
+        assert_eq!(Lcz::DenseTrees.letter(), Some("A"));
+        assert_eq!(Lcz::Water.letter(), Some("G"));
+        assert_eq!(Lcz::Unknown(0).letter(), None);
+
+        for lcz in Lcz::iter() {
+            assert_eq!(Lcz::from_letter(lcz.letter().unwrap()), Some(lcz));
+        }
+        assert_eq!(Lcz::from_letter("H"), None);
+        assert_eq!(Lcz::from_letter("0"), None);
+    }
+
+    /// Test display and parsing of LCZ classes
+    #[test]
+    fn test_lcz_display_and_parse() {
+        assert_eq!(Lcz::OpenLowRise.to_string(), "LCZ 6 — Open low-rise");
+        assert_eq!(Lcz::Unknown(99).to_string(), "LCZ 99 — Unknown");
+
+        assert_eq!("14".parse(), Ok(Lcz::LowPlants));
+        assert_eq!(" lcz10 ".parse(), Ok(Lcz::HeavyIndustry));
+        assert_eq!("g".parse(), Ok(Lcz::Water));
+        assert_eq!("Compact mid-rise".parse(), Ok(Lcz::CompactMidRise));
+        assert_eq!("BARE ROCK OR PAVED".parse(), Ok(Lcz::BareRockPaved));
+
+        for lcz in Lcz::iter() {
+            assert_eq!(lcz.to_string().parse(), Ok(lcz));
+        }
+
+        for invalid in ["0", "18", "H", "Unknown", "", "LCZ"] {
+            assert_eq!(
+                invalid.parse::<Lcz>(),
+                Err(ParseLczError {
+                    input: invalid.to_string()
+                })
+            );
+        }
+    }
+
     /// Test string representation of simplified categories
     #[test]
     fn test_category_as_ref() {
@@ -233,4 +747,85 @@ mod tests {
         assert_eq!(LczCategory::Suburban.as_ref(), "Suburban");
         assert_eq!(LczCategory::Rural.as_ref(), "Rural");
     }
+
+    /// Test display, parsing and serde of categories
+    #[test]
+    fn test_lcz_category_text() {
+        assert_eq!(LczCategory::iter().count(), 4);
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Entry {
+            category: LczCategory,
+        }
+        for category in LczCategory::iter() {
+            assert_eq!(category.to_string().parse(), Ok(category));
+            let text = toml::to_string(&Entry { category }).unwrap();
+            assert_eq!(text.trim(), format!("category = \"{}\"", category));
+            assert_eq!(toml::from_str::<Entry>(&text).unwrap(), Entry { category });
+        }
+        assert_eq!(" SUBURBAN ".parse(), Ok(LczCategory::Suburban));
+        assert_eq!(
+            "".parse::<LczCategory>(),
+            Err(ParseLczCategoryError {
+                input: String::new()
+            })
+        );
+    }
+
+    /// Test the code, label and name serde representations
+    #[test]
+    fn test_lcz_serde_representations() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Station {
+            #[serde(with = "serde_code")]
+            code: Lcz,
+            #[serde(with = "serde_letter")]
+            letter: Lcz,
+            #[serde(with = "serde_name")]
+            name: Lcz,
+        }
+
+        let station = Station {
+            code: Lcz::LowPlants,
+            letter: Lcz::LowPlants,
+            name: Lcz::LowPlants,
+        };
+        let text = toml::to_string(&station).unwrap();
+        assert_eq!(text, "code = 14\nletter = \"D\"\nname = \"Low plants\"\n");
+        assert_eq!(toml::from_str::<Station>(&text).unwrap(), station);
+
+        let lenient = "code = 99\nletter = \"d\"\nname = \"LOW PLANTS\"";
+        let parsed = toml::from_str::<Station>(lenient).unwrap();
+        assert_eq!(parsed.code, Lcz::Unknown(99));
+        assert_eq!(parsed.name, Lcz::LowPlants);
+
+        assert!(toml::from_str::<Station>("code = 1\nletter = \"H\"\nname = \"Water\"").is_err());
+        let unknown = Station {
+            code: Lcz::Unknown(0),
+            letter: Lcz::Unknown(0),
+            name: Lcz::Water,
+        };
+        assert!(toml::to_string(&unknown).is_err());
+    }
+
+    /// Test legend entries and their JSON and CSV forms
+    #[test]
+    fn test_legend() {
+        let entries = legend();
+        assert_eq!(entries.len(), 17);
+        assert_eq!(entries[0].color, "#8c0000");
+        assert_eq!(entries[16].name, "Water");
+        assert_eq!(Lcz::Unknown(0).color(), None);
+
+        let json: serde_json::Value = serde_json::from_str(&legend_to_json(&entries)).unwrap();
+        assert_eq!(json[12]["name"], "Bush, scrub");
+        assert_eq!(json[12]["category"], "Rural");
+        assert_eq!(json[12]["code"], 13);
+
+        let csv = legend_to_csv(&entries);
+        assert_eq!(csv.lines().count(), 18);
+        assert_eq!(
+            csv.lines().nth(13),
+            Some("13,C,\"Bush, scrub\",Rural,#648525")
+        );
+    }
 }
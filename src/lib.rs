@@ -7,14 +7,64 @@
 //! # Main Components
 //!
 //! - **Classifier**: The main `UrbanClassifier` struct that performs classification
+//! - **Builder**: Fluent configuration of the raster, band, policies and caching before opening
 //! - **LCZ System**: Enums and types representing the 17 Local Climate Zone categories
+//! - **Localized Names**: Class names in German, French, Spanish, Portuguese and Chinese
+//! - **LCZ Variants**: Subclasses, mixed classes and surface states such as `LCZ 2_3` or `LCZ Ab`
+//! - **Descriptions**: Stewart & Oke class definitions and typical land uses for legends and reports
+//! - **LCZ Properties**: Stewart & Oke (2012) geometric, surface and thermal property ranges per class
+//! - **Column Names**: Opt-in alias matching, coordinate detection, swap correction and string parsing
+//! - **Requests**: Per-run column names, input CRS, overrides and additional outputs in one options struct
+//! - **Typed Results**: Per-station `Lcz` records returned alongside the DataFrame
+//! - **Configuration**: TOML files describing the raster, columns, policies and overrides
+//! - **Code Mapping**: Translation of alternative raster encodings to canonical LCZ codes
+//! - **Category Schemes**: User-defined grouping of LCZ classes into Urban, Suburban and Rural
 //! - **Spatial Operations**: GeoTIFF reading and coordinate transformation utilities
+//! - **Raster Info**: Extent, resolution, CRS and nodata metadata of the loaded map
+//! - **Export**: Clipped GeoTIFF extracts of the LCZ map for a study area
+//! - **Provenance**: Data citation, product version and checksum of the loaded map
+//! - **Download**: Fetching and caching the global WUDAPT map on first use
+//! - **Mosaics**: VRT mosaics of tiled LCZ products opened as a single raster
+//! - **Open Options**: GDAL driver open options and selection of the LCZ band
+//! - **Reprojection**: On-the-fly warping to an equal-area CRS for exact buffers and areas
+//! - **Remote Rasters**: Mapping of HTTP, S3 and GCS URLs to GDAL virtual file systems
+//! - **Raster Sources**: Pluggable raster backends behind the `RasterSource` trait
+//! - **GeoTIFF Backend**: Pure-Rust reader for simple single-band GeoTIFFs (`no-gdal` feature)
+//! - **Bands**: Band selection and single-pass sampling of auxiliary bands such as probability
+//! - **Composition**: Per-class LCZ histograms around stations and class areas in km² over regions
+//! - **Distance**: Ring search for the nearest pixel of a class, in pixels and metres
+//! - **Overviews**: Reduced-resolution reads for large-radius buffer statistics
+//! - **Preloading**: Optional in-memory copy of the LCZ band for repeated sampling
+//! - **Tile Cache**: LRU cache of decoded raster blocks for clustered stations
+//! - **Pairing**: Urban–rural station pairing for urban heat island studies
+//! - **Smoothing**: Majority filtering of LCZ windows and of the pixels under stations
+//! - **Stability**: Sensitivity of each station's class to positional uncertainty
+//! - **Accuracy**: Confusion matrix, overall accuracy and kappa against reference labels
+//! - **Validation**: Pre-flight report of null, invalid and out-of-extent station coordinates
+//! - **Warnings**: Post-run report of suspicious coordinates, water and nodata samples and unused overrides
 //! - **Error Handling**: Custom error types for robust error reporting
+//! - **Diagnostics**: miette diagnostics with codes, help and labels for CLI output (`diagnostics` feature)
+//! - **Failure Reports**: Fail-fast or collect-all handling of stations that cannot be classified
+//! - **Dry Runs**: Planned pixel accesses and raster coverage without reading the raster
+//! - **Metrics**: Per-stage wall time and raster access counts of a classification run
+//! - **Expected UHI**: Literature ranges of nocturnal UHI intensity per class (`uhi` feature)
+//! - **Tracing**: Spans and debug events for each classification stage (`tracing` feature)
+//! - **H3 Aggregation**: Dominant class and class mix per H3 cell (`h3` feature)
+//! - **Test Utilities**: Mock and in-memory raster classifiers for downstream tests (`test-utils` feature)
 //! - **Python Bindings**: Optional PyO3 bindings for Python integration
 //!
+//! # GDAL
+//!
+//! GDAL is linked through the default `gdal` feature. Without it, classifiers
+//! are created from a `RasterSource` or, with the `no-gdal` feature, from a
+//! GeoTIFF; the builder, export, mosaics, overviews, reprojection, distance
+//! search and the Python bindings need GDAL and are not compiled. Examples
+//! that open a raster file are ignored by doctests without the feature.
+//!
 //! # Usage
 //!
-//! ```no_run
+#![cfg_attr(feature = "gdal", doc = "```no_run")]
+#![cfg_attr(not(feature = "gdal"), doc = "```ignore")]
 //! use urban_classifier::UrbanClassifier;
 //! use polars::prelude::*;
 //!
@@ -36,12 +86,81 @@
 //!     "latitude",
 //!     None
 //! ).unwrap();
+//!
+//! // Or, with the conventional column names above
+//! let result = classifier.classify(&df).unwrap();
 //! ```
 
+pub mod accuracy;
+pub mod bands;
+pub mod cache;
+pub mod category;
 pub mod classifier;
+pub mod columns;
+pub mod composition;
+pub mod config;
+pub mod descriptions;
+pub mod download;
 pub mod error;
+pub mod failures;
+pub mod info;
 pub mod lcz;
+pub mod locale;
+pub mod mapping;
+pub mod metrics;
+pub mod open;
+pub mod pairing;
+pub mod plan;
+pub mod preload;
+pub mod properties;
+pub mod provenance;
+pub mod records;
+pub mod remote;
+pub mod request;
+pub mod smoothing;
+pub mod source;
 pub mod spatial;
+pub mod stability;
+mod trace;
+pub mod validation;
+pub mod variant;
+pub mod warnings;
+
+#[cfg(feature = "gdal")]
+pub mod builder;
+
+#[cfg(feature = "gdal")]
+pub mod distance;
+
+#[cfg(feature = "gdal")]
+pub mod export;
+
+#[cfg(feature = "gdal")]
+pub mod mosaic;
+
+#[cfg(feature = "gdal")]
+pub mod overview;
+
+#[cfg(feature = "gdal")]
+mod pool;
+
+#[cfg(feature = "gdal")]
+pub mod warp;
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostic;
+
+#[cfg(feature = "h3")]
+pub mod h3;
+
+#[cfg(feature = "no-gdal")]
+pub mod geotiff;
+
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+
+#[cfg(feature = "uhi")]
+pub mod uhi;
 
 #[cfg(feature = "python")]
 pub mod python;
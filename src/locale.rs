@@ -0,0 +1,269 @@
+//! Localized LCZ Class Names
+//!
+//! Reports generated from classification output are often written for
+//! non-English audiences. `Lcz::full_name_localized` returns class names in
+//! the languages of the major WUDAPT contributor communities, so callers do
+//! not need to maintain their own string tables.
+//!
+//! # Examples
+//!
+//! ```
+//! use urban_classifier::locale::Language;
+//! use urban_classifier::Lcz;
+//!
+//! let language: Language = "de-DE".parse()?;
+//! assert_eq!(Lcz::DenseTrees.full_name_localized(language), "Dichter Baumbestand");
+//! assert_eq!(Lcz::Water.full_name_localized(Language::French), "Eau");
+//! # Ok::<(), urban_classifier::locale::ParseLanguageError>(())
+//! ```
+
+use crate::lcz::Lcz;
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Language of localized class names
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    /// English, as returned by `Lcz::full_name`
+    #[default]
+    English,
+    /// German
+    German,
+    /// French
+    French,
+    /// Spanish
+    Spanish,
+    /// Portuguese
+    Portuguese,
+    /// Simplified Chinese
+    Chinese,
+}
+
+impl Language {
+    /// Every supported language
+    pub const ALL: [Language; 6] = [
+        Language::English,
+        Language::German,
+        Language::French,
+        Language::Spanish,
+        Language::Portuguese,
+        Language::Chinese,
+    ];
+
+    /// ISO 639-1 code of the language
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::German => "de",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::Portuguese => "pt",
+            Language::Chinese => "zh",
+        }
+    }
+
+    /// Class names in code order, followed by the name of unknown classes
+    fn names(&self) -> Option<&'static [&'static str; 18]> {
+        match self {
+            Language::English => None,
+            Language::German => Some(&GERMAN),
+            Language::French => Some(&FRENCH),
+            Language::Spanish => Some(&SPANISH),
+            Language::Portuguese => Some(&PORTUGUESE),
+            Language::Chinese => Some(&CHINESE),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+/// Error returned when a language tag names no supported language
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Unsupported language '{input}': expected one of en, de, fr, es, pt or zh")]
+pub struct ParseLanguageError {
+    /// The string that failed to parse
+    pub input: String,
+}
+
+impl FromStr for Language {
+    type Err = ParseLanguageError;
+
+    /// Parse an ISO 639-1 code, ignoring case and any region or script
+    /// subtag, e.g. `fr`, `pt-BR` or `zh_Hans`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let primary = s.trim().split(['-', '_']).next().unwrap_or_default();
+        Language::ALL
+            .into_iter()
+            .find(|language| language.code().eq_ignore_ascii_case(primary))
+            .ok_or_else(|| ParseLanguageError {
+                input: s.to_string(),
+            })
+    }
+}
+
+const GERMAN: [&str; 18] = [
+    "Kompakte Hochhausbebauung",
+    "Kompakte mittelhohe Bebauung",
+    "Kompakte niedrige Bebauung",
+    "Offene Hochhausbebauung",
+    "Offene mittelhohe Bebauung",
+    "Offene niedrige Bebauung",
+    "Niedrige Leichtbauweise",
+    "Großflächige niedrige Bebauung",
+    "Vereinzelte Bebauung",
+    "Schwerindustrie",
+    "Dichter Baumbestand",
+    "Lockerer Baumbestand",
+    "Gebüsch, Gestrüpp",
+    "Niedrige Vegetation",
+    "Fels oder versiegelt",
+    "Offener Boden oder Sand",
+    "Wasser",
+    "Unbekannt",
+];
+
+const FRENCH: [&str; 18] = [
+    "Ensemble compact de tours",
+    "Ensemble compact d'immeubles",
+    "Ensemble compact de maisons",
+    "Ensemble de tours espacées",
+    "Ensemble d'immeubles espacés",
+    "Ensemble de maisons espacées",
+    "Ensemble de bâtiments légers",
+    "Grands bâtiments bas",
+    "Bâti épars",
+    "Industrie lourde",
+    "Arbres denses",
+    "Arbres épars",
+    "Buissons, broussailles",
+    "Végétation basse",
+    "Roche nue ou revêtement",
+    "Sol nu ou sable",
+    "Eau",
+    "Inconnu",
+];
+
+const SPANISH: [&str; 18] = [
+    "Edificios altos compactos",
+    "Edificios medianos compactos",
+    "Edificios bajos compactos",
+    "Edificios altos abiertos",
+    "Edificios medianos abiertos",
+    "Edificios bajos abiertos",
+    "Edificios bajos de materiales ligeros",
+    "Edificios bajos de gran tamaño",
+    "Edificación dispersa",
+    "Industria pesada",
+    "Arbolado denso",
+    "Arbolado disperso",
+    "Arbustos, matorral",
+    "Vegetación baja",
+    "Roca desnuda o pavimento",
+    "Suelo desnudo o arena",
+    "Agua",
+    "Desconocido",
+];
+
+const PORTUGUESE: [&str; 18] = [
+    "Edifícios altos compactos",
+    "Edifícios médios compactos",
+    "Edifícios baixos compactos",
+    "Edifícios altos espaçados",
+    "Edifícios médios espaçados",
+    "Edifícios baixos espaçados",
+    "Edifícios baixos de materiais leves",
+    "Edifícios baixos de grande porte",
+    "Edificação esparsa",
+    "Indústria pesada",
+    "Árvores densas",
+    "Árvores esparsas",
+    "Arbustos, mato",
+    "Vegetação rasteira",
+    "Rocha exposta ou pavimento",
+    "Solo exposto ou areia",
+    "Água",
+    "Desconhecido",
+];
+
+const CHINESE: [&str; 18] = [
+    "紧凑高层",
+    "紧凑中层",
+    "紧凑低层",
+    "开敞高层",
+    "开敞中层",
+    "开敞低层",
+    "轻质低层",
+    "大型低层",
+    "零散建筑",
+    "重工业",
+    "密集树木",
+    "稀疏树木",
+    "灌木",
+    "低矮植被",
+    "裸岩或铺装地面",
+    "裸土或沙地",
+    "水体",
+    "未知",
+];
+
+impl Lcz {
+    /// Get the full name of the LCZ class in `language`
+    ///
+    /// English names are those of `full_name`.
+    pub fn full_name_localized(&self, language: Language) -> &'static str {
+        let Some(names) = language.names() else {
+            return self.full_name();
+        };
+        match self {
+            Lcz::Unknown(_) => names[17],
+            lcz => names[usize::from(lcz.to_code()) - 1],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that every language names every class, with distinct names
+    #[test]
+    fn test_localized_names() {
+        for language in Language::ALL {
+            let mut names: Vec<&str> = Lcz::iter()
+                .map(|lcz| lcz.full_name_localized(language))
+                .collect();
+            names.sort_unstable();
+            names.dedup();
+            assert_eq!(names.len(), 17, "{}", language);
+        }
+        assert_eq!(
+            Lcz::OpenLowRise.full_name_localized(Language::English),
+            Lcz::OpenLowRise.full_name()
+        );
+        assert_eq!(
+            Lcz::Unknown(0).full_name_localized(Language::Spanish),
+            "Desconocido"
+        );
+        assert_eq!(
+            Lcz::LowPlants.full_name_localized(Language::Portuguese),
+            "Vegetação rasteira"
+        );
+    }
+
+    /// Test parsing of language tags
+    #[test]
+    fn test_parse_language() {
+        assert_eq!("EN".parse(), Ok(Language::English));
+        assert_eq!("pt-BR".parse(), Ok(Language::Portuguese));
+        assert_eq!("zh_Hans".parse(), Ok(Language::Chinese));
+        assert!("ja".parse::<Language>().is_err());
+        for language in Language::ALL {
+            assert_eq!(language.to_string().parse(), Ok(language));
+        }
+    }
+}
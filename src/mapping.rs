@@ -0,0 +1,108 @@
+//! Raw Raster Value to LCZ Code Mapping
+//!
+//! The WUDAPT global map stores LCZ classes as 1-17, but other LCZ products use
+//! different encodings: natural classes as 101-107 (for LCZ A-G), or 0 and 255
+//! as nodata. A `CodeMapping` translates raw raster values into canonical LCZ
+//! codes before they reach `Lcz::from_code`.
+//!
+//! # Semantics
+//!
+//! - Values with an explicit mapping are replaced by their target code
+//! - Values marked as nodata map to `None` and produce null output columns
+//! - All other values pass through unchanged
+//!
+//! # Examples
+//!
+//! ```
+//! use urban_classifier::mapping::CodeMapping;
+//!
+//! let mapping = CodeMapping::natural_101_107().with_nodata(0);
+//! assert_eq!(mapping.apply(104), Some(14));
+//! assert_eq!(mapping.apply(6), Some(6));
+//! assert_eq!(mapping.apply(0), None);
+//! ```
+
+use std::collections::HashMap;
+
+/// Mapping from raw raster values to canonical LCZ codes (or nodata)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CodeMapping {
+    codes: HashMap<u8, Option<u8>>,
+}
+
+impl CodeMapping {
+    /// Create an identity mapping where every value passes through unchanged
+    pub fn identity() -> Self {
+        Self::default()
+    }
+
+    /// Create a mapping for products encoding natural classes A-G as 101-107
+    pub fn natural_101_107() -> Self {
+        (101..=107).fold(Self::default(), |mapping, raw| {
+            mapping.with_remap(raw, raw - 90)
+        })
+    }
+
+    /// Map a raw value to a canonical LCZ code
+    pub fn with_remap(mut self, raw: u8, code: u8) -> Self {
+        self.codes.insert(raw, Some(code));
+        self
+    }
+
+    /// Treat a raw value as nodata
+    pub fn with_nodata(mut self, raw: u8) -> Self {
+        self.codes.insert(raw, None);
+        self
+    }
+
+    /// Check whether the mapping leaves every value unchanged
+    pub fn is_identity(&self) -> bool {
+        self.codes.iter().all(|(raw, code)| *code == Some(*raw))
+    }
+
+    /// Translate a raw raster value, returning `None` for nodata
+    pub fn apply(&self, raw: u8) -> Option<u8> {
+        match self.codes.get(&raw) {
+            Some(mapped) => *mapped,
+            None => Some(raw),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that unmapped values pass through unchanged
+    #[test]
+    fn test_identity_mapping() {
+        let mapping = CodeMapping::identity();
+        assert!(mapping.is_identity());
+        for raw in 0..=255u8 {
+            assert_eq!(mapping.apply(raw), Some(raw));
+        }
+    }
+
+    /// Test the 101-107 natural class encoding
+    #[test]
+    fn test_natural_101_107() {
+        let mapping = CodeMapping::natural_101_107();
+        assert!(!mapping.is_identity());
+        assert_eq!(mapping.apply(101), Some(11));
+        assert_eq!(mapping.apply(107), Some(17));
+        assert_eq!(mapping.apply(10), Some(10));
+    }
+
+    /// Test nodata and custom remaps, with later entries taking precedence
+    #[test]
+    fn test_nodata_and_remap() {
+        let mapping = CodeMapping::identity()
+            .with_nodata(255)
+            .with_remap(0, 17)
+            .with_nodata(0);
+
+        assert_eq!(mapping.apply(255), None);
+        assert_eq!(mapping.apply(0), None);
+        assert_eq!(mapping.apply(3), Some(3));
+    }
+}
@@ -0,0 +1,73 @@
+//! Classification Stage Metrics
+//!
+//! `UrbanClassifier::run_classification_with_metrics` reports how long each
+//! stage of a run took and how many samples reached the raster, so slow runs
+//! can be profiled without external tools.
+//!
+//! # Stages
+//!
+//! - **Validation**: Schema checks and reading station IDs and coordinates
+//! - **Transform**: Preparing the CRS transform and locating each station's pixel
+//! - **Sampling**: Reading each station's raster value
+//! - **Columns**: Overrides, validation-mode checks and building output columns
+
+use std::time::Duration;
+
+/// Wall time per stage and raster access counts of one classification run
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ClassificationMetrics {
+    /// Number of stations classified
+    pub stations: usize,
+    /// Time spent validating the schema and extracting coordinates
+    pub validation: Duration,
+    /// Time spent preparing and applying coordinate transforms
+    pub transform: Duration,
+    /// Time spent sampling raster values
+    pub sampling: Duration,
+    /// Time spent applying overrides and building output columns
+    pub columns: Duration,
+    /// Wall time of the whole run
+    pub total: Duration,
+    /// Reads issued to the raster source, including tile cache block reads
+    pub raster_reads: u64,
+    /// Samples served from the tile cache
+    pub cache_hits: u64,
+    /// Samples served from a preloaded band
+    pub preloaded_samples: u64,
+}
+
+impl ClassificationMetrics {
+    /// Stations classified per second of total wall time
+    ///
+    /// Returns `None` if no time was measured.
+    pub fn points_per_second(&self) -> Option<f64> {
+        let seconds = self.total.as_secs_f64();
+        (seconds > 0.0).then(|| self.stations as f64 / seconds)
+    }
+}
+
+/// Where samples of a run were served from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct SampleCounts {
+    pub(crate) raster_reads: u64,
+    pub(crate) cache_hits: u64,
+    pub(crate) preloaded: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test throughput computation
+    #[test]
+    fn test_points_per_second() {
+        assert_eq!(ClassificationMetrics::default().points_per_second(), None);
+
+        let metrics = ClassificationMetrics {
+            stations: 500,
+            total: Duration::from_millis(250),
+            ..Default::default()
+        };
+        assert_eq!(metrics.points_per_second(), Some(2000.0));
+    }
+}
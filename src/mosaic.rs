@@ -0,0 +1,199 @@
+//! VRT Mosaics of Tiled LCZ Products
+//!
+//! National and regional LCZ products are often distributed as a directory of
+//! GeoTIFF tiles rather than one file. `build_mosaic` combines the tiles into a
+//! GDAL VRT, a small XML file that GDAL reads as a single seamless raster, and
+//! `UrbanClassifier::from_tiles` opens a classifier on it directly.
+//!
+//! The tiles must share a CRS and resolution; GDAL skips tiles that do not
+//! match the first one. Where tiles overlap, the one listed last wins.
+//!
+//! # Examples
+//! ```no_run
+//! use std::path::Path;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! // Persist the mosaic so later runs can open it with `UrbanClassifier::new`
+//! let classifier =
+//!     UrbanClassifier::from_tiles("path/to/lcz_tiles", Some(Path::new("lcz_mosaic.vrt")))?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use gdal::programs::raster::build_vrt;
+use gdal::Dataset;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counter giving each in-memory mosaic of this process a unique path
+static MOSAIC_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// List the GeoTIFF tiles (`.tif` or `.tiff`) directly inside `dir`, sorted by name
+///
+/// # Errors
+/// Returns `ClassifierError::FileNotFound` if `dir` does not exist and
+/// `ClassifierError::InvalidParameter` if it contains no GeoTIFF tiles.
+pub fn find_tiles<P: AsRef<Path>>(dir: P) -> Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    if !dir.is_dir() {
+        return Err(ClassifierError::FileNotFound {
+            path: dir.to_string_lossy().to_string(),
+        });
+    }
+
+    let mut tiles = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        let is_tiff = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tif") || ext.eq_ignore_ascii_case("tiff"));
+        if is_tiff && path.is_file() {
+            tiles.push(path);
+        }
+    }
+    if tiles.is_empty() {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!("No GeoTIFF tiles found in {}", dir.display()),
+        });
+    }
+
+    tiles.sort();
+    Ok(tiles)
+}
+
+/// Build a VRT mosaic of the GeoTIFF tiles in `dir`
+///
+/// With `output`, the VRT is written to that path and can be reopened later;
+/// otherwise it only exists in memory.
+///
+/// # Errors
+/// Returns the errors of `find_tiles`, and a GDAL error if a tile cannot be
+/// opened or the VRT cannot be written.
+pub fn build_mosaic<P: AsRef<Path>>(dir: P, output: Option<&Path>) -> Result<Dataset> {
+    let tiles = find_tiles(dir)?
+        .iter()
+        .map(Dataset::open)
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(build_vrt(output, &tiles, None)?)
+}
+
+impl UrbanClassifier {
+    /// Create a classifier reading a mosaic of the GeoTIFF tiles in `dir`
+    ///
+    /// With `vrt_path`, the mosaic is persisted there, so later runs can skip
+    /// scanning the directory and use `UrbanClassifier::new(vrt_path)`.
+    /// Otherwise it is kept in GDAL's in-memory file system for the lifetime
+    /// of the process.
+    ///
+    /// # Errors
+    /// Returns the errors of `build_mosaic` and `UrbanClassifier::new`.
+    pub fn from_tiles<P: AsRef<Path>>(dir: P, vrt_path: Option<&Path>) -> Result<Self> {
+        let path = match vrt_path {
+            Some(path) => path.to_path_buf(),
+            None => PathBuf::from(format!(
+                "/vsimem/urban_classifier/mosaic_{}_{}.vrt",
+                std::process::id(),
+                MOSAIC_COUNTER.fetch_add(1, Ordering::Relaxed)
+            )),
+        };
+
+        // Close the VRT so that it is flushed before reopening it by path
+        drop(build_mosaic(dir, Some(&path))?);
+        if vrt_path.is_some() {
+            Self::new(path)
+        } else {
+            Self::from_dataset(Dataset::open(path)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gdal::raster::Buffer;
+    use gdal::spatial_ref::SpatialRef;
+    use gdal::DriverManager;
+    use polars::prelude::*;
+
+    fn write_tile(path: &Path, origin_lon: f64, value: u8) {
+        let driver = DriverManager::get_driver_by_name("GTiff").unwrap();
+        let mut dataset = driver
+            .create_with_band_type::<u8, _>(path, 10, 10, 1)
+            .unwrap();
+        dataset
+            .set_geo_transform(&[origin_lon, 0.1, 0.0, 52.0, 0.0, -0.1])
+            .unwrap();
+        dataset
+            .set_spatial_ref(&SpatialRef::from_epsg(4326).unwrap())
+            .unwrap();
+        let buffer = Buffer::new((10, 10), vec![value; 100]);
+        dataset
+            .rasterband(1)
+            .unwrap()
+            .write((0, 0), (10, 10), &buffer)
+            .unwrap();
+    }
+
+    /// Test that only GeoTIFF files are listed, in name order
+    #[test]
+    fn test_find_tiles() {
+        let dir = tempfile::tempdir().unwrap();
+        for name in ["b.TIF", "a.tiff", "readme.txt"] {
+            std::fs::write(dir.path().join(name), b"").unwrap();
+        }
+        let tiles = find_tiles(dir.path()).unwrap();
+        assert_eq!(
+            tiles,
+            vec![dir.path().join("a.tiff"), dir.path().join("b.TIF")]
+        );
+
+        let empty = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            find_tiles(empty.path()),
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+        assert!(matches!(
+            find_tiles(dir.path().join("missing")),
+            Err(ClassifierError::FileNotFound { .. })
+        ));
+    }
+
+    /// Test that adjacent tiles are mosaicked and classified as one raster
+    #[test]
+    fn test_from_tiles() {
+        let dir = tempfile::tempdir().unwrap();
+        write_tile(&dir.path().join("west.tif"), -1.0, 2);
+        write_tile(&dir.path().join("east.tif"), 0.0, 14);
+
+        let mosaic = build_mosaic(dir.path(), None).unwrap();
+        assert_eq!(mosaic.raster_size(), (20, 10));
+
+        let df = df! {
+            "station_id" => ["west", "east"],
+            "longitude" => [-0.5, 0.5],
+            "latitude" => [51.5, 51.5],
+        }
+        .unwrap();
+        let codes = |classifier: &UrbanClassifier| -> Vec<Option<u32>> {
+            let result = classifier.classify(&df).unwrap();
+            result
+                .column("lcz_code")
+                .unwrap()
+                .u32()
+                .unwrap()
+                .into_iter()
+                .collect()
+        };
+
+        let out = tempfile::tempdir().unwrap();
+        let vrt_path = out.path().join("mosaic.vrt");
+        let classifier = UrbanClassifier::from_tiles(dir.path(), Some(&vrt_path)).unwrap();
+        assert!(vrt_path.exists());
+        assert_eq!(codes(&classifier), vec![Some(2), Some(14)]);
+
+        let in_memory = UrbanClassifier::from_tiles(dir.path(), None).unwrap();
+        assert_eq!(codes(&in_memory), vec![Some(2), Some(14)]);
+    }
+}
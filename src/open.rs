@@ -0,0 +1,159 @@
+//! Raster Open Options
+//!
+//! `UrbanClassifier::open_with` accepts GDAL open options, passed to the
+//! driver every time the file is opened, and the band holding the LCZ
+//! classes. WUDAPT maps store classes in band 1, but some LCZ products put
+//! them in band 2 or 3 next to probability or filter layers.
+//!
+//! Rasters exported without an embedded spatial reference can be opened by
+//! supplying the CRS with `assume_crs`. `with_warp_to` reprojects the raster
+//! on the fly, e.g. to an equal-area CRS; see the `warp` module.
+//!
+//! # Examples
+#![cfg_attr(feature = "gdal", doc = "```no_run")]
+#![cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+//! use urban_classifier::open::OpenOptions;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! let options = OpenOptions::new()
+//!     .with_open_option("NUM_THREADS", "ALL_CPUS")
+//!     .with_open_option("GTIFF_DIRECT_IO", "YES")
+//!     .with_band("lcz_filter");
+//! let classifier = UrbanClassifier::open_with("path/to/lcz_generator.tif", &options)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::bands::BandSelector;
+use crate::source::RasterCrs;
+use crate::spatial::CrsSpec;
+
+/// GDAL open options and LCZ band used when opening a raster
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpenOptions {
+    gdal_options: Vec<String>,
+    band: BandSelector,
+    assumed_crs: Option<RasterCrs>,
+    warp_to: Option<CrsSpec>,
+}
+
+impl Default for OpenOptions {
+    fn default() -> Self {
+        OpenOptions {
+            gdal_options: Vec::new(),
+            band: BandSelector::Index(1),
+            assumed_crs: None,
+            warp_to: None,
+        }
+    }
+}
+
+impl OpenOptions {
+    /// Options reading band 1 with the driver's defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a driver open option, e.g. `("NUM_THREADS", "ALL_CPUS")`
+    ///
+    /// Setting the same option twice keeps the last value.
+    pub fn with_open_option(mut self, name: &str, value: &str) -> Self {
+        let prefix = format!("{}=", name);
+        self.gdal_options
+            .retain(|option| !option.to_uppercase().starts_with(&prefix.to_uppercase()));
+        self.gdal_options.push(format!("{}{}", prefix, value));
+        self
+    }
+
+    /// Sample LCZ classes from another band, by 1-based index or description
+    pub fn with_band(mut self, band: impl Into<BandSelector>) -> Self {
+        self.band = band.into();
+        self
+    }
+
+    /// CRS to use when the raster has no spatial reference of its own
+    ///
+    /// A spatial reference embedded in the raster always takes precedence.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::open::OpenOptions;
+    /// use urban_classifier::source::RasterCrs;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let options = OpenOptions::new().assume_crs(RasterCrs::from_epsg(4326)?);
+    /// let classifier = UrbanClassifier::open_with("path/to/exported_lcz.tif", &options)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn assume_crs(mut self, crs: RasterCrs) -> Self {
+        self.assumed_crs = Some(crs);
+        self
+    }
+
+    /// Read the raster through a warped VRT in `crs`
+    ///
+    /// Pixels are resampled with nearest neighbour as they are read, so buffer
+    /// radii and class areas are computed in the units of `crs`.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::open::OpenOptions;
+    /// use urban_classifier::spatial::CrsSpec;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let options = OpenOptions::new().with_warp_to(CrsSpec::EQUAL_AREA);
+    /// let classifier = UrbanClassifier::open_with("path/to/wudapt.tif", &options)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_warp_to(mut self, crs: CrsSpec) -> Self {
+        self.warp_to = Some(crs);
+        self
+    }
+
+    /// Driver open options as `NAME=VALUE` strings
+    pub fn gdal_options(&self) -> &[String] {
+        &self.gdal_options
+    }
+
+    /// Band holding the LCZ classes
+    pub fn band(&self) -> &BandSelector {
+        &self.band
+    }
+
+    /// CRS assumed for rasters without a spatial reference
+    pub fn assumed_crs(&self) -> Option<&RasterCrs> {
+        self.assumed_crs.as_ref()
+    }
+
+    /// CRS the raster is reprojected to when opened
+    pub fn warp_crs(&self) -> Option<&CrsSpec> {
+        self.warp_to.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that options are formatted as NAME=VALUE and later values win
+    #[test]
+    fn test_open_options() {
+        let options = OpenOptions::new()
+            .with_open_option("NUM_THREADS", "2")
+            .with_open_option("GTIFF_DIRECT_IO", "YES")
+            .with_open_option("num_threads", "ALL_CPUS");
+        assert_eq!(
+            options.gdal_options(),
+            &["GTIFF_DIRECT_IO=YES", "num_threads=ALL_CPUS"]
+        );
+        assert_eq!(options.band(), &BandSelector::Index(1));
+        assert_eq!(options.assumed_crs(), None);
+        let options = options.with_band(3).assume_crs(RasterCrs::Wgs84);
+        assert_eq!(options.band(), &BandSelector::Index(3));
+        assert_eq!(options.assumed_crs(), Some(&RasterCrs::Wgs84));
+        assert_eq!(options.warp_crs(), None);
+        let options = options.with_warp_to(CrsSpec::Epsg(6933));
+        assert_eq!(options.warp_crs(), Some(&CrsSpec::Epsg(6933)));
+    }
+}
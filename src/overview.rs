@@ -0,0 +1,143 @@
+//! Overview Selection for Coarse Buffer Statistics
+//!
+//! Class composition within multi-kilometre buffers touches many thousands of
+//! full-resolution pixels per station. Cloud-optimised GeoTIFFs carry reduced
+//! resolution overviews, and reading from those instead gives nearly identical
+//! class percentages at a fraction of the I/O.
+//!
+//! # Selection Rule
+//!
+//! For each buffer, the coarsest overview is chosen in which the buffer radius
+//! still spans at least `min_radius_pixels` overview pixels along both axes.
+//! When no overview qualifies, the full-resolution band is used.
+//!
+//! Overviews of categorical rasters must be built with nearest-neighbour or
+//! mode resampling; averaged overviews produce meaningless LCZ codes.
+//! `spatial::build_overviews` adds mode overviews to files that have none.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use gdal::raster::RasterBand;
+
+/// An overview band and its downsampling factor relative to full resolution
+pub(crate) struct OverviewLevel<'a> {
+    pub(crate) band: RasterBand<'a>,
+    /// Full-resolution pixels per overview pixel along (x, y)
+    pub(crate) scale: (f64, f64),
+}
+
+impl OverviewLevel<'_> {
+    /// Convert a full-resolution pixel position to this overview's grid
+    pub(crate) fn to_overview_pixel(&self, pixel: isize, line: isize) -> (isize, isize) {
+        (
+            (pixel as f64 / self.scale.0).floor() as isize,
+            (line as f64 / self.scale.1).floor() as isize,
+        )
+    }
+
+    /// Convert a full-resolution pixel radius to this overview's grid
+    pub(crate) fn to_overview_radius(&self, radius: (usize, usize)) -> (usize, usize) {
+        (
+            (radius.0 as f64 / self.scale.0).round() as usize,
+            (radius.1 as f64 / self.scale.1).round() as usize,
+        )
+    }
+}
+
+/// List the overviews of a band with their downsampling factors
+pub(crate) fn overview_levels<'a>(band: &RasterBand<'a>) -> Result<Vec<OverviewLevel<'a>>> {
+    let (width, height) = band.size();
+    let mut levels = Vec::new();
+
+    for index in 0..band.overview_count()? {
+        let overview = band.overview(index as isize)?;
+        let (ov_width, ov_height) = overview.size();
+        if ov_width == 0 || ov_height == 0 {
+            continue;
+        }
+        levels.push(OverviewLevel {
+            scale: (
+                width as f64 / ov_width as f64,
+                height as f64 / ov_height as f64,
+            ),
+            band: overview,
+        });
+    }
+
+    Ok(levels)
+}
+
+/// Choose the coarsest overview in which a buffer keeps enough pixels
+///
+/// # Arguments
+/// * `radius` - Buffer radius in full-resolution pixels along (x, y)
+/// * `scales` - Downsampling factor of each overview
+/// * `min_radius_pixels` - Minimum radius in overview pixels along both axes
+///
+/// # Returns
+/// Index into `scales`, or `None` to use full resolution
+pub(crate) fn select_overview(
+    radius: (usize, usize),
+    scales: &[(f64, f64)],
+    min_radius_pixels: usize,
+) -> Option<usize> {
+    let min_radius = min_radius_pixels as f64;
+
+    scales
+        .iter()
+        .enumerate()
+        .filter(|(_, &(sx, sy))| sx > 1.0 || sy > 1.0)
+        .filter(|(_, &(sx, sy))| {
+            radius.0 as f64 / sx >= min_radius && radius.1 as f64 / sy >= min_radius
+        })
+        .max_by(|a, b| (a.1 .0 * a.1 .1).total_cmp(&(b.1 .0 * b.1 .1)))
+        .map(|(index, _)| index)
+}
+
+impl UrbanClassifier {
+    /// Read buffer statistics from raster overviews when the buffer is large
+    ///
+    /// Overviews are used while the buffer radius spans at least
+    /// `min_radius_pixels` overview pixels; `None` (the default) always reads
+    /// full resolution. Larger values trade speed for closer agreement with
+    /// full-resolution percentages.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt_cog.tif")?.with_overviews(Some(25));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_overviews(mut self, min_radius_pixels: Option<usize>) -> Self {
+        self.set_overview_min_radius(min_radius_pixels);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the coarsest qualifying overview is selected
+    #[test]
+    fn test_select_overview() {
+        let scales = [(2.0, 2.0), (4.0, 4.0), (8.0, 8.0), (16.0, 16.0)];
+
+        // 100 px radius: /4 = 25 qualifies, /8 = 12.5 does not
+        assert_eq!(select_overview((100, 100), &scales, 25), Some(1));
+
+        // Small buffers stay at full resolution
+        assert_eq!(select_overview((30, 30), &scales, 25), None);
+
+        // Both axes must keep enough pixels
+        assert_eq!(select_overview((400, 50), &scales, 25), Some(0));
+    }
+
+    /// Test that degenerate overviews are never selected
+    #[test]
+    fn test_select_overview_ignores_full_resolution() {
+        assert_eq!(select_overview((1000, 1000), &[(1.0, 1.0)], 1), None);
+        assert_eq!(select_overview((1000, 1000), &[], 1), None);
+    }
+}
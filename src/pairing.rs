@@ -0,0 +1,346 @@
+//! Urban–Rural Station Pairing
+//!
+//! Urban heat island (UHI) studies compare each urban station with nearby rural
+//! reference stations. This module takes a classified DataFrame (the output of
+//! `UrbanClassifier::run_classification`) and pairs every Urban station with its
+//! nearest Rural station(s), subject to distance and elevation constraints.
+//!
+//! # Output
+//!
+//! `pair_urban_rural` returns one row per pair with the columns:
+//! - `urban_station_id`, `rural_station_id`
+//! - `distance_km`: Great-circle distance between the stations
+//! - `elevation_diff_m`: Rural minus urban elevation (null without elevation data)
+//! - `rank`: 1 for the nearest rural station, 2 for the next, and so on
+//!
+//! Urban stations without any eligible rural station are omitted.
+
+use crate::columns::column_not_found;
+use crate::error::{ClassifierError, Result};
+use crate::lcz::LczCategory;
+use crate::spatial::haversine_distance_m;
+use polars::prelude::*;
+
+/// Constraints used when pairing urban stations with rural reference stations
+#[derive(Debug, Clone, PartialEq)]
+pub struct PairingConfig {
+    /// Maximum great-circle distance between paired stations, in kilometres
+    pub max_distance_km: f64,
+    /// Maximum absolute elevation difference in metres (requires an elevation column)
+    pub max_elevation_diff_m: Option<f64>,
+    /// Number of rural stations to pair with each urban station
+    pub rural_per_urban: usize,
+}
+
+impl Default for PairingConfig {
+    fn default() -> Self {
+        PairingConfig {
+            max_distance_km: 50.0,
+            max_elevation_diff_m: None,
+            rural_per_urban: 1,
+        }
+    }
+}
+
+/// A station extracted from the classified DataFrame
+struct PairingStation {
+    id: String,
+    lon: f64,
+    lat: f64,
+    elevation: Option<f64>,
+}
+
+/// Pair each Urban station with its nearest Rural station(s)
+///
+/// # Arguments
+/// * `classified_df` - Output of `run_classification` (must contain `simple_class`)
+/// * `station_id_col` - Name of the column containing station IDs
+/// * `lon_col` - Name of the column containing longitude values
+/// * `lat_col` - Name of the column containing latitude values
+/// * `elevation_col` - Optional name of a numeric elevation column (metres)
+/// * `config` - Distance and elevation constraints
+///
+/// # Returns
+/// A pairs DataFrame as described in the module documentation
+pub fn pair_urban_rural(
+    classified_df: &DataFrame,
+    station_id_col: &str,
+    lon_col: &str,
+    lat_col: &str,
+    elevation_col: Option<&str>,
+    config: &PairingConfig,
+) -> Result<DataFrame> {
+    if !config.max_distance_km.is_finite() || config.max_distance_km < 0.0 {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "max_distance_km must be a non-negative distance, got {}",
+                config.max_distance_km
+            ),
+        });
+    }
+    if let Some(max_diff) = config.max_elevation_diff_m {
+        if !max_diff.is_finite() || max_diff < 0.0 {
+            return Err(ClassifierError::InvalidParameter {
+                message: format!(
+                    "max_elevation_diff_m must be a non-negative height difference, got {}",
+                    max_diff
+                ),
+            });
+        }
+    }
+    if config.max_elevation_diff_m.is_some() && elevation_col.is_none() {
+        return Err(ClassifierError::InvalidParameter {
+            message: "max_elevation_diff_m requires an elevation column".to_string(),
+        });
+    }
+
+    let (urban, rural) = split_by_category(
+        classified_df,
+        station_id_col,
+        lon_col,
+        lat_col,
+        elevation_col,
+    )?;
+
+    let mut urban_ids = Vec::new();
+    let mut rural_ids = Vec::new();
+    let mut distances = Vec::new();
+    let mut elevation_diffs = Vec::new();
+    let mut ranks = Vec::new();
+
+    for station in &urban {
+        let mut candidates: Vec<(f64, &PairingStation)> = rural
+            .iter()
+            .map(|r| {
+                let distance_m = haversine_distance_m(station.lon, station.lat, r.lon, r.lat);
+                (distance_m / 1000.0, r)
+            })
+            .filter(|(distance, _)| *distance <= config.max_distance_km)
+            .filter(|(_, r)| match config.max_elevation_diff_m {
+                Some(max_diff) => match (station.elevation, r.elevation) {
+                    (Some(u), Some(v)) => (v - u).abs() <= max_diff,
+                    _ => false,
+                },
+                None => true,
+            })
+            .collect();
+
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for (rank, (distance, r)) in candidates
+            .into_iter()
+            .take(config.rural_per_urban)
+            .enumerate()
+        {
+            urban_ids.push(station.id.clone());
+            rural_ids.push(r.id.clone());
+            distances.push(distance);
+            elevation_diffs.push(match (station.elevation, r.elevation) {
+                (Some(u), Some(v)) => Some(v - u),
+                _ => None,
+            });
+            ranks.push(rank as u32 + 1);
+        }
+    }
+
+    Ok(DataFrame::new(vec![
+        Series::new("urban_station_id", urban_ids),
+        Series::new("rural_station_id", rural_ids),
+        Series::new("distance_km", distances),
+        Series::new("elevation_diff_m", elevation_diffs),
+        Series::new("rank", ranks),
+    ])?)
+}
+
+/// Split the classified stations into Urban and Rural groups (Suburban is ignored)
+fn split_by_category(
+    df: &DataFrame,
+    station_id_col: &str,
+    lon_col: &str,
+    lat_col: &str,
+    elevation_col: Option<&str>,
+) -> Result<(Vec<PairingStation>, Vec<PairingStation>)> {
+    let columns = df.get_column_names();
+    for column in [station_id_col, lon_col, lat_col, "simple_class"]
+        .into_iter()
+        .chain(elevation_col)
+    {
+        if !columns.contains(&column) {
+            return Err(column_not_found(df, column));
+        }
+    }
+
+    let ids = df
+        .column(station_id_col)?
+        .str()
+        .map_err(|_| ClassifierError::SchemaValidation {
+            message: format!(
+                "Station ID column '{}' must contain string data",
+                station_id_col
+            ),
+        })?;
+    let classes =
+        df.column("simple_class")?
+            .str()
+            .map_err(|_| ClassifierError::SchemaValidation {
+                message: "Column 'simple_class' must contain string data".to_string(),
+            })?;
+    let lons = float_column(df, lon_col)?;
+    let lats = float_column(df, lat_col)?;
+    let elevations = match elevation_col {
+        Some(column) => Some(float_column(df, column)?),
+        None => None,
+    };
+
+    let urban_label = LczCategory::Urban.as_ref();
+    let rural_label = LczCategory::Rural.as_ref();
+
+    let mut urban = Vec::new();
+    let mut rural = Vec::new();
+
+    for i in 0..df.height() {
+        let (Some(lon), Some(lat)) = (lons.get(i), lats.get(i)) else {
+            continue;
+        };
+        let station = PairingStation {
+            id: ids.get(i).unwrap_or("unknown").to_string(),
+            lon,
+            lat,
+            elevation: elevations.as_ref().and_then(|e| e.get(i)),
+        };
+
+        match classes.get(i) {
+            Some(class) if class == urban_label => urban.push(station),
+            Some(class) if class == rural_label => rural.push(station),
+            _ => {}
+        }
+    }
+
+    Ok((urban, rural))
+}
+
+/// Access a numeric column as f64, casting from other numeric types if needed
+fn float_column(df: &DataFrame, column: &str) -> Result<Float64Chunked> {
+    let series = df.column(column)?;
+    if !series.dtype().is_numeric() {
+        return Err(ClassifierError::SchemaValidation {
+            message: format!("Column '{}' must contain numeric data", column),
+        });
+    }
+    Ok(series.cast(&DataType::Float64)?.f64()?.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn classified_stations() -> DataFrame {
+        df! {
+            "station_id" => ["CITY", "PARK", "FARM", "FAR", "HILL", "SUBURB"],
+            "longitude" => [0.0, 0.05, 0.1, 3.0, 0.02, 0.01],
+            "latitude" => [51.5, 51.5, 51.5, 51.5, 51.5, 51.5],
+            "elevation" => [10.0, 15.0, 20.0, 10.0, 400.0, 12.0],
+            "simple_class" => ["Urban", "Rural", "Rural", "Rural", "Rural", "Suburban"],
+        }
+        .unwrap()
+    }
+
+    /// Test that the nearest rural stations are ranked by distance
+    #[test]
+    fn test_nearest_rural_pairs() {
+        let config = PairingConfig {
+            rural_per_urban: 2,
+            ..Default::default()
+        };
+        let pairs = pair_urban_rural(
+            &classified_stations(),
+            "station_id",
+            "longitude",
+            "latitude",
+            None,
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(pairs.height(), 2);
+        let rural = pairs.column("rural_station_id").unwrap().str().unwrap();
+        assert_eq!(rural.get(0), Some("HILL"));
+        assert_eq!(rural.get(1), Some("PARK"));
+
+        let ranks = pairs.column("rank").unwrap().u32().unwrap();
+        assert_eq!(ranks.get(0), Some(1));
+        assert_eq!(ranks.get(1), Some(2));
+    }
+
+    /// Test distance and elevation constraints
+    #[test]
+    fn test_pairing_constraints() {
+        let config = PairingConfig {
+            max_distance_km: 10.0,
+            max_elevation_diff_m: Some(50.0),
+            rural_per_urban: 5,
+        };
+        let pairs = pair_urban_rural(
+            &classified_stations(),
+            "station_id",
+            "longitude",
+            "latitude",
+            Some("elevation"),
+            &config,
+        )
+        .unwrap();
+
+        // HILL is too high and FAR is too distant
+        let rural = pairs.column("rural_station_id").unwrap().str().unwrap();
+        assert_eq!(pairs.height(), 2);
+        assert_eq!(rural.get(0), Some("PARK"));
+        assert_eq!(rural.get(1), Some("FARM"));
+
+        let diffs = pairs.column("elevation_diff_m").unwrap().f64().unwrap();
+        assert_eq!(diffs.get(0), Some(5.0));
+    }
+
+    /// Test that an elevation limit without elevation data is rejected
+    #[test]
+    fn test_elevation_limit_requires_column() {
+        let config = PairingConfig {
+            max_elevation_diff_m: Some(100.0),
+            ..Default::default()
+        };
+        let result = pair_urban_rural(
+            &classified_stations(),
+            "station_id",
+            "longitude",
+            "latitude",
+            None,
+            &config,
+        );
+        assert!(matches!(
+            result,
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+    }
+
+    /// Test that negative and non-finite elevation limits are rejected
+    #[test]
+    fn test_invalid_elevation_limit() {
+        for max_diff in [-1.0, f64::NAN, f64::INFINITY] {
+            let config = PairingConfig {
+                max_elevation_diff_m: Some(max_diff),
+                ..Default::default()
+            };
+            let result = pair_urban_rural(
+                &classified_stations(),
+                "station_id",
+                "longitude",
+                "latitude",
+                Some("elevation"),
+                &config,
+            );
+            assert!(matches!(
+                result,
+                Err(ClassifierError::InvalidParameter { .. })
+            ));
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Dry-Run Access Planning
+//!
+//! Before a long run against a remote COG it is worth checking that stations
+//! land where expected and how much of the raster will be read.
+//! `UrbanClassifier::plan_classification` performs the validation, coordinate
+//! transform and geo-to-pixel steps of `classify_with` but never reads the
+//! raster, and returns the pixel of each station with a coverage summary.
+//!
+//! # Cost Estimate
+//!
+//! `CoverageSummary::distinct_blocks` counts the raster blocks containing at
+//! least one station, which is the number of block reads a run with the tile
+//! cache enabled needs. Without the cache, each station is one read.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use crate::request::ClassificationRequest;
+use crate::source::{PixelLocator, RasterMetadata};
+use polars::prelude::*;
+use std::collections::HashSet;
+
+/// Where the stations of a planned run fall on the raster
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CoverageSummary {
+    /// Number of stations planned
+    pub stations: usize,
+    /// Stations located on a raster pixel
+    pub inside_extent: usize,
+    /// Stations located outside the raster
+    pub outside_extent: usize,
+    /// Stations whose coordinates are invalid or cannot be transformed
+    pub untransformable: usize,
+    /// Distinct pixels that would be sampled
+    pub distinct_pixels: usize,
+    /// Distinct raster blocks that would be read
+    pub distinct_blocks: usize,
+    /// Smallest (pixel, line, width, height) window containing every sampled pixel
+    pub pixel_window: Option<(isize, isize, usize, usize)>,
+}
+
+/// Planned pixel accesses of a classification run
+#[derive(Debug, Clone)]
+pub struct ClassificationPlan {
+    /// The station ID column followed by `pixel` and `line` (null when the
+    /// coordinate cannot be transformed) and `in_extent`
+    pub stations: DataFrame,
+    /// Coverage of the raster by the stations
+    pub summary: CoverageSummary,
+}
+
+/// Summarise station locations on a raster of the given size and block size
+pub(crate) fn summarize(
+    locations: &[Option<(isize, isize)>],
+    metadata: &RasterMetadata,
+) -> CoverageSummary {
+    let (block_width, block_height) = (
+        metadata.block_size.0.max(1) as isize,
+        metadata.block_size.1.max(1) as isize,
+    );
+    let mut summary = CoverageSummary {
+        stations: locations.len(),
+        ..Default::default()
+    };
+    let mut pixels = HashSet::new();
+    let mut blocks = HashSet::new();
+    let mut bounds: Option<(isize, isize, isize, isize)> = None;
+
+    for location in locations {
+        let Some((pixel, line)) = *location else {
+            summary.untransformable += 1;
+            continue;
+        };
+        if !in_extent(pixel, line, metadata) {
+            summary.outside_extent += 1;
+            continue;
+        }
+        summary.inside_extent += 1;
+        pixels.insert((pixel, line));
+        blocks.insert((pixel / block_width, line / block_height));
+        bounds = Some(match bounds {
+            None => (pixel, line, pixel, line),
+            Some((min_p, min_l, max_p, max_l)) => (
+                min_p.min(pixel),
+                min_l.min(line),
+                max_p.max(pixel),
+                max_l.max(line),
+            ),
+        });
+    }
+
+    summary.distinct_pixels = pixels.len();
+    summary.distinct_blocks = blocks.len();
+    summary.pixel_window = bounds.map(|(min_p, min_l, max_p, max_l)| {
+        (
+            min_p,
+            min_l,
+            (max_p - min_p + 1) as usize,
+            (max_l - min_l + 1) as usize,
+        )
+    });
+    summary
+}
+
+fn in_extent(pixel: isize, line: isize, metadata: &RasterMetadata) -> bool {
+    (0..metadata.width as isize).contains(&pixel) && (0..metadata.height as isize).contains(&line)
+}
+
+impl UrbanClassifier {
+    /// Locate every station on the raster without reading it
+    ///
+    /// Columns are resolved and coordinates prepared exactly as in
+    /// `classify_with`, so a plan checks the georeferencing of the real run.
+    ///
+    /// # Errors
+    /// Returns the schema errors `classify_with` would return; stations that
+    /// cannot be located are counted in the summary instead.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::request::ClassificationRequest;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("https://example.org/wudapt_lcz_global.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.12, -0.45],
+    ///     "latitude" => [51.5, 51.47],
+    /// }?;
+    /// let plan = classifier.plan_classification(&stations, &ClassificationRequest::new())?;
+    /// println!(
+    ///     "{} of {} stations on the map, {} blocks to read",
+    ///     plan.summary.inside_extent, plan.summary.stations, plan.summary.distinct_blocks
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn plan_classification(
+        &self,
+        stations_df: &DataFrame,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<ClassificationPlan> {
+        let columns = request.resolve_columns(stations_df)?;
+        let prepared = self.prepare_stations(
+            stations_df,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+            request,
+        )?;
+        self.validate_dataframe_schema(
+            &prepared,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+        )?;
+        let (_, coordinates) = self.extract_coordinates(
+            &prepared,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+        )?;
+
+        let metadata = self.source_metadata();
+        let locator = PixelLocator::new(metadata)?;
+        let locations: Vec<Option<(isize, isize)>> = coordinates
+            .iter()
+            .map(|(lon, lat)| locator.locate(*lon, *lat).ok())
+            .collect();
+
+        let pixels: Vec<Option<i64>> = locations
+            .iter()
+            .map(|location| location.map(|(pixel, _)| pixel as i64))
+            .collect();
+        let lines: Vec<Option<i64>> = locations
+            .iter()
+            .map(|location| location.map(|(_, line)| line as i64))
+            .collect();
+        let inside: Vec<bool> = locations
+            .iter()
+            .map(|location| location.is_some_and(|(pixel, line)| in_extent(pixel, line, metadata)))
+            .collect();
+
+        let mut stations = stations_df.select([columns.station_id.as_str()])?;
+        stations.hstack_mut(&[
+            Series::new("pixel", pixels),
+            Series::new("line", lines),
+            Series::new("in_extent", inside),
+        ])?;
+
+        Ok(ClassificationPlan {
+            stations,
+            summary: summarize(&locations, metadata),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::RasterCrs;
+
+    /// Test extent, pixel and block counts of a coverage summary
+    #[test]
+    fn test_summarize() {
+        let metadata = RasterMetadata {
+            width: 100,
+            height: 100,
+            geo_transform: [0.0, 1.0, 0.0, 100.0, 0.0, -1.0],
+            crs: RasterCrs::Wgs84,
+            block_size: (10, 10),
+            nodata: None,
+        };
+        let locations = [
+            Some((5, 5)),
+            Some((5, 5)),
+            Some((8, 2)),
+            Some((25, 40)),
+            Some((-1, 5)),
+            None,
+        ];
+
+        let summary = summarize(&locations, &metadata);
+        assert_eq!(summary.stations, 6);
+        assert_eq!(summary.inside_extent, 4);
+        assert_eq!(summary.outside_extent, 1);
+        assert_eq!(summary.untransformable, 1);
+        assert_eq!(summary.distinct_pixels, 3);
+        assert_eq!(summary.distinct_blocks, 2);
+        assert_eq!(summary.pixel_window, Some((5, 2, 21, 39)));
+
+        assert_eq!(summarize(&[], &metadata).pixel_window, None);
+    }
+}
@@ -0,0 +1,226 @@
+//! Thread-safe GDAL Dataset Pool
+//!
+//! GDAL dataset handles must not be used from several threads at once, so
+//! `gdal::Dataset` is `Send` but not `Sync`. To make `UrbanClassifier` shareable
+//! across threads (e.g. in web server state), it keeps a pool of datasets
+//! instead of a single handle.
+//!
+//! # Behaviour
+//!
+//! Each operation checks a dataset out of the pool for its duration and returns
+//! it afterwards. When all pooled datasets are in use, the file is opened again,
+//! so the pool grows to the number of threads classifying concurrently and a
+//! single-threaded caller only ever uses the dataset opened at construction.
+//!
+//! A pool around a dataset supplied by the caller (`UrbanClassifier::from_dataset`)
+//! reopens it from its description, which works for files, VSI paths and
+//! subdatasets. In-memory datasets cannot be reopened; concurrent callers then
+//! wait for the one dataset to be returned.
+//!
+//! A pool opened with a `Warp` wraps every dataset in a warped VRT and keeps
+//! the underlying dataset open alongside it.
+
+use crate::error::{ClassifierError, Result};
+use crate::warp::Warp;
+use gdal::{Dataset, DatasetOptions, GdalOpenFlags, Metadata};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::thread::{self, ThreadId};
+
+/// Pool of GDAL datasets opened from the same file
+pub(crate) struct DatasetPool {
+    path: PathBuf,
+    open_options: Vec<String>,
+    warp: Option<Warp>,
+    idle: Mutex<Vec<PoolEntry>>,
+    /// True when the first dataset was supplied by the caller
+    supplied: bool,
+    /// Thread that last checked out a dataset of a supplied pool
+    holder: Mutex<Option<ThreadId>>,
+    returned: Condvar,
+}
+
+/// Pooled dataset and, for warped pools, the dataset the VRT reads from
+struct PoolEntry {
+    // Declared first so that a warped VRT is closed before its source
+    dataset: Dataset,
+    _source: Option<Dataset>,
+}
+
+/// Dataset checked out of a pool, returned to it when dropped
+pub(crate) struct PooledDataset<'a> {
+    pool: &'a DatasetPool,
+    entry: Option<PoolEntry>,
+}
+
+impl DatasetPool {
+    /// Open `path` with GDAL open options (`NAME=VALUE`) and seed a pool with it
+    ///
+    /// Datasets opened later for concurrent callers use the same options and
+    /// are wrapped in the same `warp`, if any.
+    pub(crate) fn open(path: &Path, open_options: Vec<String>, warp: Option<Warp>) -> Result<Self> {
+        let pool = DatasetPool {
+            path: path.to_path_buf(),
+            open_options,
+            warp,
+            idle: Mutex::new(Vec::new()),
+            supplied: false,
+            holder: Mutex::new(None),
+            returned: Condvar::new(),
+        };
+        let entry = pool.open_entry()?;
+        pool.lock_idle().push(entry);
+        Ok(pool)
+    }
+
+    /// Seed a pool with a dataset opened by the caller
+    ///
+    /// Further datasets are opened from the dataset's description, usually
+    /// the name it was opened with.
+    pub(crate) fn from_dataset(dataset: Dataset) -> Self {
+        DatasetPool {
+            path: PathBuf::from(dataset.description().unwrap_or_default()),
+            open_options: Vec::new(),
+            warp: None,
+            idle: Mutex::new(vec![PoolEntry {
+                dataset,
+                _source: None,
+            }]),
+            supplied: true,
+            holder: Mutex::new(None),
+            returned: Condvar::new(),
+        }
+    }
+
+    /// Path the datasets are opened from, as passed to GDAL
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Check out an idle dataset, opening a new one if none is available
+    ///
+    /// A supplied dataset that cannot be reopened is waited for, unless the
+    /// calling thread holds it already, which is reported as an error rather
+    /// than deadlocking.
+    pub(crate) fn get(&self) -> Result<PooledDataset<'_>> {
+        let mut idle = self.lock_idle();
+        let mut tried_reopen = false;
+        let entry = loop {
+            if let Some(entry) = idle.pop() {
+                if self.supplied {
+                    *self.lock_holder() = Some(thread::current().id());
+                }
+                break entry;
+            }
+            if !self.supplied {
+                drop(idle);
+                break self.open_entry()?;
+            }
+            if !tried_reopen {
+                tried_reopen = true;
+                drop(idle);
+                if let Ok(entry) = self.open_entry() {
+                    break entry;
+                }
+                idle = self.lock_idle();
+                continue;
+            }
+            if *self.lock_holder() == Some(thread::current().id()) {
+                return Err(ClassifierError::GdalError {
+                    message: "The dataset passed to from_dataset cannot be reopened and is \
+                              already in use by this operation"
+                        .to_string(),
+                    source: None,
+                });
+            }
+            idle = self
+                .returned
+                .wait(idle)
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+        };
+
+        Ok(PooledDataset {
+            pool: self,
+            entry: Some(entry),
+        })
+    }
+
+    /// Open the dataset and wrap it in the pool's warp, if any
+    fn open_entry(&self) -> Result<PoolEntry> {
+        let dataset = self.open_dataset()?;
+        Ok(match &self.warp {
+            Some(warp) => PoolEntry {
+                dataset: warp.apply(&dataset)?,
+                _source: Some(dataset),
+            },
+            None => PoolEntry {
+                dataset,
+                _source: None,
+            },
+        })
+    }
+
+    fn open_dataset(&self) -> Result<Dataset> {
+        if self.open_options.is_empty() {
+            return Ok(Dataset::open(&self.path)?);
+        }
+
+        let open_options: Vec<&str> = self.open_options.iter().map(String::as_str).collect();
+        let options = DatasetOptions {
+            open_flags: GdalOpenFlags::GDAL_OF_RASTER | GdalOpenFlags::GDAL_OF_READONLY,
+            open_options: Some(&open_options),
+            ..Default::default()
+        };
+        Ok(Dataset::open_ex(&self.path, options)?)
+    }
+
+    /// Lock the idle list, recovering from poisoning
+    ///
+    /// A panic while holding the lock cannot leave the list inconsistent, since
+    /// it is only ever pushed to or popped from.
+    fn lock_idle(&self) -> MutexGuard<'_, Vec<PoolEntry>> {
+        self.idle
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_holder(&self) -> MutexGuard<'_, Option<ThreadId>> {
+        self.holder
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Deref for PooledDataset<'_> {
+    type Target = Dataset;
+
+    fn deref(&self) -> &Dataset {
+        &self
+            .entry
+            .as_ref()
+            .expect("pooled dataset is present until dropped")
+            .dataset
+    }
+}
+
+impl Drop for PooledDataset<'_> {
+    fn drop(&mut self) {
+        if let Some(entry) = self.entry.take() {
+            self.pool.lock_idle().push(entry);
+            self.pool.returned.notify_one();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the pool can be shared between threads
+    #[test]
+    fn test_pool_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<DatasetPool>();
+    }
+}
@@ -0,0 +1,220 @@
+//! In-memory Raster Preloading
+//!
+//! Sampling normally issues one GDAL read per point. When the same region is
+//! classified repeatedly, the LCZ band (or the part of it covering the stations)
+//! can instead be read into memory once and sampled from there.
+//!
+//! # Strategies
+//!
+//! - `PreloadStrategy::None`: Read from the file on every sample (default)
+//! - `PreloadStrategy::FullBand`: Load the entire LCZ band
+//! - `PreloadStrategy::BoundingBox`: Load only the pixels covering a WGS84 box
+//!
+//! Samples outside the preloaded region fall back to reading from the file, so
+//! preloading never changes classification results. Note that the global WUDAPT
+//! map is far too large to hold in memory; use `FullBand` for regional rasters
+//! and `BoundingBox` for study areas within a global one.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::source::PixelLocator;
+use crate::spatial::{BoundingBox, PixelWindow};
+use ndarray::{s, Array2};
+
+/// Which part of the LCZ band to hold in memory
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PreloadStrategy {
+    /// Read from the file on every sample
+    #[default]
+    None,
+    /// Load the entire LCZ band
+    FullBand,
+    /// Load the pixels covering a WGS84 bounding box, e.g. around the stations
+    BoundingBox(BoundingBox),
+}
+
+/// Block of LCZ band values held in memory
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PreloadedBand {
+    /// Position of the top-left preloaded pixel in the full raster
+    x_off: isize,
+    y_off: isize,
+    /// Size of the full raster, used to clip window requests
+    raster_size: (usize, usize),
+    /// Values indexed by [row, column]
+    data: Array2<u8>,
+}
+
+impl PreloadedBand {
+    /// Wrap a window read from a raster of the given size
+    pub(crate) fn from_window(window: PixelWindow, raster_size: (usize, usize)) -> Result<Self> {
+        let data =
+            Array2::from_shape_vec((window.height, window.width), window.data).map_err(|e| {
+                ClassifierError::GdalError {
+                    message: format!("Preloaded raster block has inconsistent shape: {}", e),
+                    source: None,
+                }
+            })?;
+
+        Ok(PreloadedBand {
+            x_off: window.x_off,
+            y_off: window.y_off,
+            raster_size,
+            data,
+        })
+    }
+
+    /// Get a preloaded value, or `None` if the pixel was not preloaded
+    pub(crate) fn get(&self, pixel: isize, line: isize) -> Option<u8> {
+        let col = usize::try_from(pixel - self.x_off).ok()?;
+        let row = usize::try_from(line - self.y_off).ok()?;
+        self.data.get((row, col)).copied()
+    }
+
+    /// Serve a window request (clipped to the raster extent) from memory
+    ///
+    /// Returns `None` when the clipped request is not entirely preloaded.
+    pub(crate) fn window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Option<PixelWindow> {
+        let x_start = pixel.max(0);
+        let y_start = line.max(0);
+        let x_end = (pixel + width as isize).min(self.raster_size.0 as isize);
+        let y_end = (line + height as isize).min(self.raster_size.1 as isize);
+
+        if x_end <= x_start || y_end <= y_start {
+            return Some(PixelWindow {
+                x_off: x_start,
+                y_off: y_start,
+                width: 0,
+                height: 0,
+                data: Vec::new(),
+            });
+        }
+
+        let (rows, cols) = self.data.dim();
+        let col_start = usize::try_from(x_start - self.x_off).ok()?;
+        let row_start = usize::try_from(y_start - self.y_off).ok()?;
+        let col_end = (x_end - self.x_off) as usize;
+        let row_end = (y_end - self.y_off) as usize;
+        if col_end > cols || row_end > rows {
+            return None;
+        }
+
+        let block = self.data.slice(s![row_start..row_end, col_start..col_end]);
+        Some(PixelWindow {
+            x_off: x_start,
+            y_off: y_start,
+            width: col_end - col_start,
+            height: row_end - row_start,
+            data: block.iter().copied().collect(),
+        })
+    }
+}
+
+impl UrbanClassifier {
+    /// Hold the LCZ band, or part of it, in memory for faster sampling
+    ///
+    /// The data is read immediately, so this fails if the region cannot be read.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::preload::PreloadStrategy;
+    /// use urban_classifier::spatial::BoundingBox;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let uk = BoundingBox::new(-8.7, 49.8, 1.8, 60.9)?;
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?
+    ///     .with_preload(PreloadStrategy::BoundingBox(uk))?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_preload(mut self, strategy: PreloadStrategy) -> Result<Self> {
+        let metadata = self.source_metadata();
+        let raster_size = (metadata.width, metadata.height);
+
+        let window = match strategy {
+            PreloadStrategy::None => None,
+            PreloadStrategy::FullBand => {
+                Some(self.read_source_window(0, 0, raster_size.0, raster_size.1)?)
+            }
+            PreloadStrategy::BoundingBox(bbox) => {
+                let (pixel, line, width, height) =
+                    PixelLocator::new(metadata)?.bbox_window(&bbox)?;
+                Some(self.read_source_window(pixel, line, width, height)?)
+            }
+        };
+
+        let preloaded = window
+            .map(|window| PreloadedBand::from_window(window, raster_size))
+            .transpose()?;
+
+        self.set_preloaded(preloaded);
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn preloaded_block() -> PreloadedBand {
+        // 3x2 block at (10, 20) of a 100x50 raster
+        let window = PixelWindow {
+            x_off: 10,
+            y_off: 20,
+            width: 3,
+            height: 2,
+            data: vec![1, 2, 3, 4, 5, 6],
+        };
+        PreloadedBand::from_window(window, (100, 50)).unwrap()
+    }
+
+    /// Test single-pixel lookups inside and outside the preloaded block
+    #[test]
+    fn test_preloaded_get() {
+        let block = preloaded_block();
+        assert_eq!(block.get(10, 20), Some(1));
+        assert_eq!(block.get(12, 21), Some(6));
+        assert_eq!(block.get(9, 20), None);
+        assert_eq!(block.get(10, 22), None);
+    }
+
+    /// Test windows served from memory and fallback for partial coverage
+    #[test]
+    fn test_preloaded_window() {
+        let block = preloaded_block();
+
+        let window = block.window(11, 20, 2, 2).unwrap();
+        assert_eq!((window.x_off, window.y_off), (11, 20));
+        assert_eq!(window.data, vec![2, 3, 5, 6]);
+
+        // Extends past the preloaded block
+        assert!(block.window(11, 20, 3, 1).is_none());
+
+        // Entirely outside the raster: empty, as with a file read
+        let empty = block.window(200, 0, 5, 5).unwrap();
+        assert!(empty.data.is_empty());
+    }
+
+    /// Test that a full-band preload serves requests clipped at raster edges
+    #[test]
+    fn test_full_band_edges() {
+        let window = PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width: 2,
+            height: 2,
+            data: vec![7, 8, 9, 10],
+        };
+        let block = PreloadedBand::from_window(window, (2, 2)).unwrap();
+
+        let clipped = block.window(-1, -1, 3, 3).unwrap();
+        assert_eq!((clipped.width, clipped.height), (2, 2));
+        assert_eq!(clipped.data, vec![7, 8, 9, 10]);
+    }
+}
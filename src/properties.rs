@@ -0,0 +1,446 @@
+//! Physical Properties of LCZ Classes
+//!
+//! Stewart and Oke (2012, Tables 3 and 4) give typical ranges of geometric,
+//! surface cover, thermal, radiative and metabolic properties for each Local
+//! Climate Zone. `Lcz::properties` exposes these ranges so that models can be
+//! parameterised directly from classification output.
+//!
+//! # Units
+//!
+//! - Sky view factor, aspect ratio and albedo are dimensionless
+//! - Surface fractions are percentages of plan area
+//! - Roughness element height is in metres
+//! - Terrain roughness is a Davenport class (1-8)
+//! - Surface admittance is in J m⁻² s⁻¹ᐟ² K⁻¹
+//! - Anthropogenic heat flux is an annual mean in W m⁻²
+//!
+//! # Aerodynamic Roughness
+//!
+//! For wind and dispersion models, `Lcz::davenport_class` returns the terrain
+//! roughness classes of a zone and `Lcz::roughness_length` the corresponding
+//! effective roughness lengths z₀ of the Davenport classification
+//! (Wieringa, 1992; Davenport et al., 2000).
+//!
+//! Reference: Stewart, I. D. and Oke, T. R.: Local climate zones for urban
+//! temperature studies, Bull. Amer. Meteor. Soc., 93, 1879-1900,
+//! https://doi.org/10.1175/BAMS-D-11-00019.1, 2012.
+
+use crate::lcz::Lcz;
+
+/// A range of typical values; either bound may be open
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PropertyRange {
+    /// Lower bound, or `None` where the table gives only an upper bound
+    pub min: Option<f64>,
+    /// Upper bound, or `None` where the table gives only a lower bound
+    pub max: Option<f64>,
+}
+
+impl PropertyRange {
+    const fn between(min: f64, max: f64) -> Self {
+        PropertyRange {
+            min: Some(min),
+            max: Some(max),
+        }
+    }
+
+    const fn at_least(min: f64) -> Self {
+        PropertyRange {
+            min: Some(min),
+            max: None,
+        }
+    }
+
+    const fn below(max: f64) -> Self {
+        PropertyRange {
+            min: None,
+            max: Some(max),
+        }
+    }
+
+    const fn exactly(value: f64) -> Self {
+        Self::between(value, value)
+    }
+
+    const fn unknown() -> Self {
+        PropertyRange {
+            min: None,
+            max: None,
+        }
+    }
+
+    /// Centre of the range when both bounds are given
+    pub fn midpoint(&self) -> Option<f64> {
+        Some((self.min? + self.max?) / 2.0)
+    }
+
+    /// Check whether a value lies within the range, treating open bounds as unlimited
+    pub fn contains(&self, value: f64) -> bool {
+        !self.min.is_some_and(|min| value < min) && !self.max.is_some_and(|max| value > max)
+    }
+}
+
+/// Typical property ranges of one LCZ class (Stewart and Oke, 2012)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LczProperties {
+    /// Fraction of the sky hemisphere visible from ground level
+    pub sky_view_factor: PropertyRange,
+    /// Mean height-to-width ratio of street canyons or tree spacing
+    pub aspect_ratio: PropertyRange,
+    /// Percentage of plan area covered by buildings
+    pub building_surface_fraction: PropertyRange,
+    /// Percentage of plan area with impervious cover (paved, rock)
+    pub impervious_surface_fraction: PropertyRange,
+    /// Percentage of plan area with pervious cover (soil, vegetation, water)
+    pub pervious_surface_fraction: PropertyRange,
+    /// Geometric average height of buildings or trees in metres
+    pub roughness_element_height: PropertyRange,
+    /// Davenport terrain roughness class as an inclusive (lowest, highest) range
+    pub terrain_roughness_class: (u8, u8),
+    /// Ability of the surface to accept or release heat, J m⁻² s⁻¹ᐟ² K⁻¹
+    pub surface_admittance: PropertyRange,
+    /// Ratio of reflected to incoming solar radiation
+    pub surface_albedo: PropertyRange,
+    /// Annual mean heat flux from fuel combustion and human activity, W m⁻²
+    pub anthropogenic_heat_flux: PropertyRange,
+}
+
+use PropertyRange as R;
+
+/// Properties of LCZ 1-10 and A-G, indexed by code - 1
+const PROPERTIES: [LczProperties; 17] = [
+    // 1 Compact high-rise
+    LczProperties {
+        sky_view_factor: R::between(0.2, 0.4),
+        aspect_ratio: R::at_least(2.0),
+        building_surface_fraction: R::between(40.0, 60.0),
+        impervious_surface_fraction: R::between(40.0, 60.0),
+        pervious_surface_fraction: R::below(10.0),
+        roughness_element_height: R::at_least(25.0),
+        terrain_roughness_class: (8, 8),
+        surface_admittance: R::between(1500.0, 1800.0),
+        surface_albedo: R::between(0.10, 0.20),
+        anthropogenic_heat_flux: R::between(50.0, 300.0),
+    },
+    // 2 Compact midrise
+    LczProperties {
+        sky_view_factor: R::between(0.3, 0.6),
+        aspect_ratio: R::between(0.75, 2.0),
+        building_surface_fraction: R::between(40.0, 70.0),
+        impervious_surface_fraction: R::between(30.0, 50.0),
+        pervious_surface_fraction: R::below(20.0),
+        roughness_element_height: R::between(10.0, 25.0),
+        terrain_roughness_class: (6, 7),
+        surface_admittance: R::between(1500.0, 2200.0),
+        surface_albedo: R::between(0.10, 0.20),
+        anthropogenic_heat_flux: R::below(75.0),
+    },
+    // 3 Compact low-rise
+    LczProperties {
+        sky_view_factor: R::between(0.2, 0.6),
+        aspect_ratio: R::between(0.75, 1.5),
+        building_surface_fraction: R::between(40.0, 70.0),
+        impervious_surface_fraction: R::between(20.0, 50.0),
+        pervious_surface_fraction: R::below(30.0),
+        roughness_element_height: R::between(3.0, 10.0),
+        terrain_roughness_class: (6, 6),
+        surface_admittance: R::between(1200.0, 1800.0),
+        surface_albedo: R::between(0.10, 0.20),
+        anthropogenic_heat_flux: R::below(75.0),
+    },
+    // 4 Open high-rise
+    LczProperties {
+        sky_view_factor: R::between(0.5, 0.7),
+        aspect_ratio: R::between(0.75, 1.25),
+        building_surface_fraction: R::between(20.0, 40.0),
+        impervious_surface_fraction: R::between(30.0, 40.0),
+        pervious_surface_fraction: R::between(30.0, 40.0),
+        roughness_element_height: R::at_least(25.0),
+        terrain_roughness_class: (7, 8),
+        surface_admittance: R::between(1400.0, 1800.0),
+        surface_albedo: R::between(0.12, 0.25),
+        anthropogenic_heat_flux: R::below(50.0),
+    },
+    // 5 Open midrise
+    LczProperties {
+        sky_view_factor: R::between(0.5, 0.8),
+        aspect_ratio: R::between(0.3, 0.75),
+        building_surface_fraction: R::between(20.0, 40.0),
+        impervious_surface_fraction: R::between(30.0, 50.0),
+        pervious_surface_fraction: R::between(20.0, 40.0),
+        roughness_element_height: R::between(10.0, 25.0),
+        terrain_roughness_class: (5, 6),
+        surface_admittance: R::between(1400.0, 2000.0),
+        surface_albedo: R::between(0.12, 0.25),
+        anthropogenic_heat_flux: R::below(25.0),
+    },
+    // 6 Open low-rise
+    LczProperties {
+        sky_view_factor: R::between(0.6, 0.9),
+        aspect_ratio: R::between(0.3, 0.75),
+        building_surface_fraction: R::between(20.0, 40.0),
+        impervious_surface_fraction: R::between(20.0, 50.0),
+        pervious_surface_fraction: R::between(30.0, 60.0),
+        roughness_element_height: R::between(3.0, 10.0),
+        terrain_roughness_class: (5, 6),
+        surface_admittance: R::between(1200.0, 1800.0),
+        surface_albedo: R::between(0.12, 0.25),
+        anthropogenic_heat_flux: R::below(25.0),
+    },
+    // 7 Lightweight low-rise
+    LczProperties {
+        sky_view_factor: R::between(0.2, 0.5),
+        aspect_ratio: R::between(1.0, 2.0),
+        building_surface_fraction: R::between(60.0, 90.0),
+        impervious_surface_fraction: R::below(20.0),
+        pervious_surface_fraction: R::below(30.0),
+        roughness_element_height: R::between(2.0, 4.0),
+        terrain_roughness_class: (4, 5),
+        surface_admittance: R::between(800.0, 1500.0),
+        surface_albedo: R::between(0.15, 0.35),
+        anthropogenic_heat_flux: R::below(35.0),
+    },
+    // 8 Large low-rise
+    LczProperties {
+        sky_view_factor: R::at_least(0.7),
+        aspect_ratio: R::between(0.1, 0.3),
+        building_surface_fraction: R::between(30.0, 50.0),
+        impervious_surface_fraction: R::between(40.0, 50.0),
+        pervious_surface_fraction: R::below(20.0),
+        roughness_element_height: R::between(3.0, 10.0),
+        terrain_roughness_class: (5, 5),
+        surface_admittance: R::between(1200.0, 1800.0),
+        surface_albedo: R::between(0.15, 0.25),
+        anthropogenic_heat_flux: R::below(50.0),
+    },
+    // 9 Sparsely built
+    LczProperties {
+        sky_view_factor: R::at_least(0.8),
+        aspect_ratio: R::between(0.1, 0.25),
+        building_surface_fraction: R::between(10.0, 20.0),
+        impervious_surface_fraction: R::below(20.0),
+        pervious_surface_fraction: R::between(60.0, 80.0),
+        roughness_element_height: R::between(3.0, 10.0),
+        terrain_roughness_class: (5, 6),
+        surface_admittance: R::between(1000.0, 1800.0),
+        surface_albedo: R::between(0.12, 0.25),
+        anthropogenic_heat_flux: R::below(10.0),
+    },
+    // 10 Heavy industry
+    LczProperties {
+        sky_view_factor: R::between(0.6, 0.9),
+        aspect_ratio: R::between(0.2, 0.5),
+        building_surface_fraction: R::between(20.0, 30.0),
+        impervious_surface_fraction: R::between(20.0, 40.0),
+        pervious_surface_fraction: R::between(40.0, 50.0),
+        roughness_element_height: R::between(5.0, 15.0),
+        terrain_roughness_class: (5, 6),
+        surface_admittance: R::between(1000.0, 2500.0),
+        surface_albedo: R::between(0.12, 0.20),
+        anthropogenic_heat_flux: R::at_least(300.0),
+    },
+    // A Dense trees
+    LczProperties {
+        sky_view_factor: R::below(0.4),
+        aspect_ratio: R::at_least(1.0),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::between(3.0, 30.0),
+        terrain_roughness_class: (8, 8),
+        surface_admittance: R::unknown(),
+        surface_albedo: R::between(0.10, 0.20),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // B Scattered trees
+    LczProperties {
+        sky_view_factor: R::between(0.5, 0.8),
+        aspect_ratio: R::between(0.25, 0.75),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::between(3.0, 15.0),
+        terrain_roughness_class: (5, 6),
+        surface_admittance: R::between(1000.0, 1800.0),
+        surface_albedo: R::between(0.15, 0.25),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // C Bush, scrub
+    LczProperties {
+        sky_view_factor: R::between(0.7, 0.9),
+        aspect_ratio: R::between(0.25, 1.0),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::below(2.0),
+        terrain_roughness_class: (4, 5),
+        surface_admittance: R::between(700.0, 1500.0),
+        surface_albedo: R::between(0.15, 0.30),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // D Low plants
+    LczProperties {
+        sky_view_factor: R::at_least(0.9),
+        aspect_ratio: R::below(0.1),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::below(1.0),
+        terrain_roughness_class: (3, 4),
+        surface_admittance: R::between(1200.0, 1600.0),
+        surface_albedo: R::between(0.15, 0.25),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // E Bare rock or paved
+    LczProperties {
+        sky_view_factor: R::at_least(0.9),
+        aspect_ratio: R::below(0.1),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::at_least(90.0),
+        pervious_surface_fraction: R::below(10.0),
+        roughness_element_height: R::below(0.25),
+        terrain_roughness_class: (1, 2),
+        surface_admittance: R::between(1200.0, 2500.0),
+        surface_albedo: R::between(0.20, 0.35),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // F Bare soil or sand
+    LczProperties {
+        sky_view_factor: R::at_least(0.9),
+        aspect_ratio: R::below(0.1),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::below(0.25),
+        terrain_roughness_class: (1, 2),
+        surface_admittance: R::between(600.0, 1400.0),
+        surface_albedo: R::between(0.20, 0.35),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+    // G Water
+    LczProperties {
+        sky_view_factor: R::at_least(0.9),
+        aspect_ratio: R::below(0.1),
+        building_surface_fraction: R::below(10.0),
+        impervious_surface_fraction: R::below(10.0),
+        pervious_surface_fraction: R::at_least(90.0),
+        roughness_element_height: R::unknown(),
+        terrain_roughness_class: (1, 1),
+        surface_admittance: R::exactly(1500.0),
+        surface_albedo: R::between(0.02, 0.10),
+        anthropogenic_heat_flux: R::exactly(0.0),
+    },
+];
+
+impl Lcz {
+    /// Typical physical properties of this class, or `None` for unknown codes
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// let open_low_rise = Lcz::OpenLowRise.properties().unwrap();
+    /// assert_eq!(open_low_rise.sky_view_factor.midpoint(), Some(0.75));
+    /// assert!(Lcz::Unknown(0).properties().is_none());
+    /// ```
+    pub fn properties(&self) -> Option<&'static LczProperties> {
+        match self {
+            Lcz::Unknown(_) => None,
+            lcz => Some(&PROPERTIES[usize::from(lcz.to_code()) - 1]),
+        }
+    }
+
+    /// Davenport terrain roughness classes of this class as an inclusive
+    /// (lowest, highest) range, or `None` for unknown codes
+    pub fn davenport_class(&self) -> Option<(u8, u8)> {
+        self.properties()
+            .map(|properties| properties.terrain_roughness_class)
+    }
+
+    /// Typical effective roughness length z₀ in metres, or `None` for unknown
+    /// codes
+    ///
+    /// The range spans the z₀ of the zone's lowest and highest Davenport
+    /// classes; zones reaching class 8 ("chaotic") have no upper bound.
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// let z0 = Lcz::OpenLowRise.roughness_length().unwrap();
+    /// assert_eq!((z0.min, z0.max), (Some(0.25), Some(0.5)));
+    /// ```
+    pub fn roughness_length(&self) -> Option<PropertyRange> {
+        let (low, high) = self.davenport_class()?;
+        Some(PropertyRange {
+            min: davenport_roughness_length(low),
+            max: davenport_roughness_length(high).filter(|_| high < 8),
+        })
+    }
+}
+
+/// Effective roughness length z₀ in metres of a Davenport class (1-8)
+///
+/// Class 8 is the lower bound of chaotic terrain (z₀ ≥ 2 m).
+pub fn davenport_roughness_length(class: u8) -> Option<f64> {
+    const ROUGHNESS_LENGTHS: [f64; 8] = [0.0002, 0.005, 0.03, 0.10, 0.25, 0.5, 1.0, 2.0];
+    ROUGHNESS_LENGTHS
+        .get(usize::from(class).checked_sub(1)?)
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test range bounds, midpoints and containment
+    #[test]
+    fn test_property_range() {
+        let range = PropertyRange::between(0.2, 0.4);
+        assert!((range.midpoint().unwrap() - 0.3).abs() < 1e-12);
+        assert!(range.contains(0.4));
+        assert!(!range.contains(0.5));
+
+        let open = PropertyRange::at_least(25.0);
+        assert_eq!(open.midpoint(), None);
+        assert!(open.contains(100.0));
+        assert!(PropertyRange::unknown().contains(-1.0));
+    }
+
+    /// Test that the table is consistent for every standard class
+    #[test]
+    fn test_lcz_properties() {
+        for lcz in Lcz::iter() {
+            let properties = lcz.properties().unwrap();
+            let (low, high) = properties.terrain_roughness_class;
+            assert!((1..=8).contains(&low) && low <= high, "{}", lcz);
+            if let (Some(min), Some(max)) =
+                (properties.surface_albedo.min, properties.surface_albedo.max)
+            {
+                assert!(min <= max, "{}", lcz);
+            }
+        }
+
+        let industry = Lcz::HeavyIndustry.properties().unwrap();
+        assert!(industry.anthropogenic_heat_flux.contains(400.0));
+        assert_eq!(
+            Lcz::Water.properties().unwrap().terrain_roughness_class,
+            (1, 1)
+        );
+    }
+
+    /// Test Davenport classes and roughness lengths
+    #[test]
+    fn test_roughness() {
+        assert_eq!(Lcz::CompactMidRise.davenport_class(), Some((6, 7)));
+        assert_eq!(davenport_roughness_length(1), Some(0.0002));
+        assert_eq!(davenport_roughness_length(0), None);
+        assert_eq!(davenport_roughness_length(9), None);
+
+        let water = Lcz::Water.roughness_length().unwrap();
+        assert_eq!((water.min, water.max), (Some(0.0002), Some(0.0002)));
+        let high_rise = Lcz::CompactHighRise.roughness_length().unwrap();
+        assert_eq!((high_rise.min, high_rise.max), (Some(2.0), None));
+        assert_eq!(Lcz::Unknown(0).roughness_length(), None);
+    }
+}
@@ -0,0 +1,176 @@
+//! Data Citation and Map Provenance
+//!
+//! Results derived from the WUDAPT global LCZ map should cite it and record
+//! which version of the map was used. `UrbanClassifier::data_citation`
+//! returns the reference to cite, and `UrbanClassifier::map_metadata`
+//! describes the loaded file so reports can record it programmatically.
+//!
+//! # Version Detection
+//!
+//! The product version is taken from a `VERSION`-like GeoTIFF metadata tag
+//! or the image description if present, and otherwise from a `v<number>`
+//! component of the file name, e.g. `v3` for `lcz_filter_v3_cog.tif`.
+
+use crate::classifier::UrbanClassifier;
+#[cfg(feature = "gdal")]
+use crate::error::Result;
+#[cfg(feature = "gdal")]
+use gdal::Metadata;
+#[cfg(feature = "gdal")]
+use std::{fs::File, io::Read, path::Path};
+
+/// Reference for the WUDAPT global LCZ map
+pub const WUDAPT_CITATION: &str = "Demuzere, M., Kittner, J., Martilli, A., Mills, G., \
+Moede, C., Stewart, I. D., van Vliet, J., and Bechtel, B.: A global map of local climate \
+zones to support earth system modelling and urban-scale environmental science, Earth Syst. \
+Sci. Data, 14, 3835-3873, https://doi.org/10.5194/essd-14-3835-2022, 2022.";
+
+/// Provenance of the loaded LCZ map
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapMetadata {
+    /// Path or GDAL virtual file system path the map was opened from
+    pub path: String,
+    /// Product version, e.g. `v3`, if it could be detected
+    pub product_version: Option<String>,
+    /// File size in bytes, for local files
+    pub file_size: Option<u64>,
+    /// CRC-32 of the file contents as 8 hex digits, for local files
+    pub checksum: Option<String>,
+}
+
+/// Find a `v<number>` version component in a file name or description
+///
+/// The component must start the text or follow a non-alphanumeric character,
+/// and may contain dots, e.g. `v3` or `v1.0`.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::provenance::detect_version;
+///
+/// assert_eq!(detect_version("lcz_filter_v3_cog.tif").as_deref(), Some("v3"));
+/// assert_eq!(detect_version("WUDAPT LCZ v1.0").as_deref(), Some("v1.0"));
+/// assert_eq!(detect_version("overview.tif"), None);
+/// ```
+pub fn detect_version(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    (0..bytes.len()).find_map(|start| {
+        let at_boundary = start == 0 || !bytes[start - 1].is_ascii_alphanumeric();
+        if !at_boundary || !matches!(bytes[start], b'v' | b'V') {
+            return None;
+        }
+        let rest = &text[start + 1..];
+        let digits = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let number = rest[..digits].trim_end_matches('.');
+        let starts_with_digit = number.starts_with(|c: char| c.is_ascii_digit());
+        starts_with_digit.then(|| format!("v{}", number))
+    })
+}
+
+/// CRC-32 of a file's contents, read in chunks
+#[cfg(feature = "gdal")]
+fn file_checksum(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:08x}", hasher.finalize()))
+}
+
+impl UrbanClassifier {
+    /// Reference to cite for results derived from the WUDAPT global LCZ map
+    pub fn data_citation(&self) -> &'static str {
+        WUDAPT_CITATION
+    }
+
+    /// Describe the loaded map file: path, product version and checksum
+    ///
+    /// The checksum reads the whole file, which takes a while for the global
+    /// map; remote maps have no size or checksum.
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::GdalError` for classifiers created from a
+    /// custom raster source, and `Io` if a local file cannot be read.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/lcz_filter_v3.tif")?;
+    /// let map = classifier.map_metadata()?;
+    /// println!("{} ({:?}, crc32 {:?})", map.path, map.product_version, map.checksum);
+    /// println!("{}", classifier.data_citation());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn map_metadata(&self) -> Result<MapMetadata> {
+        let dataset = self.dataset()?;
+        let path = self
+            .dataset_path()
+            .expect("GDAL classifiers have a dataset path");
+
+        let tagged_version = dataset
+            .metadata()
+            .find(|entry| {
+                entry.domain.is_empty() && entry.key.to_ascii_uppercase().contains("VERSION")
+            })
+            .map(|entry| entry.value)
+            .or_else(|| {
+                dataset
+                    .metadata_item("TIFFTAG_IMAGEDESCRIPTION", "")
+                    .and_then(|description| detect_version(&description))
+            });
+        let product_version = tagged_version.or_else(|| {
+            path.file_name()
+                .and_then(|name| detect_version(&name.to_string_lossy()))
+        });
+
+        let (file_size, checksum) = if path.is_file() {
+            (Some(path.metadata()?.len()), Some(file_checksum(path)?))
+        } else {
+            (None, None)
+        };
+
+        Ok(MapMetadata {
+            path: path.to_string_lossy().to_string(),
+            product_version,
+            file_size,
+            checksum,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "gdal")]
+    use std::io::Write;
+
+    /// Test version detection from file names and descriptions
+    #[test]
+    fn test_detect_version() {
+        assert_eq!(
+            detect_version("lcz_filter_v2_cog.tif").as_deref(),
+            Some("v2")
+        );
+        assert_eq!(detect_version("V1.0.2. final").as_deref(), Some("v1.0.2"));
+        assert_eq!(detect_version("lcz_dev3.tif"), None);
+        assert_eq!(detect_version("version.tif"), None);
+    }
+
+    /// Test the CRC-32 checksum of a known file
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_file_checksum() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"123456789").unwrap();
+        assert_eq!(file_checksum(file.path()).unwrap(), "cbf43926");
+    }
+}
@@ -2,35 +2,132 @@
 //!
 //! This module provides PyO3 bindings to expose the urban classifier
 //! functionality to Python. It wraps the Rust implementation in a
-//! Python-friendly interface using Polars DataFrames.
+//! Python-friendly interface using Polars DataFrames. pandas DataFrames are
+//! accepted by the `*_pandas` methods, which convert through Polars (this
+//! needs `pandas` and `pyarrow` installed).
 //!
 //! # Main Components
 //!
 //! - `PyUrbanClassifier`: Python wrapper around the Rust classifier
-//! - Error conversion from Rust to Python exceptions
+//! - Error conversion from Rust errors to an `UrbanClassifierError`
+//!   exception hierarchy
 //! - Module initialization with metadata
 //!
 //! # Python API
 //!
 //! The module exposes:
-//! - `PyUrbanClassifier` class with classification methods
+//! - `PyUrbanClassifier` class with classification methods, including
+//!   `classify_point`/`classify_points` returning plain dicts, usable as a
+//!   context manager that closes the raster on exit. `run_classification`
+//!   takes keyword-only sampling options (buffer, majority kernel, nodata
+//!   category, out-of-bounds policy)
+//! - `Lcz` class mirroring the Rust enum, with code, letter, name, category
+//!   and colour
+//! - `download_wudapt` function fetching the global LCZ map on first use
 //! - Static method to get LCZ information
 //! - DataFrame validation utilities
+//! - Exceptions deriving from `UrbanClassifierError`: `DataNotFoundError`,
+//!   `SchemaError`, `CoordinateError`, `RasterError` (with subclass
+//!   `RasterSamplingError`), `ConfigurationError`, `DownloadError` and
+//!   `ClassificationFailuresError`
 
 #![allow(non_local_definitions)]
 
+use polars::prelude::{DataFrame, NamedFrom, Series};
+use pyo3::create_exception;
 use pyo3::prelude::*;
-use pyo3::types::PyType;
+use pyo3::pyclass::CompareOp;
+use pyo3::types::{PyDict, PyType};
 use pyo3_polars::PyDataFrame;
 use std::collections::HashMap;
+use std::path::PathBuf;
 
 use crate::classifier::UrbanClassifier;
+use crate::download::fetch_wudapt;
 use crate::error::ClassifierError;
+use crate::failures::FailureMode;
+use crate::lcz::{Lcz, LczCategory};
+use crate::request::ClassificationRequest;
+use crate::smoothing::MajorityFilter;
 
 /// Python wrapper for the UrbanClassifier
 #[pyclass]
 pub struct PyUrbanClassifier {
-    inner: UrbanClassifier,
+    /// The classifier, or None once `close` has released it
+    inner: Option<UrbanClassifier>,
+}
+
+impl PyUrbanClassifier {
+    fn classifier(&self) -> PyResult<&UrbanClassifier> {
+        self.inner.as_ref().ok_or_else(closed_error)
+    }
+}
+
+fn closed_error() -> PyErr {
+    pyo3::exceptions::PyValueError::new_err("Operation on a closed UrbanClassifier")
+}
+
+/// Sampling keyword arguments of `run_classification`, checked and mapped
+/// to their Rust options
+struct SamplingOptions {
+    majority_filter: Option<MajorityFilter>,
+    nodata_category: Option<LczCategory>,
+    failure_mode: FailureMode,
+}
+
+impl SamplingOptions {
+    fn parse(
+        kernel_size: Option<usize>,
+        aggregation: Option<&str>,
+        nodata: Option<&str>,
+        out_of_bounds: &str,
+    ) -> PyResult<Self> {
+        let invalid = |message: String| pyo3::exceptions::PyValueError::new_err(message);
+
+        let majority = match aggregation {
+            None => kernel_size.is_some(),
+            Some("pixel") if kernel_size.is_some() => {
+                return Err(invalid(
+                    "kernel_size requires the \"majority\" aggregation".to_string(),
+                ))
+            }
+            Some("pixel") => false,
+            Some("majority") => true,
+            Some(other) => {
+                return Err(invalid(format!(
+                    "Unknown aggregation {:?}: expected \"pixel\" or \"majority\"",
+                    other
+                )))
+            }
+        };
+        let majority_filter = match kernel_size.unwrap_or(3) {
+            _ if !majority => None,
+            size if size % 2 == 1 => Some(MajorityFilter::square(size / 2)),
+            size => return Err(invalid(format!("kernel_size must be odd, got {}", size))),
+        };
+
+        let nodata_category = nodata
+            .map(|name| name.parse::<LczCategory>())
+            .transpose()
+            .map_err(|e| invalid(e.to_string()))?;
+
+        let failure_mode = match out_of_bounds {
+            "raise" => FailureMode::FailFast,
+            "null" => FailureMode::CollectAll,
+            other => {
+                return Err(invalid(format!(
+                    "Unknown out_of_bounds policy {:?}: expected \"raise\" or \"null\"",
+                    other
+                )))
+            }
+        };
+
+        Ok(SamplingOptions {
+            majority_filter,
+            nodata_category,
+            failure_mode,
+        })
+    }
 }
 
 #[pymethods]
@@ -42,7 +139,7 @@ impl PyUrbanClassifier {
             None => UrbanClassifier::from_default_data(),
         }
         .map_err(convert_classifier_error_to_py)?;
-        Ok(PyUrbanClassifier { inner })
+        Ok(PyUrbanClassifier { inner: Some(inner) })
     }
 
     /// Create a PyUrbanClassifier using the default WUDAPT data file location.
@@ -57,7 +154,36 @@ impl PyUrbanClassifier {
     #[classmethod]
     fn from_default_data(_cls: &PyType) -> PyResult<Self> {
         let inner = UrbanClassifier::from_default_data().map_err(convert_classifier_error_to_py)?;
-        Ok(PyUrbanClassifier { inner })
+        Ok(PyUrbanClassifier { inner: Some(inner) })
+    }
+
+    /// Release the raster dataset and its GDAL handle.
+    ///
+    /// Methods that read the raster raise ValueError once the classifier is
+    /// closed. Closing an already closed classifier does nothing.
+    fn close(&mut self) {
+        self.inner = None;
+    }
+
+    /// True once close has been called
+    #[getter]
+    fn closed(&self) -> bool {
+        self.inner.is_none()
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Close the classifier on leaving a `with` block; exceptions propagate.
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&PyAny>,
+        _exc_value: Option<&PyAny>,
+        _traceback: Option<&PyAny>,
+    ) -> bool {
+        self.close();
+        false
     }
 
     /// Get the default WUDAPT data file path for this system.
@@ -80,11 +206,40 @@ impl PyUrbanClassifier {
     /// - lat_col: Name of the column containing latitude values  
     /// - overrides: Optional dict mapping station IDs to LCZ codes for manual overrides
     ///
+    /// Keyword-only sampling options:
+    /// - buffer_radius_m: Add class composition columns for a buffer of this
+    ///   radius in metres
+    /// - kernel_size: Odd width in pixels of the square kernel used by the
+    ///   "majority" aggregation (3 by default)
+    /// - aggregation: "pixel" to take the class of the station's pixel, or
+    ///   "majority" for the most frequent class in the kernel around it;
+    ///   "majority" if kernel_size is given, "pixel" otherwise
+    /// - nodata: Category ("Urban", "Suburban", "Rural" or "Unknown") given to
+    ///   stations on nodata pixels; their simple_class is null by default
+    /// - out_of_bounds: "raise" (default) to fail on stations outside the
+    ///   raster, or "null" to return null classes for them and for any other
+    ///   station that cannot be sampled
+    ///
     /// Returns:
     /// Polars DataFrame with additional columns:
     /// - lcz_code: Numeric LCZ code (1-17)
     /// - lcz_name: Full descriptive name of the LCZ class
     /// - simple_class: Simplified category (Urban/Suburban/Rural)
+    /// - lcz_1_pct ... lcz_17_pct: Class percentages in the buffer, with buffer_radius_m
+    #[pyo3(signature = (
+        df,
+        station_id_col,
+        lon_col,
+        lat_col,
+        overrides = None,
+        *,
+        buffer_radius_m = None,
+        kernel_size = None,
+        aggregation = None,
+        nodata = None,
+        out_of_bounds = "raise",
+    ))]
+    #[allow(clippy::too_many_arguments)]
     fn run_classification(
         &self,
         df: PyDataFrame,
@@ -92,10 +247,163 @@ impl PyUrbanClassifier {
         lon_col: &str,
         lat_col: &str,
         overrides: Option<HashMap<String, u8>>,
+        buffer_radius_m: Option<f64>,
+        kernel_size: Option<usize>,
+        aggregation: Option<&str>,
+        nodata: Option<&str>,
+        out_of_bounds: &str,
     ) -> PyResult<PyDataFrame> {
+        let options = SamplingOptions::parse(kernel_size, aggregation, nodata, out_of_bounds)?;
+        let mut request = ClassificationRequest::new()
+            .with_station_id_column(station_id_col)
+            .with_longitude_column(lon_col)
+            .with_latitude_column(lat_col)
+            .with_failure_mode(options.failure_mode);
+        if let Some(overrides) = &overrides {
+            request = request.with_overrides(overrides);
+        }
+        if let Some(radius_m) = buffer_radius_m {
+            request = request.with_buffer(radius_m);
+        }
+        if let Some(filter) = options.majority_filter {
+            request = request.with_majority_filter(filter);
+        }
+        if let Some(category) = options.nodata_category {
+            request = request.with_nodata_category(category);
+        }
+
+        let result = self.classifier()?.classify_with(&df.0, &request);
+        Ok(PyDataFrame(result.map_err(convert_classifier_error_to_py)?))
+    }
+
+    /// Classify a pandas DataFrame, returning a pandas DataFrame.
+    ///
+    /// Same as run_classification, converting the input to Polars and the
+    /// result back to pandas. The pandas index is not preserved.
+    ///
+    /// Parameters:
+    /// - df: pandas DataFrame containing station data
+    /// - station_id_col: Name of the column containing station IDs
+    /// - lon_col: Name of the column containing longitude values
+    /// - lat_col: Name of the column containing latitude values
+    /// - overrides: Optional dict mapping station IDs to LCZ codes for manual overrides
+    ///
+    /// Accepts the keyword-only sampling options of run_classification.
+    ///
+    /// Returns:
+    /// pandas DataFrame with the same columns as run_classification
+    #[pyo3(signature = (
+        df,
+        station_id_col,
+        lon_col,
+        lat_col,
+        overrides = None,
+        *,
+        buffer_radius_m = None,
+        kernel_size = None,
+        aggregation = None,
+        nodata = None,
+        out_of_bounds = "raise",
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn run_classification_pandas(
+        &self,
+        py: Python<'_>,
+        df: &PyAny,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        overrides: Option<HashMap<String, u8>>,
+        buffer_radius_m: Option<f64>,
+        kernel_size: Option<usize>,
+        aggregation: Option<&str>,
+        nodata: Option<&str>,
+        out_of_bounds: &str,
+    ) -> PyResult<PyObject> {
+        let df = pandas_to_polars(py, df)?;
+        let result = self.run_classification(
+            df,
+            station_id_col,
+            lon_col,
+            lat_col,
+            overrides,
+            buffer_radius_m,
+            kernel_size,
+            aggregation,
+            nodata,
+            out_of_bounds,
+        )?;
+        polars_to_pandas(py, result)
+    }
+
+    /// Classify a single WGS84 coordinate.
+    ///
+    /// Parameters:
+    /// - lon: Longitude in degrees
+    /// - lat: Latitude in degrees
+    ///
+    /// Returns:
+    /// Dict such as {"code": 6, "name": "Open low-rise", "category": "Urban"};
+    /// code and name are None where the raster holds nodata
+    fn classify_point(&self, py: Python<'_>, lon: f64, lat: f64) -> PyResult<PyObject> {
+        let mut classified = self.classify_points(py, vec![(lon, lat)])?;
+        Ok(classified.remove(0))
+    }
+
+    /// Classify a list of (lon, lat) WGS84 coordinates.
+    ///
+    /// Parameters:
+    /// - points: List of (longitude, latitude) tuples in degrees
+    ///
+    /// Returns:
+    /// List of dicts in the same order, as returned by classify_point
+    fn classify_points(&self, py: Python<'_>, points: Vec<(f64, f64)>) -> PyResult<Vec<PyObject>> {
+        let ids: Vec<String> = (0..points.len()).map(|i| i.to_string()).collect();
+        let (lons, lats): (Vec<f64>, Vec<f64>) = points.into_iter().unzip();
+        let df = DataFrame::new(vec![
+            Series::new("station_id", ids),
+            Series::new("longitude", lons),
+            Series::new("latitude", lats),
+        ])
+        .map_err(|e| convert_classifier_error_to_py(e.into()))?;
+
+        let classified = self
+            .classifier()?
+            .classify_stations(&df, &ClassificationRequest::new())
+            .map_err(convert_classifier_error_to_py)?;
+        classified
+            .iter()
+            .map(|station| {
+                let dict = PyDict::new(py);
+                dict.set_item("code", station.lcz.map(|lcz| lcz.to_code()))?;
+                dict.set_item("name", station.lcz.map(|lcz| lcz.full_name()))?;
+                dict.set_item(
+                    "category",
+                    station
+                        .category
+                        .as_ref()
+                        .map(|category| category.as_ref().to_string()),
+                )?;
+                Ok(dict.to_object(py))
+            })
+            .collect()
+    }
+
+    /// Classify a DataFrame whose column names are not known in advance.
+    ///
+    /// The station ID column is matched through common aliases (station, id,
+    /// site_id, ...) and the longitude/latitude columns are guessed from
+    /// their names and value ranges.
+    ///
+    /// Parameters:
+    /// - df: Polars DataFrame containing station data
+    ///
+    /// Returns:
+    /// Polars DataFrame with the same columns as run_classification
+    fn classify_detected(&self, df: PyDataFrame) -> PyResult<PyDataFrame> {
         let result_df = self
-            .inner
-            .run_classification(&df.0, station_id_col, lon_col, lat_col, overrides.as_ref())
+            .classifier()?
+            .classify_detected(&df.0)
             .map_err(convert_classifier_error_to_py)?;
 
         Ok(PyDataFrame(result_df))
@@ -109,15 +417,12 @@ impl PyUrbanClassifier {
     /// - categories: List of simplified categories
     #[staticmethod]
     fn get_lcz_info() -> PyResult<HashMap<String, Vec<String>>> {
-        use crate::lcz::Lcz;
-
         let mut codes = Vec::new();
         let mut names = Vec::new();
         let mut categories = Vec::new();
 
-        for code in 1..=17 {
-            let lcz = Lcz::from_code(code);
-            codes.push(code.to_string());
+        for lcz in Lcz::iter() {
+            codes.push(lcz.to_code().to_string());
             names.push(lcz.full_name().to_string());
             categories.push(lcz.simple_category().as_ref().to_string());
         }
@@ -150,7 +455,7 @@ impl PyUrbanClassifier {
         // Use a dummy classification to validate the schema
         // This is a bit inefficient but ensures we use the same validation logic
         let _result = self
-            .inner
+            .classifier()?
             .run_classification(&df.0, station_id_col, lon_col, lat_col, None)
             .map_err(convert_classifier_error_to_py)?;
 
@@ -158,50 +463,249 @@ impl PyUrbanClassifier {
     }
 }
 
-/// Convert Rust ClassifierError to appropriate Python exceptions
+/// One of the 17 standard Local Climate Zones, exposed to Python as `Lcz`
+#[pyclass(name = "Lcz", frozen)]
+#[derive(Clone, Copy)]
+pub struct PyLcz {
+    inner: Lcz,
+}
+
+#[pymethods]
+impl PyLcz {
+    /// Look up a class by its numeric code (1-17).
+    ///
+    /// Raises ValueError for other codes.
+    #[staticmethod]
+    fn from_code(code: u8) -> PyResult<Self> {
+        Lcz::try_from_code(code)
+            .map(|inner| PyLcz { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// Look up a class by name, letter or code, ignoring case,
+    /// e.g. "Open low-rise", "LCZ D" or "6".
+    ///
+    /// Raises ValueError if the string names no class.
+    #[staticmethod]
+    fn from_name(name: &str) -> PyResult<Self> {
+        name.parse::<Lcz>()
+            .map(|inner| PyLcz { inner })
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(e.to_string()))
+    }
+
+    /// All 17 classes in code order.
+    #[staticmethod]
+    fn all() -> Vec<Self> {
+        Lcz::iter().map(|inner| PyLcz { inner }).collect()
+    }
+
+    /// Numeric code (1-17)
+    #[getter]
+    fn code(&self) -> u8 {
+        self.inner.to_code()
+    }
+
+    /// WUDAPT label, "1"-"10" or "A"-"G"
+    #[getter]
+    fn letter(&self) -> &'static str {
+        self.inner.letter().unwrap_or_default()
+    }
+
+    /// Full class name, e.g. "Open low-rise"
+    #[getter]
+    fn name(&self) -> &'static str {
+        self.inner.full_name()
+    }
+
+    /// Simplified category: "Urban", "Suburban" or "Rural"
+    #[getter]
+    fn category(&self) -> String {
+        self.inner.simple_category().as_ref().to_string()
+    }
+
+    /// WUDAPT map colour as "#rrggbb"
+    #[getter]
+    fn color(&self) -> &'static str {
+        self.inner.color().unwrap_or_default()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Lcz({}, '{}')",
+            self.inner.to_code(),
+            self.inner.full_name()
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __int__(&self) -> u8 {
+        self.inner.to_code()
+    }
+
+    fn __hash__(&self) -> u64 {
+        u64::from(self.inner.to_code())
+    }
+
+    fn __richcmp__(&self, other: &Self, op: CompareOp) -> bool {
+        op.matches(self.inner.cmp(&other.inner))
+    }
+}
+
+/// Convert a pandas DataFrame to Polars with `polars.from_pandas`
+fn pandas_to_polars(py: Python<'_>, df: &PyAny) -> PyResult<PyDataFrame> {
+    py.import("polars")?
+        .call_method1("from_pandas", (df,))?
+        .extract()
+}
+
+/// Convert a Polars DataFrame to pandas with `polars.DataFrame.to_pandas`
+fn polars_to_pandas(py: Python<'_>, df: PyDataFrame) -> PyResult<PyObject> {
+    df.into_py(py).call_method0(py, "to_pandas")
+}
+
+create_exception!(
+    urban_classifier,
+    UrbanClassifierError,
+    pyo3::exceptions::PyException,
+    "Base class of all errors raised by urban_classifier."
+);
+create_exception!(
+    urban_classifier,
+    DataNotFoundError,
+    UrbanClassifierError,
+    "The LCZ map or another input file could not be found."
+);
+create_exception!(
+    urban_classifier,
+    SchemaError,
+    UrbanClassifierError,
+    "The DataFrame lacks a required column or has columns of the wrong type."
+);
+create_exception!(
+    urban_classifier,
+    CoordinateError,
+    UrbanClassifierError,
+    "A coordinate is invalid, unparseable, cannot be transformed or lies outside the map."
+);
+create_exception!(
+    urban_classifier,
+    RasterError,
+    UrbanClassifierError,
+    "The raster could not be opened or read."
+);
+create_exception!(
+    urban_classifier,
+    RasterSamplingError,
+    RasterError,
+    "A pixel could not be read or holds a value that is not an LCZ code."
+);
+create_exception!(
+    urban_classifier,
+    ConfigurationError,
+    UrbanClassifierError,
+    "An override, configuration file or parameter is invalid."
+);
+create_exception!(
+    urban_classifier,
+    DownloadError,
+    UrbanClassifierError,
+    "Downloading the LCZ map failed."
+);
+create_exception!(
+    urban_classifier,
+    ClassificationFailuresError,
+    UrbanClassifierError,
+    "Several stations could not be classified."
+);
+
+/// Convert Rust ClassifierError to the matching urban_classifier exception
+///
+/// Every exception carries the attributes `code` (the stable error code),
+/// `station_id`, `row_index`, `pixel` (a `(pixel, line)` tuple), `path` (the
+/// missing file or download URL) and `retryable`; those that do not apply
+/// are None.
 fn convert_classifier_error_to_py(error: ClassifierError) -> PyErr {
-    match error {
-        ClassifierError::FileNotFound { path } => {
-            pyo3::exceptions::PyFileNotFoundError::new_err(format!("File not found: {}", path))
+    let code = error.code();
+    let station_id = error.station_id().map(str::to_string);
+    let row_index = error.row_index();
+    let retryable = error.is_retryable();
+    let (pixel, path) = match &error {
+        ClassifierError::RasterSampling { pixel, line, .. }
+        | ClassifierError::OutOfBounds { pixel, line, .. } => (Some((*pixel, *line)), None),
+        ClassifierError::FileNotFound { path } => (None, Some(path.clone())),
+        ClassifierError::Download { url, .. } => (None, Some(url.clone())),
+        _ => (None, None),
+    };
+
+    let message = error.to_string();
+    let py_err = match error {
+        ClassifierError::FileNotFound { .. } | ClassifierError::MapNotFound { .. } => {
+            DataNotFoundError::new_err(message)
         }
-        ClassifierError::ColumnNotFound { column } => {
-            pyo3::exceptions::PyKeyError::new_err(format!("Column not found: {}", column))
+        ClassifierError::ColumnNotFound { .. } | ClassifierError::SchemaValidation { .. } => {
+            SchemaError::new_err(message)
         }
-        ClassifierError::InvalidCoordinate { lon, lat } => pyo3::exceptions::PyValueError::new_err(
-            format!("Invalid coordinate: longitude={}, latitude={}", lon, lat),
-        ),
-        ClassifierError::SchemaValidation { message } => pyo3::exceptions::PyValueError::new_err(
-            format!("Schema validation failed: {}", message),
-        ),
-        ClassifierError::GdalError { message } => {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("GDAL error: {}", message))
+        ClassifierError::InvalidCoordinate { .. }
+        | ClassifierError::CoordinateTransform { .. }
+        | ClassifierError::CoordinateParse { .. }
+        | ClassifierError::OutOfBounds { .. } => CoordinateError::new_err(message),
+        ClassifierError::RasterSampling { .. } | ClassifierError::UnexpectedRasterValue { .. } => {
+            RasterSamplingError::new_err(message)
         }
-        ClassifierError::CoordinateTransform { message } => {
-            pyo3::exceptions::PyRuntimeError::new_err(format!(
-                "Coordinate transformation failed: {}",
-                message
-            ))
+        ClassifierError::GdalError { .. } | ClassifierError::UnsupportedRaster { .. } => {
+            RasterError::new_err(message)
         }
-        ClassifierError::RasterSampling {
-            pixel,
-            line,
-            message,
-        } => pyo3::exceptions::PyRuntimeError::new_err(format!(
-            "Raster sampling failed at ({}, {}): {}",
-            pixel, line, message
-        )),
-        ClassifierError::OverrideApplication {
-            station_id,
-            message,
-        } => pyo3::exceptions::PyValueError::new_err(format!(
-            "Override application failed for station {}: {}",
-            station_id, message
-        )),
-        ClassifierError::Polars(e) => {
-            pyo3::exceptions::PyRuntimeError::new_err(format!("Polars error: {}", e))
+        ClassifierError::OverrideApplication { .. }
+        | ClassifierError::Config { .. }
+        | ClassifierError::InvalidParameter { .. } => ConfigurationError::new_err(message),
+        ClassifierError::Download { .. } => DownloadError::new_err(message),
+        ClassifierError::Multiple(_) => ClassificationFailuresError::new_err(message),
+        ClassifierError::Polars(_) | ClassifierError::Io(_) => {
+            UrbanClassifierError::new_err(message)
         }
-        ClassifierError::Io(e) => pyo3::exceptions::PyIOError::new_err(format!("I/O error: {}", e)),
-    }
+    };
+
+    Python::with_gil(|py| {
+        let value = py_err.value(py);
+        // Instances of these exception classes accept any attribute
+        let _ = value.setattr("code", code);
+        let _ = value.setattr("station_id", station_id);
+        let _ = value.setattr("row_index", row_index);
+        let _ = value.setattr("pixel", pixel);
+        let _ = value.setattr("path", path);
+        let _ = value.setattr("retryable", retryable);
+    });
+    py_err
+}
+
+/// Download the global WUDAPT LCZ map, returning the path of the local copy.
+///
+/// Blocks until the download completes; the global map is several gigabytes.
+/// Transient failures are retried before moving on to the next mirror.
+///
+/// Parameters:
+/// - version: Map version, "v3" (default) or "v2"
+/// - dest: File path to download to; defaults to
+///   PyUrbanClassifier.default_data_path(), where from_default_data looks
+/// - force: Download again even if a valid copy already exists at dest
+///
+/// Returns:
+/// String path to the downloaded (or already present) map
+#[pyfunction]
+#[pyo3(signature = (version = "v3", dest = None, force = false))]
+fn download_wudapt(
+    py: Python<'_>,
+    version: &str,
+    dest: Option<PathBuf>,
+    force: bool,
+) -> PyResult<String> {
+    let path = dest.unwrap_or_else(UrbanClassifier::default_data_path);
+    py.allow_threads(|| fetch_wudapt(version, &path, force))
+        .map_err(convert_classifier_error_to_py)?;
+    Ok(path.to_string_lossy().to_string())
 }
 
 /// Urban classifier module for Local Climate Zone (LCZ) classification.
@@ -210,8 +714,27 @@ fn convert_classifier_error_to_py(error: ClassifierError) -> PyErr {
 /// according to the Local Climate Zone system using WUDAPT global data.
 #[pymodule]
 #[pyo3(name = "urban_classifier")]
-pub fn urban_classifier_module(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+pub fn urban_classifier_module(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     m.add_class::<PyUrbanClassifier>()?;
+    m.add_class::<PyLcz>()?;
+    m.add_function(wrap_pyfunction!(download_wudapt, m)?)?;
+
+    // Exception hierarchy
+    m.add(
+        "UrbanClassifierError",
+        py.get_type::<UrbanClassifierError>(),
+    )?;
+    m.add("DataNotFoundError", py.get_type::<DataNotFoundError>())?;
+    m.add("SchemaError", py.get_type::<SchemaError>())?;
+    m.add("CoordinateError", py.get_type::<CoordinateError>())?;
+    m.add("RasterError", py.get_type::<RasterError>())?;
+    m.add("RasterSamplingError", py.get_type::<RasterSamplingError>())?;
+    m.add("ConfigurationError", py.get_type::<ConfigurationError>())?;
+    m.add("DownloadError", py.get_type::<DownloadError>())?;
+    m.add(
+        "ClassificationFailuresError",
+        py.get_type::<ClassificationFailuresError>(),
+    )?;
 
     // Add module-level constants
     m.add("__version__", "0.1.0")?;
@@ -0,0 +1,164 @@
+//! Typed Classification Results
+//!
+//! `UrbanClassifier::classify_stations` returns a `ClassifiedStations`
+//! holding the usual enhanced DataFrame together with one
+//! `StationClassification` record per input row, so callers that do not work
+//! with DataFrames get the class of each station as `Lcz` and `LczCategory`
+//! values instead of parsing string columns.
+//!
+//! # Examples
+#![cfg_attr(feature = "gdal", doc = "```no_run")]
+#![cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+//! use polars::prelude::*;
+//! use urban_classifier::request::ClassificationRequest;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+//! let stations = df! {
+//!     "station_id" => ["A", "B"],
+//!     "longitude" => [-0.12, -0.45],
+//!     "latitude" => [51.5, 51.47],
+//! }?;
+//! let classified = classifier.classify_stations(&stations, &ClassificationRequest::new())?;
+//! for station in &classified {
+//!     println!("{}: {:?}", station.id, station.lcz);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use crate::lcz::{Lcz, LczCategory};
+use crate::request::ClassificationRequest;
+use polars::prelude::*;
+
+/// Classification of one station
+#[derive(Debug, Clone, PartialEq)]
+pub struct StationClassification {
+    /// Station ID ("unknown" where the ID column is null)
+    pub id: String,
+    /// WGS84 longitude in degrees, after any input CRS transform
+    pub lon: f64,
+    /// WGS84 latitude in degrees, after any input CRS transform
+    pub lat: f64,
+    /// Local climate zone, or `None` where the raster holds nodata
+    pub lcz: Option<Lcz>,
+    /// Simplified category of `lcz` under the classifier's category scheme,
+    /// or the scheme's nodata category where `lcz` is `None`
+    pub category: Option<LczCategory>,
+    /// True when the class comes from a manual override
+    pub overridden: bool,
+}
+
+/// Enhanced DataFrame and typed per-station records of one classification run
+#[derive(Debug, Clone)]
+pub struct ClassifiedStations {
+    dataframe: DataFrame,
+    stations: Vec<StationClassification>,
+}
+
+impl ClassifiedStations {
+    /// The `classify_with` output DataFrame
+    pub fn dataframe(&self) -> &DataFrame {
+        &self.dataframe
+    }
+
+    /// Take the DataFrame, discarding the records
+    pub fn into_dataframe(self) -> DataFrame {
+        self.dataframe
+    }
+
+    /// Records in input row order
+    pub fn stations(&self) -> &[StationClassification] {
+        &self.stations
+    }
+
+    /// Iterate over the records in input row order
+    pub fn iter(&self) -> std::slice::Iter<'_, StationClassification> {
+        self.stations.iter()
+    }
+
+    /// Number of classified stations
+    pub fn len(&self) -> usize {
+        self.stations.len()
+    }
+
+    /// True when the input had no rows
+    pub fn is_empty(&self) -> bool {
+        self.stations.is_empty()
+    }
+}
+
+impl IntoIterator for ClassifiedStations {
+    type Item = StationClassification;
+    type IntoIter = std::vec::IntoIter<StationClassification>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stations.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ClassifiedStations {
+    type Item = &'a StationClassification;
+    type IntoIter = std::slice::Iter<'a, StationClassification>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.stations.iter()
+    }
+}
+
+impl UrbanClassifier {
+    /// Run LCZ classification and return typed records alongside the DataFrame
+    ///
+    /// The DataFrame is the same as returned by `classify_with`.
+    pub fn classify_stations(
+        &self,
+        stations_df: &DataFrame,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<ClassifiedStations> {
+        let dataframe = self.classify_with(stations_df, request)?;
+
+        let columns = request.resolve_columns(stations_df)?;
+        let prepared = self.prepare_stations(
+            stations_df,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+            request,
+        )?;
+        let (ids, coordinates) = self.extract_coordinates(
+            &prepared,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+        )?;
+        let codes = dataframe.column("lcz_code")?.u32()?;
+
+        let stations = ids
+            .into_iter()
+            .zip(coordinates)
+            .zip(codes)
+            .map(|((id, (lon, lat)), code)| {
+                let lcz = code.map(|code| Lcz::from_code(code as u8));
+                StationClassification {
+                    overridden: request
+                        .overrides()
+                        .is_some_and(|overrides| overrides.contains_key(&id)),
+                    id,
+                    lon,
+                    lat,
+                    lcz,
+                    category: match lcz {
+                        Some(lcz) => Some(self.category_scheme().category(lcz)),
+                        None => self.category_scheme().nodata_category(),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(ClassifiedStations {
+            dataframe,
+            stations,
+        })
+    }
+}
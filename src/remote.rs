@@ -0,0 +1,87 @@
+//! Remote Raster Paths
+//!
+//! GDAL can read cloud-optimised GeoTIFFs over the network through its virtual
+//! file systems, fetching only the blocks that are sampled. This module maps
+//! URLs onto those virtual file system paths so the hosted WUDAPT map can be
+//! used without downloading it first.
+//!
+//! # Supported Schemes
+//!
+//! - `http://` and `https://` → `/vsicurl/`
+//! - `s3://bucket/key` → `/vsis3/bucket/key`
+//! - `gs://bucket/key` → `/vsigs/bucket/key`
+//! - Paths already starting with `/vsi` are passed through unchanged
+//!
+//! Credentials and network behaviour are configured through GDAL's usual
+//! environment variables (e.g. `AWS_ACCESS_KEY_ID`, `AWS_NO_SIGN_REQUEST`,
+//! `GS_OAUTH2_REFRESH_TOKEN`, `GDAL_HTTP_TIMEOUT`).
+
+/// Convert a remote URL to a GDAL virtual file system path
+///
+/// Returns `None` for local paths.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::remote::to_gdal_path;
+///
+/// assert_eq!(
+///     to_gdal_path("s3://wudapt/lcz.tif").as_deref(),
+///     Some("/vsis3/wudapt/lcz.tif")
+/// );
+/// assert_eq!(to_gdal_path("data/lcz.tif"), None);
+/// ```
+pub fn to_gdal_path(path: &str) -> Option<String> {
+    let lower = path.to_ascii_lowercase();
+
+    if lower.starts_with("/vsi") {
+        Some(path.to_string())
+    } else if lower.starts_with("http://") || lower.starts_with("https://") {
+        Some(format!("/vsicurl/{}", path))
+    } else if lower.starts_with("s3://") {
+        Some(format!("/vsis3/{}", &path["s3://".len()..]))
+    } else if lower.starts_with("gs://") {
+        Some(format!("/vsigs/{}", &path["gs://".len()..]))
+    } else {
+        None
+    }
+}
+
+/// Check whether a path refers to a remote raster
+pub fn is_remote(path: &str) -> bool {
+    to_gdal_path(path).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test mapping of each supported URL scheme
+    #[test]
+    fn test_to_gdal_path() {
+        assert_eq!(
+            to_gdal_path("https://example.org/lcz.tif").as_deref(),
+            Some("/vsicurl/https://example.org/lcz.tif")
+        );
+        assert_eq!(
+            to_gdal_path("HTTP://example.org/lcz.tif").as_deref(),
+            Some("/vsicurl/HTTP://example.org/lcz.tif")
+        );
+        assert_eq!(
+            to_gdal_path("gs://bucket/dir/lcz.tif").as_deref(),
+            Some("/vsigs/bucket/dir/lcz.tif")
+        );
+        assert_eq!(
+            to_gdal_path("/vsizip/archive.zip/lcz.tif").as_deref(),
+            Some("/vsizip/archive.zip/lcz.tif")
+        );
+    }
+
+    /// Test that local paths are not treated as remote
+    #[test]
+    fn test_local_paths() {
+        assert!(!is_remote("/data/wudapt_lcz_global.tif"));
+        assert!(!is_remote("C:\\data\\lcz.tif"));
+        assert!(!is_remote("https_data/lcz.tif"));
+        assert!(is_remote("s3://bucket/lcz.tif"));
+    }
+}
@@ -0,0 +1,597 @@
+//! Classification Requests
+//!
+//! `ClassificationRequest` gathers the per-run options of a classification
+//! (column names, input CRS, manual overrides and additional output columns) so that
+//! `UrbanClassifier::classify_with` keeps one signature as options are
+//! added. The positional `run_classification*` methods remain as shorthands.
+//!
+//! # Examples
+#![cfg_attr(feature = "gdal", doc = "```no_run")]
+#![cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+//! use polars::prelude::*;
+//! use std::collections::HashMap;
+//! use urban_classifier::request::ClassificationRequest;
+//! use urban_classifier::stability::StabilityConfig;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+//! let stations = df! {
+//!     "src_id" => ["A", "B"],
+//!     "lon" => [-0.12, -0.45],
+//!     "lat" => [51.5, 51.47],
+//! }?;
+//! let overrides = HashMap::from([("B".to_string(), 14)]);
+//!
+//! let request = ClassificationRequest::new()
+//!     .with_station_id_column("src_id")
+//!     .with_longitude_column("lon")
+//!     .with_latitude_column("lat")
+//!     .with_overrides(&overrides)
+//!     .with_buffer(500.0)
+//!     .with_stability(StabilityConfig::default());
+//! let result = classifier.classify_with(&stations, &request)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+#[cfg(feature = "gdal")]
+use crate::bands::BandSet;
+use crate::category::Grouping;
+use crate::classifier::{check_buffer_radius, check_stability_config, UrbanClassifier};
+use crate::columns::{coordinates_swapped, detect_coordinate_columns, parse_coordinate_column};
+use crate::config::ColumnNames;
+use crate::error::{ClassifierError, Result};
+use crate::failures::{ClassificationErrors, FailureMode};
+use crate::lcz::LczCategory;
+use crate::smoothing::MajorityFilter;
+use crate::source::RasterCrs;
+use crate::stability::StabilityConfig;
+use polars::prelude::*;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Which columns a classification run returns
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputColumns {
+    /// Every input column followed by the classification columns
+    #[default]
+    All,
+    /// Only the station ID column followed by the classification columns,
+    /// for joining back onto wide input frames
+    LczOnly,
+}
+
+/// Column names, overrides and additional outputs of one classification run
+#[derive(Debug, Clone, Default)]
+pub struct ClassificationRequest<'a> {
+    columns: ColumnNames,
+    overrides: Option<&'a HashMap<String, u8>>,
+    buffer_radius_m: Option<f64>,
+    stability: Option<StabilityConfig>,
+    #[cfg(feature = "gdal")]
+    bands: Option<BandSet>,
+    groupings: Vec<Grouping>,
+    output: OutputColumns,
+    column_aliases: bool,
+    coordinate_detection: bool,
+    swap_correction: bool,
+    skip_swap_detection: bool,
+    input_crs: Option<RasterCrs>,
+    coordinate_parsing: bool,
+    failure_mode: FailureMode,
+    majority_filter: Option<MajorityFilter>,
+    nodata_category: Option<LczCategory>,
+}
+
+impl<'a> ClassificationRequest<'a> {
+    /// Request classification only, reading the `station_id`, `longitude`
+    /// and `latitude` columns
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set all three station column names
+    pub fn with_columns(mut self, columns: ColumnNames) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Set the column holding station IDs
+    pub fn with_station_id_column(mut self, name: &str) -> Self {
+        self.columns.station_id = name.to_string();
+        self
+    }
+
+    /// Set the column holding longitudes in degrees
+    pub fn with_longitude_column(mut self, name: &str) -> Self {
+        self.columns.longitude = name.to_string();
+        self
+    }
+
+    /// Set the column holding latitudes in degrees
+    pub fn with_latitude_column(mut self, name: &str) -> Self {
+        self.columns.latitude = name.to_string();
+        self
+    }
+
+    /// Match column names case-insensitively and through common aliases
+    ///
+    /// See the `columns` module for the accepted aliases, e.g. `lat`, `LNG`
+    /// or `Longitude`. Off by default.
+    pub fn with_column_aliases(mut self, enabled: bool) -> Self {
+        self.column_aliases = enabled;
+        self
+    }
+
+    /// Ignore the configured coordinate column names and guess them instead
+    ///
+    /// Uses `columns::detect_coordinate_columns`. Off by default.
+    pub fn with_coordinate_detection(mut self, enabled: bool) -> Self {
+        self.coordinate_detection = enabled;
+        self
+    }
+
+    /// Exchange the coordinate columns when they appear to be swapped
+    ///
+    /// Without this, a run whose columns `columns::coordinates_swapped`
+    /// reports as swapped fails with a `SchemaValidation` error. Off by
+    /// default.
+    pub fn with_swap_correction(mut self, enabled: bool) -> Self {
+        self.swap_correction = enabled;
+        self
+    }
+
+    /// Leave coordinate columns that appear to be swapped as they are
+    ///
+    /// Used by the positional `run_classification`, which predates swap
+    /// detection.
+    pub(crate) fn without_swap_detection(mut self) -> Self {
+        self.skip_swap_detection = true;
+        self
+    }
+
+    /// Parse string coordinate columns instead of rejecting them
+    ///
+    /// Uses `columns::parse_coordinate_column`, so values such as
+    /// `"51.5074 N"` are accepted and unparseable rows are reported together
+    /// in one `CoordinateParse` error. Output columns keep the input strings.
+    /// Off by default.
+    pub fn with_coordinate_parsing(mut self, enabled: bool) -> Self {
+        self.coordinate_parsing = enabled;
+        self
+    }
+
+    /// Read station coordinates in `crs` instead of WGS84 longitude/latitude
+    ///
+    /// The coordinate columns then hold (x, y), e.g. (easting, northing) for
+    /// `RasterCrs::from_epsg(27700)`. They are transformed to WGS84 before
+    /// sampling; output columns keep the input values.
+    pub fn with_input_crs(mut self, crs: RasterCrs) -> Self {
+        self.input_crs = Some(crs);
+        self
+    }
+
+    /// Replace the classes of specific stations, keyed by station ID
+    pub fn with_overrides(mut self, overrides: &'a HashMap<String, u8>) -> Self {
+        self.overrides = Some(overrides);
+        self
+    }
+
+    /// Add class composition columns for a buffer of `radius_m` metres
+    pub fn with_buffer(mut self, radius_m: f64) -> Self {
+        self.buffer_radius_m = Some(radius_m);
+        self
+    }
+
+    /// Add positional stability columns
+    pub fn with_stability(mut self, config: StabilityConfig) -> Self {
+        self.stability = Some(config);
+        self
+    }
+
+    /// Add one column per sampled auxiliary band
+    #[cfg(feature = "gdal")]
+    pub fn with_bands(mut self, bands: BandSet) -> Self {
+        self.bands = Some(bands);
+        self
+    }
+
+    /// Add a column labelling each station's class under `grouping`
+    ///
+    /// May be called once per grouping; the columns follow `simple_class`
+    /// in the order requested.
+    pub fn with_grouping(mut self, grouping: Grouping) -> Self {
+        if !self.groupings.contains(&grouping) {
+            self.groupings.push(grouping);
+        }
+        self
+    }
+
+    /// Choose how stations that cannot be classified are handled
+    ///
+    /// With `FailureMode::CollectAll`, stations outside the raster or on
+    /// unreadable pixels get null classes, and null composition, stability and
+    /// band columns, instead of failing the run. `classify_with_report`
+    /// returns their errors. `FailureMode::FailFast` by default.
+    pub fn with_failure_mode(mut self, mode: FailureMode) -> Self {
+        self.failure_mode = mode;
+        self
+    }
+
+    /// Smooth station classes with `filter` instead of the classifier's
+    /// majority filter
+    pub fn with_majority_filter(mut self, filter: MajorityFilter) -> Self {
+        self.majority_filter = Some(filter);
+        self
+    }
+
+    /// Assign nodata stations to `category` in the `simple_class` column
+    /// instead of the classifier's category scheme default
+    pub fn with_nodata_category(mut self, category: LczCategory) -> Self {
+        self.nodata_category = Some(category);
+        self
+    }
+
+    /// Return only the station ID and classification columns
+    ///
+    /// Avoids carrying every input column through the result when it will be
+    /// joined back onto the input by station ID.
+    pub fn with_output_columns(mut self, output: OutputColumns) -> Self {
+        self.output = output;
+        self
+    }
+
+    /// Station column names
+    pub fn columns(&self) -> &ColumnNames {
+        &self.columns
+    }
+
+    /// Station column names as found in `df`, after alias resolution,
+    /// coordinate detection and swap correction if enabled
+    ///
+    /// # Errors
+    /// Returns the error of `detect_coordinate_columns` when detection is
+    /// enabled and fails, and `ClassifierError::SchemaValidation` when the
+    /// coordinate columns appear to be swapped and swap correction is off.
+    pub fn resolve_columns(&self, df: &DataFrame) -> Result<ColumnNames> {
+        let mut columns = if self.column_aliases {
+            self.columns.resolve_aliases(df)
+        } else {
+            self.columns.clone()
+        };
+        if self.coordinate_detection {
+            (columns.longitude, columns.latitude) = detect_coordinate_columns(df)?;
+        }
+        let geographic = matches!(self.input_crs, None | Some(RasterCrs::Wgs84));
+        if geographic
+            && !self.skip_swap_detection
+            && coordinates_swapped(df, &columns.longitude, &columns.latitude)
+        {
+            if !self.swap_correction {
+                return Err(ClassifierError::SchemaValidation {
+                    message: format!(
+                        "Longitude column '{}' and latitude column '{}' appear to be swapped: \
+                         most '{}' values lie outside ±90. Exchange the column names or \
+                         enable swap correction",
+                        columns.longitude, columns.latitude, columns.latitude
+                    ),
+                });
+            }
+            std::mem::swap(&mut columns.longitude, &mut columns.latitude);
+        }
+        Ok(columns)
+    }
+
+    /// Manual overrides, if any
+    pub fn overrides(&self) -> Option<&'a HashMap<String, u8>> {
+        self.overrides
+    }
+
+    /// CRS of the station coordinates, if not WGS84
+    pub fn input_crs(&self) -> Option<&RasterCrs> {
+        self.input_crs.as_ref()
+    }
+}
+
+impl UrbanClassifier {
+    /// Run LCZ classification on the conventional `station_id`, `longitude`
+    /// and `latitude` columns
+    ///
+    /// Equivalent to `classify_with(stations_df, &ClassificationRequest::new())`.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A"],
+    ///     "longitude" => [-0.12],
+    ///     "latitude" => [51.5],
+    /// }?;
+    /// let result = classifier.classify(&stations)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify(&self, stations_df: &DataFrame) -> Result<DataFrame> {
+        self.classify_with(stations_df, &ClassificationRequest::new())
+    }
+
+    /// Run LCZ classification on a frame with unknown column names
+    ///
+    /// The station ID column is found through its aliases and the coordinate
+    /// columns are guessed with `columns::detect_coordinate_columns`, which is
+    /// convenient for exploring unfamiliar files. Name the columns explicitly
+    /// with `classify_with` for production use.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "ID" => ["A"],
+    ///     "Y" => [51.5],
+    ///     "X" => [-120.12],
+    /// }?;
+    /// let result = classifier.classify_detected(&stations)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify_detected(&self, stations_df: &DataFrame) -> Result<DataFrame> {
+        let request = ClassificationRequest::new()
+            .with_column_aliases(true)
+            .with_coordinate_detection(true);
+        self.classify_with(stations_df, &request)
+    }
+
+    /// Run LCZ classification as described by a request
+    ///
+    /// # Returns
+    /// The `run_classification` output (reduced to the station ID column and
+    /// new columns with `OutputColumns::LczOnly`), followed by a column per
+    /// requested `Grouping`, the composition columns
+    /// of `run_classification_with_composition`, the stability columns of
+    /// `run_classification_with_stability` and the band columns of
+    /// `run_classification_with_bands` for each option that is set.
+    ///
+    /// # Errors
+    /// Invalid buffer radii, stability configurations and band selections are
+    /// reported before any station is sampled.
+    pub fn classify_with(
+        &self,
+        stations_df: &DataFrame,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<DataFrame> {
+        self.classify_with_report(stations_df, request)
+            .map(|(classified, _)| classified)
+    }
+
+    /// Run LCZ classification as described by a request, returning the
+    /// stations that could not be classified
+    ///
+    /// As `classify_with`. With `FailureMode::CollectAll`, failing stations
+    /// are listed in the returned `ClassificationErrors` and every column of
+    /// their row added by the run is null.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::failures::FailureMode;
+    /// use urban_classifier::request::ClassificationRequest;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.12, 540.0],
+    ///     "latitude" => [51.5, 51.47],
+    /// }?;
+    /// let request = ClassificationRequest::new()
+    ///     .with_buffer(500.0)
+    ///     .with_failure_mode(FailureMode::CollectAll);
+    /// let (result, errors) = classifier.classify_with_report(&stations, &request)?;
+    /// for error in &errors {
+    ///     eprintln!("{}", error);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify_with_report(
+        &self,
+        stations_df: &DataFrame,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<(DataFrame, ClassificationErrors)> {
+        if let Some(radius_m) = request.buffer_radius_m {
+            check_buffer_radius(radius_m)?;
+        }
+        if let Some(config) = &request.stability {
+            check_stability_config(config)?;
+        }
+        #[cfg(feature = "gdal")]
+        let bands = request
+            .bands
+            .as_ref()
+            .map(|bands| self.resolve_band_columns(bands, stations_df))
+            .transpose()?;
+        #[cfg(not(feature = "gdal"))]
+        let bands: Option<Vec<(isize, String)>> = None;
+
+        let ColumnNames {
+            station_id,
+            longitude,
+            latitude,
+        } = &request.resolve_columns(stations_df)?;
+        let majority_filter = request.majority_filter.as_ref().or(self.majority_filter());
+        let category_scheme = match request.nodata_category {
+            Some(category) => self.category_scheme().with_nodata_category(category),
+            None => *self.category_scheme(),
+        };
+        let prepared =
+            self.prepare_stations(stations_df, station_id, longitude, latitude, request)?;
+        let (mut classified, _, errors) = self.classify_core(
+            &prepared,
+            station_id,
+            longitude,
+            latitude,
+            request.overrides,
+            request.failure_mode,
+            request.output,
+            majority_filter,
+            &category_scheme,
+        )?;
+        if matches!(prepared, Cow::Owned(_)) {
+            for name in [longitude, latitude] {
+                if classified.get_column_index(name).is_some() {
+                    classified.replace(name, stations_df.column(name)?.clone())?;
+                }
+            }
+        }
+        if !request.groupings.is_empty() {
+            let codes = classified.column("lcz_code")?.u32()?.clone();
+            let groupings: Vec<Series> = request
+                .groupings
+                .iter()
+                .map(|grouping| grouping.series(&codes))
+                .collect();
+            classified.hstack_mut(&groupings)?;
+        }
+        if request.buffer_radius_m.is_none() && request.stability.is_none() && bands.is_none() {
+            return Ok((classified, errors));
+        }
+
+        // Stations that failed above are left out of the later stages
+        let (_, coordinates) =
+            self.extract_coordinates(&prepared, station_id, longitude, latitude)?;
+        let rows: Vec<usize> = (0..coordinates.len())
+            .filter(|row| errors.iter().all(|error| error.row != *row))
+            .collect();
+        let classified_coordinates: Vec<(f64, f64)> =
+            rows.iter().map(|&row| coordinates[row]).collect();
+        let input_row = |e: ClassifierError| match e.row_index() {
+            Some(row) => e.with_row(rows[row]),
+            None => e,
+        };
+
+        let mut extra = Vec::new();
+        if let Some(radius_m) = request.buffer_radius_m {
+            extra.extend(
+                self.composition_series(&classified_coordinates, radius_m)
+                    .map_err(input_row)?,
+            );
+        }
+        if let Some(config) = &request.stability {
+            extra.extend(
+                self.stability_series(&classified_coordinates, config, majority_filter)
+                    .map_err(input_row)?,
+            );
+        }
+        #[cfg(feature = "gdal")]
+        if let Some(bands) = &bands {
+            extra.extend(
+                self.band_series(bands, &classified_coordinates)
+                    .map_err(input_row)?,
+            );
+        }
+
+        if rows.len() < coordinates.len() {
+            extra = extra
+                .iter()
+                .map(|series| spread_rows(series, &rows, coordinates.len()))
+                .collect::<Result<_>>()?;
+        }
+        classified.hstack_mut(&extra)?;
+        Ok((classified, errors))
+    }
+
+    /// `stations_df` with its coordinate columns parsed from strings and
+    /// transformed from the request's input CRS to WGS84 as requested, or
+    /// unchanged when neither applies
+    pub(crate) fn prepare_stations<'d>(
+        &self,
+        stations_df: &'d DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<Cow<'d, DataFrame>> {
+        let mut prepared = Cow::Borrowed(stations_df);
+        if request.coordinate_parsing {
+            for name in [lon_col, lat_col] {
+                // Missing columns are reported by schema validation
+                let Ok(series) = stations_df.column(name) else {
+                    continue;
+                };
+                if let Some(parsed) = parse_coordinate_column(series)? {
+                    prepared.to_mut().replace(name, parsed)?;
+                }
+            }
+        }
+
+        let input_crs = match &request.input_crs {
+            None | Some(RasterCrs::Wgs84) => return Ok(prepared),
+            Some(crs) => crs,
+        };
+        self.validate_dataframe_schema(&prepared, station_id_col, lon_col, lat_col)?;
+
+        let xs = prepared.column(lon_col)?.cast(&DataType::Float64)?;
+        let ys = prepared.column(lat_col)?.cast(&DataType::Float64)?;
+        let rows: Vec<(usize, f64, f64)> = xs
+            .f64()?
+            .into_iter()
+            .zip(ys.f64()?)
+            .enumerate()
+            .filter_map(|(i, (x, y))| Some((i, x?, y?)))
+            .collect();
+
+        let mut lons: Vec<f64> = rows.iter().map(|(_, x, _)| *x).collect();
+        let mut lats: Vec<f64> = rows.iter().map(|(_, _, y)| *y).collect();
+        if !rows.is_empty() {
+            input_crs.transform_to_wgs84(&mut lons, &mut lats)?;
+        }
+
+        // Rows with a null coordinate stay null in both columns
+        let mut lon_values = vec![None; stations_df.height()];
+        let mut lat_values = vec![None; stations_df.height()];
+        for ((i, _, _), (lon, lat)) in rows.iter().zip(lons.into_iter().zip(lats)) {
+            lon_values[*i] = Some(lon);
+            lat_values[*i] = Some(lat);
+        }
+
+        let wgs84_df = prepared.to_mut();
+        wgs84_df.replace(lon_col, Series::new(lon_col, lon_values))?;
+        wgs84_df.replace(lat_col, Series::new(lat_col, lat_values))?;
+        Ok(prepared)
+    }
+}
+
+/// Spread a column computed for `rows` over a DataFrame of `height` rows,
+/// null in the rows not listed
+fn spread_rows(series: &Series, rows: &[usize], height: usize) -> Result<Series> {
+    let mut positions = vec![None; height];
+    for (position, &row) in rows.iter().enumerate() {
+        positions[row] = Some(position as IdxSize);
+    }
+    Ok(series.take(&IdxCa::from_iter(positions))?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that requests default to the conventional column names
+    #[test]
+    fn test_request_columns() {
+        let request = ClassificationRequest::new();
+        assert_eq!(request.columns(), &ColumnNames::default());
+        assert!(request.overrides().is_none());
+
+        let request = request
+            .with_station_id_column("src_id")
+            .with_latitude_column("lat");
+        assert_eq!(request.columns().station_id, "src_id");
+        assert_eq!(request.columns().longitude, "longitude");
+        assert_eq!(request.columns().latitude, "lat");
+    }
+}
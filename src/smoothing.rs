@@ -0,0 +1,249 @@
+//! Majority Filtering of LCZ Windows
+//!
+//! LCZ maps produced by pixel-wise classification contain isolated pixels whose
+//! class differs from all of their neighbours. A majority (modal) filter
+//! replaces each pixel by the most frequent value in a small kernel around it,
+//! suppressing this noise while keeping the boundaries of larger patches.
+//!
+//! `majority_filter` smooths a whole `PixelWindow`. The classifier can also
+//! apply the filter to the pixel under each station before lookup, see
+//! `UrbanClassifier::with_majority_filter`.
+//!
+//! # Ties
+//!
+//! When several values are equally frequent, the centre pixel keeps its value
+//! if it is one of them; otherwise the lowest value wins. Nodata pixels are
+//! neither counted nor changed.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use crate::spatial::PixelWindow;
+
+/// Shape of the neighbourhood considered by a majority filter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum KernelShape {
+    /// All pixels within `radius` along both axes
+    #[default]
+    Square,
+    /// Pixels whose centre lies within `radius` pixels of the centre pixel
+    Circle,
+}
+
+/// Majority filter over a square or circular kernel
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MajorityFilter {
+    radius: usize,
+    shape: KernelShape,
+}
+
+impl MajorityFilter {
+    /// Filter over a `(2 * radius + 1)` pixel square, e.g. 3x3 for radius 1
+    pub fn square(radius: usize) -> Self {
+        MajorityFilter {
+            radius,
+            shape: KernelShape::Square,
+        }
+    }
+
+    /// Filter over a disc of `radius` pixels
+    pub fn circle(radius: usize) -> Self {
+        MajorityFilter {
+            radius,
+            shape: KernelShape::Circle,
+        }
+    }
+
+    /// Radius of the kernel in pixels
+    pub fn radius(&self) -> usize {
+        self.radius
+    }
+
+    /// Shape of the kernel
+    pub fn shape(&self) -> KernelShape {
+        self.shape
+    }
+
+    /// Most frequent value around the absolute position (`pixel`, `line`)
+    ///
+    /// Pixels outside `window` and pixels equal to `nodata` are not counted.
+    /// Returns `None` if the centre pixel is outside the window or nodata.
+    pub fn mode_at(
+        &self,
+        window: &PixelWindow,
+        pixel: isize,
+        line: isize,
+        nodata: Option<u8>,
+    ) -> Option<u8> {
+        let centre = window.get(pixel, line).filter(|v| Some(*v) != nodata)?;
+        let radius = self.radius as isize;
+
+        let mut counts = [0u32; 256];
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                if self.shape == KernelShape::Circle && dx * dx + dy * dy > radius * radius {
+                    continue;
+                }
+                match window.get(pixel + dx, line + dy) {
+                    Some(value) if Some(value) != nodata => counts[usize::from(value)] += 1,
+                    _ => {}
+                }
+            }
+        }
+
+        // Ties keep the centre value, otherwise the lowest value with the top count wins
+        let max = counts.iter().copied().max().unwrap_or(0);
+        if counts[usize::from(centre)] == max {
+            return Some(centre);
+        }
+        counts
+            .iter()
+            .position(|&count| count == max)
+            .map(|v| v as u8)
+    }
+}
+
+/// Apply a majority filter to every pixel of a window
+///
+/// The kernel is truncated at the window edges, so pixels near the edges are
+/// filtered using fewer neighbours. Read a window `radius` pixels larger than
+/// the area of interest to avoid this.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::smoothing::{majority_filter, MajorityFilter};
+/// use urban_classifier::spatial::PixelWindow;
+///
+/// let window = PixelWindow {
+///     x_off: 0,
+///     y_off: 0,
+///     width: 3,
+///     height: 3,
+///     data: vec![6, 6, 6, 6, 2, 6, 6, 6, 6],
+/// };
+/// let smoothed = majority_filter(&window, &MajorityFilter::square(1), None);
+/// assert_eq!(smoothed.data, vec![6; 9]);
+/// ```
+pub fn majority_filter(
+    window: &PixelWindow,
+    filter: &MajorityFilter,
+    nodata: Option<u8>,
+) -> PixelWindow {
+    let mut data = window.data.clone();
+    for row in 0..window.height {
+        for col in 0..window.width {
+            let (pixel, line) = (window.x_off + col as isize, window.y_off + row as isize);
+            if let Some(mode) = filter.mode_at(window, pixel, line, nodata) {
+                data[row * window.width + col] = mode;
+            }
+        }
+    }
+
+    PixelWindow {
+        data,
+        ..window.clone()
+    }
+}
+
+impl UrbanClassifier {
+    /// Smooth the LCZ map with a majority filter before sampling stations
+    ///
+    /// Each station takes the most frequent class in the kernel around its
+    /// pixel instead of the class of the pixel itself. Only standard classes
+    /// (1-17, after the code mapping) are counted; stations on nodata pixels
+    /// remain nodata.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use urban_classifier::smoothing::MajorityFilter;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier =
+    ///     UrbanClassifier::new("path/to/wudapt.tif")?.with_majority_filter(MajorityFilter::square(1));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn with_majority_filter(mut self, filter: MajorityFilter) -> Self {
+        self.set_majority_filter(Some(filter));
+        self
+    }
+
+    /// Apply a majority filter to the mapped code of a station pixel
+    ///
+    /// Returns `code` unchanged without a filter, for nodata and for codes
+    /// outside the standard classes.
+    pub(crate) fn smoothed_code(
+        &self,
+        filter: Option<&MajorityFilter>,
+        pixel: isize,
+        line: isize,
+        code: Option<u8>,
+    ) -> Result<Option<u8>> {
+        let (Some(filter), Some(_)) = (filter, code) else {
+            return Ok(code);
+        };
+        let radius = filter.radius() as isize;
+        let size = 2 * filter.radius() + 1;
+        let mut window =
+            self.map_window(self.read_window(pixel - radius, line - radius, size, size)?);
+
+        // Count standard classes only
+        for value in window.data.iter_mut() {
+            if !(1..=17).contains(value) {
+                *value = 0;
+            }
+        }
+        Ok(filter.mode_at(&window, pixel, line, Some(0)).or(code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(width: usize, data: Vec<u8>) -> PixelWindow {
+        PixelWindow {
+            x_off: 10,
+            y_off: 20,
+            width,
+            height: data.len() / width,
+            data,
+        }
+    }
+
+    /// Test that isolated pixels are removed and patch edges are kept
+    #[test]
+    fn test_majority_filter() {
+        let noisy = window(4, vec![6, 6, 14, 14, 6, 2, 14, 14, 6, 6, 14, 14]);
+        let smoothed = majority_filter(&noisy, &MajorityFilter::square(1), None);
+        assert_eq!(
+            smoothed.data,
+            vec![6, 6, 14, 14, 6, 6, 14, 14, 6, 6, 14, 14]
+        );
+        assert_eq!((smoothed.x_off, smoothed.y_off), (10, 20));
+    }
+
+    /// Test tie-breaking, nodata and circular kernels
+    #[test]
+    fn test_mode_at() {
+        let filter = MajorityFilter::square(1);
+
+        // Two 3s, two 5s: the centre keeps its value, otherwise the lowest wins
+        let tied = window(2, vec![3, 5, 5, 3]);
+        assert_eq!(filter.mode_at(&tied, 11, 20, None), Some(5));
+        let tied = window(3, vec![3, 5, 3, 5, 9, 0]);
+        assert_eq!(filter.mode_at(&tied, 11, 21, Some(0)), Some(3));
+
+        // Nodata is neither counted nor replaced
+        let sparse = window(3, vec![0, 0, 0, 0, 4, 0, 0, 0, 7]);
+        assert_eq!(filter.mode_at(&sparse, 11, 21, Some(0)), Some(4));
+        assert_eq!(filter.mode_at(&sparse, 10, 20, Some(0)), None);
+
+        // Corners are outside a radius-1 circle
+        let corners = window(3, vec![9, 1, 9, 1, 1, 9, 9, 1, 9]);
+        assert_eq!(filter.mode_at(&corners, 11, 21, None), Some(9));
+        assert_eq!(
+            MajorityFilter::circle(1).mode_at(&corners, 11, 21, None),
+            Some(1)
+        );
+    }
+}
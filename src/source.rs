@@ -0,0 +1,580 @@
+//! Raster Source Abstraction
+//!
+//! The classifier reads LCZ values through the `RasterSource` trait rather than
+//! calling GDAL directly, so point classification, stability scoring, buffer
+//! composition and station validation work with any backend: the default GDAL
+//! dataset, an in-memory grid for tests, or a remote tile service.
+//!
+//! # Implementing a Source
+//!
+//! A source reports its size, geotransform and CRS once through `metadata`, and
+//! serves single pixels and rectangular windows of the LCZ band. Sources must
+//! be `Send + Sync`; the classifier may call them from several threads.
+//!
+//! Operations that need GDAL-specific capabilities (overviews, additional
+//! bands, H3 aggregation, raster info) remain available only for classifiers
+//! opened from a file, and only with the default `gdal` feature.
+
+use crate::error::{ClassifierError, Result};
+#[cfg(feature = "gdal")]
+use crate::pool::DatasetPool;
+use crate::spatial::{
+    bbox_window_with, buffer_radius_pixels, geo_to_pixel, geo_to_pixel_f, wrap_longitude,
+    BoundingBox, CrsUnits, PixelWindow,
+};
+#[cfg(feature = "gdal")]
+use crate::spatial::{
+    create_raster_to_wgs84_transform, create_wgs84_to_raster_transform, nodata_to_u8,
+    read_pixel_window, sample_raster_value, transform_coordinate,
+};
+use crate::trace::debug_event;
+#[cfg(feature = "gdal")]
+use gdal::spatial_ref::{CoordTransform, SpatialRef};
+#[cfg(feature = "gdal")]
+use std::sync::Arc;
+
+/// Coordinate reference system of a raster source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RasterCrs {
+    /// Geographic WGS84 with (longitude, latitude) axis order; no transform needed
+    Wgs84,
+    /// Any CRS described as WKT, transformed through GDAL/PROJ (`gdal` feature)
+    #[cfg(feature = "gdal")]
+    Wkt(String),
+    /// A CRS described as a PROJ.4 string, transformed with `proj4rs` when the
+    /// `no-gdal` feature is enabled and through GDAL/PROJ otherwise; rasters in
+    /// this CRS cannot be located when neither feature is enabled
+    Proj4(String),
+}
+
+impl RasterCrs {
+    /// Look up an EPSG code, using `Wgs84` for EPSG:4326
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use urban_classifier::source::RasterCrs;
+    ///
+    /// let etrs89_laea = RasterCrs::from_epsg(3035)?;
+    /// assert!(matches!(etrs89_laea, RasterCrs::Wkt(_)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "gdal")]
+    pub fn from_epsg(code: u32) -> Result<Self> {
+        if code == 4326 {
+            return Ok(RasterCrs::Wgs84);
+        }
+        Ok(RasterCrs::Wkt(SpatialRef::from_epsg(code)?.to_wkt()?))
+    }
+
+    /// Build the GDAL spatial reference for this CRS
+    #[cfg(feature = "gdal")]
+    pub(crate) fn to_spatial_ref(&self) -> Result<SpatialRef> {
+        Ok(match self {
+            RasterCrs::Wgs84 => SpatialRef::from_epsg(4326)?,
+            RasterCrs::Wkt(wkt) => SpatialRef::from_wkt(wkt)?,
+            RasterCrs::Proj4(proj) => SpatialRef::from_proj4(proj)?,
+        })
+    }
+
+    /// Check whether the CRS uses angular (degree) units
+    pub fn is_geographic(&self) -> Result<bool> {
+        match self {
+            RasterCrs::Wgs84 => Ok(true),
+            #[cfg(feature = "gdal")]
+            RasterCrs::Wkt(wkt) => Ok(SpatialRef::from_wkt(wkt)?.is_geographic()),
+            RasterCrs::Proj4(proj) => Ok(proj
+                .split_whitespace()
+                .any(|param| param == "+proj=longlat" || param == "+proj=latlong")),
+        }
+    }
+
+    /// Units of the CRS axes, for converting metric distances to pixels
+    pub fn units(&self) -> Result<CrsUnits> {
+        match self {
+            RasterCrs::Wgs84 => Ok(CrsUnits::Degrees),
+            #[cfg(feature = "gdal")]
+            RasterCrs::Wkt(wkt) => {
+                let srs = SpatialRef::from_wkt(wkt)?;
+                Ok(if srs.is_geographic() {
+                    CrsUnits::Degrees
+                } else {
+                    CrsUnits::Linear(srs.linear_units())
+                })
+            }
+            RasterCrs::Proj4(_) if self.is_geographic()? => Ok(CrsUnits::Degrees),
+            RasterCrs::Proj4(proj) => proj4_units(proj),
+        }
+    }
+
+    /// Transform coordinates in this CRS to WGS84 (longitude, latitude) in place
+    #[cfg_attr(
+        not(any(feature = "gdal", feature = "no-gdal")),
+        allow(unused_variables)
+    )]
+    pub(crate) fn transform_to_wgs84(&self, xs: &mut [f64], ys: &mut [f64]) -> Result<()> {
+        #[cfg(any(feature = "gdal", feature = "no-gdal"))]
+        let failed = |e: String| ClassifierError::CoordinateTransform {
+            message: format!("Failed to transform coordinates to WGS84: {}", e),
+            station_id: None,
+            row_index: None,
+        };
+        match self {
+            RasterCrs::Wgs84 => Ok(()),
+            #[cfg(feature = "no-gdal")]
+            RasterCrs::Proj4(proj) => {
+                let parse = |definition: &str| {
+                    proj4rs::Proj::from_proj_string(definition).map_err(|e| {
+                        failed(format!("invalid PROJ.4 definition '{}': {}", definition, e))
+                    })
+                };
+                let (raster, wgs84) = (parse(proj)?, parse("+proj=longlat +datum=WGS84 +no_defs")?);
+                let geographic = self.is_geographic()?;
+                for (x, y) in xs.iter_mut().zip(ys.iter_mut()) {
+                    let point = if geographic {
+                        (x.to_radians(), y.to_radians())
+                    } else {
+                        (*x, *y)
+                    };
+                    // proj4rs works in radians for geographic coordinates
+                    let (lon, lat) = proj4rs::adaptors::transform_vertex_2d(&raster, &wgs84, point)
+                        .map_err(|e| failed(e.to_string()))?;
+                    (*x, *y) = (lon.to_degrees(), lat.to_degrees());
+                }
+                Ok(())
+            }
+            #[cfg(not(any(feature = "gdal", feature = "no-gdal")))]
+            RasterCrs::Proj4(proj) => Err(ClassifierError::UnsupportedRaster {
+                message: format!(
+                    "Transforming from '{}' needs the `gdal` or `no-gdal` feature",
+                    proj
+                ),
+            }),
+            #[cfg(feature = "gdal")]
+            _ => {
+                let mut zs = vec![0.0; xs.len()];
+                create_raster_to_wgs84_transform(&self.to_spatial_ref()?)?
+                    .transform_coords(xs, ys, &mut zs)
+                    .map_err(|e| failed(e.to_string()))
+            }
+        }
+    }
+}
+
+/// Linear units of a projected PROJ.4 definition, from `+to_meter` or `+units`
+fn proj4_units(proj: &str) -> Result<CrsUnits> {
+    let param = |name: &str| {
+        proj.split_whitespace()
+            .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+    };
+    let unsupported = |message: String| ClassifierError::UnsupportedRaster { message };
+
+    if let Some(to_meter) = param("+to_meter") {
+        return to_meter
+            .parse()
+            .map(CrsUnits::Linear)
+            .map_err(|_| unsupported(format!("Invalid +to_meter value '{}'", to_meter)));
+    }
+    match param("+units") {
+        None | Some("m") => Ok(CrsUnits::METRES),
+        Some("km") => Ok(CrsUnits::Linear(1000.0)),
+        Some("ft") => Ok(CrsUnits::Linear(0.3048)),
+        Some("us-ft") => Ok(CrsUnits::Linear(1200.0 / 3937.0)),
+        Some(units) => Err(unsupported(format!("Unsupported PROJ.4 units '{}'", units))),
+    }
+}
+
+/// Size and georeferencing of a raster source
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterMetadata {
+    /// Raster width in pixels
+    pub width: usize,
+    /// Raster height in pixels
+    pub height: usize,
+    /// GDAL-style affine geotransform
+    pub geo_transform: [f64; 6],
+    /// Coordinate reference system of the geotransform
+    pub crs: RasterCrs,
+    /// Natural block size for reads, used by the tile cache
+    pub block_size: (usize, usize),
+    /// Raw value marked as nodata in the raster's metadata, if any
+    pub nodata: Option<u8>,
+}
+
+impl RasterMetadata {
+    /// Convert a radius in metres into pixel radii along the X and Y axes at
+    /// latitude `lat`, in the units of the raster's CRS
+    ///
+    /// See `spatial::buffer_radius_pixels`.
+    pub fn buffer_radius_pixels(&self, radius_m: f64, lat: f64) -> Result<(usize, usize)> {
+        Ok(buffer_radius_pixels(
+            radius_m,
+            lat,
+            &self.geo_transform,
+            self.crs.units()?,
+        ))
+    }
+}
+
+/// Backend providing LCZ band values to the classifier
+pub trait RasterSource: Send + Sync {
+    /// Describe the raster's size and georeferencing
+    fn metadata(&self) -> Result<RasterMetadata>;
+
+    /// Read a single raw value, failing for pixels outside the raster
+    fn sample(&self, pixel: isize, line: isize) -> Result<u8>;
+
+    /// Read a window of raw values, clipped to the raster extent
+    ///
+    /// Must follow the semantics of `spatial::read_pixel_window`: the result
+    /// holds the in-bounds part of the request and is empty when the request
+    /// lies entirely outside the raster.
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow>;
+}
+
+/// Raster source reading a band of a GDAL dataset
+#[cfg(feature = "gdal")]
+pub struct GdalSource {
+    datasets: Arc<DatasetPool>,
+    band_index: isize,
+    assumed_crs: Option<RasterCrs>,
+}
+
+#[cfg(feature = "gdal")]
+impl GdalSource {
+    /// Read band `band_index` (1-based) of the pooled datasets
+    ///
+    /// `assumed_crs` is used when the dataset has no spatial reference.
+    pub(crate) fn new(
+        datasets: Arc<DatasetPool>,
+        band_index: isize,
+        assumed_crs: Option<RasterCrs>,
+    ) -> Self {
+        GdalSource {
+            datasets,
+            band_index,
+            assumed_crs,
+        }
+    }
+}
+
+#[cfg(feature = "gdal")]
+impl RasterSource for GdalSource {
+    fn metadata(&self) -> Result<RasterMetadata> {
+        let dataset = self.datasets.get()?;
+        let band = dataset.rasterband(self.band_index)?;
+        let (width, height) = band.size();
+
+        let crs = match (dataset.spatial_ref(), &self.assumed_crs) {
+            (Ok(srs), _) => RasterCrs::Wkt(srs.to_wkt()?),
+            (Err(_), Some(crs)) => crs.clone(),
+            (Err(e), None) => {
+                return Err(ClassifierError::GdalError {
+                    message: format!(
+                        "Raster has no usable spatial reference ({}); \
+                         supply one with OpenOptions::assume_crs",
+                        e
+                    ),
+                    source: Some(e),
+                })
+            }
+        };
+
+        Ok(RasterMetadata {
+            width,
+            height,
+            geo_transform: dataset.geo_transform()?,
+            crs,
+            block_size: band.block_size(),
+            nodata: band
+                .no_data_value()
+                .map(|nodata| nodata_to_u8(nodata).unwrap_or(0)),
+        })
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> Result<u8> {
+        let dataset = self.datasets.get()?;
+        let band = dataset.rasterband(self.band_index)?;
+        sample_raster_value(&band, pixel, line)
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        let dataset = self.datasets.get()?;
+        let band = dataset.rasterband(self.band_index)?;
+        read_pixel_window(&band, pixel, line, width, height)
+    }
+}
+
+/// Transform from WGS84 into a raster source's CRS
+enum Projection {
+    Identity,
+    #[cfg(feature = "gdal")]
+    Gdal(CoordTransform),
+    #[cfg(feature = "no-gdal")]
+    Proj4 {
+        wgs84: Box<proj4rs::Proj>,
+        raster: Box<proj4rs::Proj>,
+        geographic: bool,
+    },
+}
+
+/// Edges of a north-up geographic raster, for wrap-aware station lookup
+#[derive(Debug, Clone, Copy)]
+struct GeographicEdges {
+    west: f64,
+    south: f64,
+    height: isize,
+}
+
+/// Maps WGS84 coordinates onto the pixel grid of a raster source
+pub(crate) struct PixelLocator {
+    projection: Projection,
+    geo_transform: [f64; 6],
+    /// Set for north-up rasters in a geographic CRS
+    edges: Option<GeographicEdges>,
+}
+
+impl PixelLocator {
+    /// Prepare the coordinate transform for a source's CRS
+    pub(crate) fn new(metadata: &RasterMetadata) -> Result<Self> {
+        let projection = match &metadata.crs {
+            RasterCrs::Wgs84 => Projection::Identity,
+            #[cfg(feature = "gdal")]
+            RasterCrs::Wkt(wkt) => Projection::Gdal(create_wgs84_to_raster_transform(
+                &SpatialRef::from_wkt(wkt)?,
+            )?),
+            #[cfg(feature = "no-gdal")]
+            RasterCrs::Proj4(proj) => {
+                let parse = |definition: &str| {
+                    proj4rs::Proj::from_proj_string(definition)
+                        .map(Box::new)
+                        .map_err(|e| ClassifierError::CoordinateTransform {
+                            message: format!("Invalid PROJ.4 definition '{}': {}", definition, e),
+                            station_id: None,
+                            row_index: None,
+                        })
+                };
+                Projection::Proj4 {
+                    wgs84: parse("+proj=longlat +datum=WGS84 +no_defs")?,
+                    raster: parse(proj)?,
+                    geographic: metadata.crs.is_geographic()?,
+                }
+            }
+            #[cfg(all(feature = "gdal", not(feature = "no-gdal")))]
+            RasterCrs::Proj4(proj) => Projection::Gdal(create_wgs84_to_raster_transform(
+                &SpatialRef::from_proj4(proj)?,
+            )?),
+            #[cfg(not(any(feature = "gdal", feature = "no-gdal")))]
+            RasterCrs::Proj4(proj) => {
+                return Err(ClassifierError::UnsupportedRaster {
+                    message: format!(
+                        "Transforming to '{}' needs the `gdal` or `no-gdal` feature",
+                        proj
+                    ),
+                })
+            }
+        };
+
+        debug_event!(crs = ?metadata.crs, "prepared coordinate transform");
+
+        let gt = metadata.geo_transform;
+        let north_up = gt[2] == 0.0 && gt[4] == 0.0 && gt[1] > 0.0 && gt[5] < 0.0;
+        let edges = (north_up && metadata.crs.is_geographic()?).then(|| GeographicEdges {
+            west: gt[0],
+            south: gt[3] + metadata.height as f64 * gt[5],
+            height: metadata.height as isize,
+        });
+
+        Ok(PixelLocator {
+            projection,
+            geo_transform: gt,
+            edges,
+        })
+    }
+
+    /// Find the (pixel, line) containing a WGS84 coordinate
+    ///
+    /// In geographic rasters, longitudes are wrapped into the raster's extent,
+    /// so stations just across the antimeridian from a raster edge are found
+    /// on the other side, and stations at the South Pole fall into the bottom
+    /// row of rasters reaching it.
+    pub(crate) fn locate(&self, lon: f64, lat: f64) -> Result<(isize, isize)> {
+        let (pixel, line) = self.locate_f(lon, lat)?;
+        let (pixel, line) = (pixel.floor() as isize, line.floor() as isize);
+        match self.edges {
+            Some(edges) if line == edges.height && edges.south <= -90.0 => Ok((pixel, line - 1)),
+            _ => Ok((pixel, line)),
+        }
+    }
+
+    /// Find the fractional (pixel, line) position of a WGS84 coordinate
+    ///
+    /// Longitudes are wrapped as in `locate`; the fractional part is the
+    /// position of the coordinate within its pixel.
+    pub(crate) fn locate_f(&self, lon: f64, lat: f64) -> Result<(f64, f64)> {
+        let (x, y) = self.project(lon, lat)?;
+        let Some(edges) = self.edges else {
+            return Ok(geo_to_pixel_f(x, y, &self.geo_transform));
+        };
+
+        // Transforms may return coordinates a rounding error beyond the poles
+        let y = y.clamp(-90.0, 90.0);
+        Ok(geo_to_pixel_f(
+            wrap_longitude(x, edges.west),
+            y,
+            &self.geo_transform,
+        ))
+    }
+
+    /// Find the pixel window covering a WGS84 bounding box, unclipped
+    pub(crate) fn bbox_window(&self, bbox: &BoundingBox) -> Result<(isize, isize, usize, usize)> {
+        bbox_window_with(bbox, |lon, lat| {
+            let (x, y) = self.project(lon, lat)?;
+            Ok(geo_to_pixel(x, y, &self.geo_transform))
+        })
+    }
+
+    /// Transform a WGS84 coordinate into the raster's CRS
+    fn project(&self, lon: f64, lat: f64) -> Result<(f64, f64)> {
+        Ok(match &self.projection {
+            #[cfg(feature = "gdal")]
+            Projection::Gdal(transform) => transform_coordinate(lon, lat, transform)?,
+            Projection::Identity => {
+                check_wgs84_range(lon, lat)?;
+                (lon, lat)
+            }
+            #[cfg(feature = "no-gdal")]
+            Projection::Proj4 {
+                wgs84,
+                raster,
+                geographic,
+            } => {
+                check_wgs84_range(lon, lat)?;
+                // proj4rs works in radians for geographic coordinates
+                let (x, y) = proj4rs::adaptors::transform_vertex_2d(
+                    wgs84,
+                    raster,
+                    (lon.to_radians(), lat.to_radians()),
+                )
+                .map_err(|e| ClassifierError::CoordinateTransform {
+                    message: format!("Failed to transform ({}, {}): {}", lon, lat, e),
+                    station_id: None,
+                    row_index: None,
+                })?;
+                if *geographic {
+                    (x.to_degrees(), y.to_degrees())
+                } else {
+                    (x, y)
+                }
+            }
+        })
+    }
+}
+
+/// Reject coordinates outside the WGS84 range before transforming them
+fn check_wgs84_range(lon: f64, lat: f64) -> Result<()> {
+    if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+        return Err(ClassifierError::InvalidCoordinate {
+            lon,
+            lat,
+            station_id: None,
+            row_index: None,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that WGS84 sources are located without a GDAL transform
+    #[test]
+    fn test_wgs84_locator() {
+        let metadata = RasterMetadata {
+            width: 360,
+            height: 180,
+            geo_transform: [-180.0, 1.0, 0.0, 90.0, 0.0, -1.0],
+            crs: RasterCrs::Wgs84,
+            block_size: (360, 1),
+            nodata: None,
+        };
+        let locator = PixelLocator::new(&metadata).unwrap();
+
+        assert_eq!(locator.locate(-0.5, 51.5).unwrap(), (179, 38));
+        assert!(matches!(
+            locator.locate(0.0, 95.0),
+            Err(ClassifierError::InvalidCoordinate { .. })
+        ));
+        assert!(RasterCrs::Wgs84.is_geographic().unwrap());
+
+        // Both edges of the antimeridian and the South Pole are inside
+        assert_eq!(locator.locate(180.0, 0.0).unwrap(), (0, 90));
+        assert_eq!(locator.locate(-180.0, -90.0).unwrap(), (0, 179));
+        assert_eq!(locator.locate(179.5, 89.5).unwrap(), (359, 0));
+    }
+
+    /// Test lookup in a geographic raster crossing the antimeridian
+    #[test]
+    fn test_antimeridian_locator() {
+        // Fiji, 170°E to 190°E
+        let metadata = RasterMetadata {
+            width: 200,
+            height: 100,
+            geo_transform: [170.0, 0.1, 0.0, -10.0, 0.0, -0.1],
+            crs: RasterCrs::Wgs84,
+            block_size: (200, 1),
+            nodata: None,
+        };
+        let locator = PixelLocator::new(&metadata).unwrap();
+
+        assert_eq!(locator.locate(178.45, -18.15).unwrap(), (84, 81));
+        assert_eq!(locator.locate(-179.85, -16.55).unwrap(), (101, 65));
+        assert_eq!(locator.locate(-169.95, -16.55).unwrap().0, 200);
+    }
+
+    /// Test CRS units of PROJ.4 definitions and metric buffers in pixels
+    #[test]
+    fn test_crs_units() {
+        let units = |proj: &str| RasterCrs::Proj4(proj.to_string()).units();
+
+        assert_eq!(
+            units("+proj=longlat +datum=WGS84").unwrap(),
+            CrsUnits::Degrees
+        );
+        assert_eq!(units("+proj=utm +zone=30").unwrap(), CrsUnits::METRES);
+        assert_eq!(
+            units("+proj=lcc +units=ft").unwrap(),
+            CrsUnits::Linear(0.3048)
+        );
+        assert_eq!(
+            units("+proj=tmerc +to_meter=0.9144").unwrap(),
+            CrsUnits::Linear(0.9144)
+        );
+        assert!(units("+proj=tmerc +units=furlong").is_err());
+
+        let metadata = RasterMetadata {
+            width: 100,
+            height: 100,
+            geo_transform: [0.0, 100.0, 0.0, 0.0, 0.0, -100.0],
+            crs: RasterCrs::Proj4("+proj=utm +zone=30 +units=ft".to_string()),
+            block_size: (100, 1),
+            nodata: None,
+        };
+        assert_eq!(
+            metadata.buffer_radius_pixels(304.8, 50.0).unwrap(),
+            (10, 10)
+        );
+    }
+}
@@ -3,14 +3,25 @@
 //! This module provides utilities for working with geospatial data, including:
 //! - Coordinate system transformations (WGS84 to raster CRS)
 //! - Converting geographic coordinates to pixel coordinates
-//! - Sampling raster values at specific locations
+//! - Sampling raster values at specific locations, in batches and in windows
 //! - Validating geotransform parameters
+//! - Great-circle distances and bearings between WGS84 points
+//! - Building overviews for raster files that lack them
+//! - Converting raster files to Cloud-Optimized GeoTIFFs
 //!
 //! # Coordinate Systems
 //!
 //! The module handles transformations between:
 //! - **WGS84 (EPSG:4326)**: Standard lat/lon coordinates
 //! - **Raster CRS**: The coordinate system of the input GeoTIFF
+//! - **Input CRS**: The coordinate system of station coordinates not given in
+//!   WGS84, such as British National Grid (EPSG:27700)
+//!
+//! `create_transform` builds a transform from any `CrsSpec` (EPSG code, WKT or
+//! PROJ string) to a target spatial reference.
+//!
+//! Coordinates are exchanged in traditional GIS order (longitude, latitude)
+//! regardless of the axis order in the CRS definition; see `AxisOrder`.
 //!
 //! # Geotransform
 //!
@@ -21,20 +32,221 @@
 //! - [3]: Top-left Y coordinate
 //! - \[4\]: Rotation (0 for north-up images)
 //! - [5]: Pixel height (negative for north-up)
+//!
+//! Rotated and skewed geotransforms are supported: `geo_to_pixel` applies the
+//! full inverse of the affine transformation, as GDAL's `InvGeoTransform`.
+//! `geo_to_pixel_f` returns the fractional position, and `geo_to_pixel_with`
+//! maps it to a pixel with an explicit `PixelRounding`.
 
 use crate::error::{ClassifierError, Result};
-use gdal::raster::RasterBand;
+use crate::source::RasterCrs;
+#[cfg(feature = "gdal")]
+use gdal::raster::{GdalDataType, GdalType, RasterBand, RasterCreationOption};
+#[cfg(feature = "gdal")]
 use gdal::spatial_ref::{CoordTransform, SpatialRef};
+#[cfg(feature = "gdal")]
+use gdal::{Dataset, DatasetOptions, DriverManager, GdalOpenFlags};
+#[cfg(feature = "gdal")]
+use ndarray::Array2;
+#[cfg(feature = "gdal")]
+use std::collections::BTreeMap;
+#[cfg(feature = "gdal")]
+use std::path::Path;
+
+/// Axis order used when passing coordinates to and from PROJ
+///
+/// Since GDAL 3, spatial references follow the axis order of their authority
+/// definition, so EPSG:4326 expects (latitude, longitude) and some projected
+/// CRSs expect (northing, easting). Inputs given as (longitude, latitude)
+/// would then be silently flipped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AxisOrder {
+    /// Always (longitude, latitude) or (easting, northing)
+    #[default]
+    TraditionalGis,
+    /// The order defined by the CRS authority, e.g. (latitude, longitude) for EPSG:4326
+    AuthorityCompliant,
+}
+
+#[cfg(feature = "gdal")]
+impl AxisOrder {
+    fn strategy(self) -> gdal_sys::OSRAxisMappingStrategy::Type {
+        match self {
+            AxisOrder::TraditionalGis => {
+                gdal_sys::OSRAxisMappingStrategy::OAMS_TRADITIONAL_GIS_ORDER
+            }
+            AxisOrder::AuthorityCompliant => {
+                gdal_sys::OSRAxisMappingStrategy::OAMS_AUTHORITY_COMPLIANT
+            }
+        }
+    }
+}
+
+/// A coordinate reference system given by EPSG code, WKT or PROJ string
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CrsSpec {
+    /// An EPSG code, e.g. 27700 for British National Grid
+    Epsg(u32),
+    /// A WKT definition
+    Wkt(String),
+    /// A PROJ string, e.g. `+proj=utm +zone=30 +datum=WGS84`
+    Proj(String),
+}
+
+impl CrsSpec {
+    /// WGS84 (EPSG:4326)
+    pub const WGS84: CrsSpec = CrsSpec::Epsg(4326);
+
+    /// Build the GDAL spatial reference for this CRS
+    #[cfg(feature = "gdal")]
+    pub fn to_spatial_ref(&self) -> Result<SpatialRef> {
+        Ok(match self {
+            CrsSpec::Epsg(code) => SpatialRef::from_epsg(*code)?,
+            CrsSpec::Wkt(wkt) => SpatialRef::from_wkt(wkt)?,
+            CrsSpec::Proj(proj) => SpatialRef::from_proj4(proj)?,
+        })
+    }
+}
+
+impl From<&RasterCrs> for CrsSpec {
+    fn from(crs: &RasterCrs) -> Self {
+        match crs {
+            RasterCrs::Wgs84 => CrsSpec::WGS84,
+            #[cfg(feature = "gdal")]
+            RasterCrs::Wkt(wkt) => CrsSpec::Wkt(wkt.clone()),
+            RasterCrs::Proj4(proj) => CrsSpec::Proj(proj.clone()),
+        }
+    }
+}
+
+/// Create a coordinate transformation from any CRS to a target spatial reference
+///
+/// Coordinates use the traditional GIS axis order, i.e. (longitude, latitude)
+/// or (easting, northing), on both sides.
+///
+/// # Examples
+/// ```no_run
+/// use gdal::spatial_ref::SpatialRef;
+/// use urban_classifier::spatial::{create_transform, CrsSpec};
+///
+/// // British National Grid to WGS84
+/// let transform = create_transform(&CrsSpec::Epsg(27700), &SpatialRef::from_epsg(4326)?)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gdal")]
+pub fn create_transform(src: &CrsSpec, dst: &SpatialRef) -> Result<CoordTransform> {
+    let (src, dst) = (src.to_spatial_ref()?, dst.clone());
+    src.set_axis_mapping_strategy(AxisOrder::default().strategy());
+    dst.set_axis_mapping_strategy(AxisOrder::default().strategy());
+
+    CoordTransform::new(&src, &dst).map_err(|e| ClassifierError::CoordinateTransform {
+        message: format!("Failed to create coordinate transform: {}", e),
+        station_id: None,
+        row_index: None,
+    })
+}
 
 /// Create a coordinate transformation from WGS84 to the raster's coordinate system
+///
+/// Coordinates use the traditional GIS axis order (longitude, latitude).
+#[cfg(feature = "gdal")]
 pub fn create_wgs84_to_raster_transform(raster_srs: &SpatialRef) -> Result<CoordTransform> {
-    let wgs84 = SpatialRef::from_epsg(4326)?;
+    create_transform(&CrsSpec::WGS84, raster_srs)
+}
 
-    CoordTransform::new(&wgs84, raster_srs).map_err(|e| ClassifierError::CoordinateTransform {
+/// Create a coordinate transformation from WGS84 to the raster's CRS with an explicit axis order
+///
+/// With `AxisOrder::AuthorityCompliant`, the arguments of `transform_coordinate`
+/// are interpreted in the CRS's own order, i.e. (latitude, longitude) for WGS84.
+#[cfg(feature = "gdal")]
+pub fn create_wgs84_to_raster_transform_with_axis_order(
+    raster_srs: &SpatialRef,
+    axis_order: AxisOrder,
+) -> Result<CoordTransform> {
+    let (wgs84, raster_srs) = axis_ordered_pair(raster_srs, axis_order)?;
+
+    CoordTransform::new(&wgs84, &raster_srs).map_err(|e| ClassifierError::CoordinateTransform {
         message: format!("Failed to create coordinate transform: {}", e),
+        station_id: None,
+        row_index: None,
+    })
+}
+
+/// Create a coordinate transformation from the raster's coordinate system back to WGS84
+///
+/// Coordinates use the traditional GIS axis order (longitude, latitude).
+#[cfg(feature = "gdal")]
+pub fn create_raster_to_wgs84_transform(raster_srs: &SpatialRef) -> Result<CoordTransform> {
+    create_raster_to_wgs84_transform_with_axis_order(raster_srs, AxisOrder::default())
+}
+
+/// Create a coordinate transformation from the raster's CRS to WGS84 with an explicit axis order
+#[cfg(feature = "gdal")]
+pub fn create_raster_to_wgs84_transform_with_axis_order(
+    raster_srs: &SpatialRef,
+    axis_order: AxisOrder,
+) -> Result<CoordTransform> {
+    let (wgs84, raster_srs) = axis_ordered_pair(raster_srs, axis_order)?;
+
+    CoordTransform::new(&raster_srs, &wgs84).map_err(|e| ClassifierError::CoordinateTransform {
+        message: format!("Failed to create inverse coordinate transform: {}", e),
+        station_id: None,
+        row_index: None,
+    })
+}
+
+/// Create a coordinate transformation from the CRS of station coordinates to WGS84
+///
+/// Coordinates use the traditional GIS axis order, i.e. (easting, northing)
+/// in and (longitude, latitude) out.
+#[cfg(feature = "gdal")]
+pub fn create_input_to_wgs84_transform(input_srs: &SpatialRef) -> Result<CoordTransform> {
+    let (wgs84, input_srs) = axis_ordered_pair(input_srs, AxisOrder::default())?;
+
+    CoordTransform::new(&input_srs, &wgs84).map_err(|e| ClassifierError::CoordinateTransform {
+        message: format!("Failed to create input CRS transform: {}", e),
+        station_id: None,
+        row_index: None,
     })
 }
 
+/// Transform station coordinates in place from their input CRS to WGS84
+///
+/// # Arguments
+/// * `xs` - X coordinates (eastings), replaced by longitudes in degrees
+/// * `ys` - Y coordinates (northings), replaced by latitudes in degrees
+/// * `transform` - A transform from `create_input_to_wgs84_transform`
+#[cfg(feature = "gdal")]
+pub fn transform_to_wgs84(
+    xs: &mut [f64],
+    ys: &mut [f64],
+    transform: &CoordTransform,
+) -> Result<()> {
+    let mut zs = vec![0.0; xs.len()];
+    transform
+        .transform_coords(xs, ys, &mut zs)
+        .map_err(|e| ClassifierError::CoordinateTransform {
+            message: format!("Failed to transform station coordinates to WGS84: {}", e),
+            station_id: None,
+            row_index: None,
+        })
+}
+
+/// WGS84 and a copy of the raster's spatial reference, both using `axis_order`
+///
+/// The raster's spatial reference is copied so the caller's is left unchanged.
+#[cfg(feature = "gdal")]
+fn axis_ordered_pair(
+    raster_srs: &SpatialRef,
+    axis_order: AxisOrder,
+) -> Result<(SpatialRef, SpatialRef)> {
+    let wgs84 = SpatialRef::from_epsg(4326)?;
+    let raster_srs = raster_srs.clone();
+    wgs84.set_axis_mapping_strategy(axis_order.strategy());
+    raster_srs.set_axis_mapping_strategy(axis_order.strategy());
+    Ok((wgs84, raster_srs))
+}
+
 /// Transform a single coordinate from WGS84 to the target coordinate system
 ///
 /// # Arguments
@@ -44,10 +256,16 @@ pub fn create_wgs84_to_raster_transform(raster_srs: &SpatialRef) -> Result<Coord
 ///
 /// # Returns
 /// Transformed (x, y) coordinates in the target system
+#[cfg(feature = "gdal")]
 pub fn transform_coordinate(lon: f64, lat: f64, transform: &CoordTransform) -> Result<(f64, f64)> {
     // Validate input coordinates
     if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
-        return Err(ClassifierError::InvalidCoordinate { lon, lat });
+        return Err(ClassifierError::InvalidCoordinate {
+            lon,
+            lat,
+            station_id: None,
+            row_index: None,
+        });
     }
 
     let mut x = [lon];
@@ -58,11 +276,90 @@ pub fn transform_coordinate(lon: f64, lat: f64, transform: &CoordTransform) -> R
         .transform_coords(&mut x, &mut y, &mut z)
         .map_err(|e| ClassifierError::CoordinateTransform {
             message: format!("Failed to transform coordinates ({}, {}): {}", lon, lat, e),
+            station_id: None,
+            row_index: None,
         })?;
 
     Ok((x[0], y[0]))
 }
 
+/// Transform many coordinates from WGS84 to the target coordinate system
+///
+/// Equivalent to calling `transform_coordinate` for each point, but makes a
+/// single PROJ call for the whole array, which dominates the cost of
+/// transforming large station lists.
+///
+/// # Arguments
+/// * `lons` - Longitudes in degrees (-180 to 180)
+/// * `lats` - Latitudes in degrees (-90 to 90), one per longitude
+/// * `transform` - The coordinate transformation to apply
+///
+/// # Returns
+/// Transformed (x, y) coordinates in the target system, in input order
+///
+/// # Errors
+/// Returns `InvalidParameter` if the slices differ in length and
+/// `InvalidCoordinate` for the first point outside the WGS84 range.
+#[cfg(feature = "gdal")]
+pub fn transform_coordinates(
+    lons: &[f64],
+    lats: &[f64],
+    transform: &CoordTransform,
+) -> Result<Vec<(f64, f64)>> {
+    if lons.len() != lats.len() {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!(
+                "{} longitudes but {} latitudes to transform",
+                lons.len(),
+                lats.len()
+            ),
+        });
+    }
+    if let Some((&lon, &lat)) = lons
+        .iter()
+        .zip(lats)
+        .find(|(lon, lat)| !(-180.0..=180.0).contains(*lon) || !(-90.0..=90.0).contains(*lat))
+    {
+        return Err(ClassifierError::InvalidCoordinate {
+            lon,
+            lat,
+            station_id: None,
+            row_index: None,
+        });
+    }
+    if lons.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut xs = lons.to_vec();
+    let mut ys = lats.to_vec();
+    let mut zs = vec![0.0; lons.len()];
+
+    transform
+        .transform_coords(&mut xs, &mut ys, &mut zs)
+        .map_err(|e| ClassifierError::CoordinateTransform {
+            message: format!("Failed to transform {} coordinates: {}", lons.len(), e),
+            station_id: None,
+            row_index: None,
+        })?;
+
+    Ok(xs.into_iter().zip(ys).collect())
+}
+
+/// Shift a longitude by whole turns into `[west, west + 360)`
+///
+/// Used to look up stations in geographic rasters whose extent crosses the
+/// antimeridian, e.g. a Fiji tile spanning 170°E to 190°E, where a station at
+/// -179° lies at 181° on the raster's grid.
+pub fn wrap_longitude(lon: f64, west: f64) -> f64 {
+    west + (lon - west).rem_euclid(360.0)
+}
+
+/// Normalize a longitude into `[-180, 180)`, so that 180° becomes -180°
+pub fn normalize_longitude(lon: f64) -> f64 {
+    wrap_longitude(lon, -180.0)
+}
+
 /// Convert geographic coordinates to pixel coordinates using the geotransform
 ///
 /// # Arguments
@@ -72,19 +369,196 @@ pub fn transform_coordinate(lon: f64, lat: f64, transform: &CoordTransform) -> R
 ///
 /// # Returns
 /// (pixel, line) coordinates in the raster
+///
+/// The geotransform must be invertible, as checked by `validate_geo_transform`.
+/// Coordinates are floored, so points left of or above the origin map to
+/// negative indices rather than to the first row or column.
 pub fn geo_to_pixel(x: f64, y: f64, geo_transform: &[f64; 6]) -> (isize, isize) {
+    geo_to_pixel_with(x, y, geo_transform, PixelRounding::Floor)
+}
+
+/// How fractional pixel coordinates are mapped to pixel indices
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PixelRounding {
+    /// Round down, selecting the pixel whose area contains the point. This is
+    /// correct for GDAL geotransforms, which refer to pixel corners.
+    #[default]
+    Floor,
+    /// Round to the nearest integer, for rasters whose geotransform refers to
+    /// pixel centres (GeoTIFF `PixelIsPoint`)
+    Nearest,
+}
+
+impl PixelRounding {
+    /// Apply the rounding to a fractional pixel coordinate
+    pub fn apply(&self, value: f64) -> isize {
+        match self {
+            PixelRounding::Floor => value.floor() as isize,
+            PixelRounding::Nearest => value.round() as isize,
+        }
+    }
+}
+
+/// Convert geographic coordinates to pixel indices with an explicit rounding
+/// strategy
+pub fn geo_to_pixel_with(
+    x: f64,
+    y: f64,
+    geo_transform: &[f64; 6],
+    rounding: PixelRounding,
+) -> (isize, isize) {
+    let (pixel, line) = geo_to_pixel_f(x, y, geo_transform);
+    (rounding.apply(pixel), rounding.apply(line))
+}
+
+/// Convert geographic coordinates to fractional pixel coordinates
+///
+/// The integer part is the pixel index and the fractional part the position
+/// within the pixel, so `(2.5, 3.5)` is the centre of pixel (2, 3).
+pub fn geo_to_pixel_f(x: f64, y: f64, geo_transform: &[f64; 6]) -> (f64, f64) {
     // Apply inverse affine transformation
     // geo_transform: [x_origin, pixel_width, x_skew, y_origin, y_skew, pixel_height]
-    // where pixel_height is typically negative
+    // where pixel_height is typically negative. For north-up rasters the skew
+    // terms are zero and this reduces to dividing by the pixel size.
+    let [x_origin, a, b, y_origin, d, e] = *geo_transform;
+    let determinant = a * e - b * d;
+    let (dx, dy) = (x - x_origin, y - y_origin);
 
-    let pixel = ((x - geo_transform[0]) / geo_transform[1]) as isize;
-    let line = ((y - geo_transform[3]) / geo_transform[5]) as isize;
+    let pixel = (e * dx - b * dy) / determinant;
+    let line = (a * dy - d * dx) / determinant;
 
     (pixel, line)
 }
 
+/// Convert the centre of a pixel to coordinates in the raster's coordinate system
+///
+/// This is the forward affine transformation, including rotation terms.
+pub fn pixel_center_to_geo(pixel: isize, line: isize, geo_transform: &[f64; 6]) -> (f64, f64) {
+    let col = pixel as f64 + 0.5;
+    let row = line as f64 + 0.5;
+
+    let x = geo_transform[0] + col * geo_transform[1] + row * geo_transform[2];
+    let y = geo_transform[3] + col * geo_transform[4] + row * geo_transform[5];
+
+    (x, y)
+}
+
+/// A WGS84 bounding box in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    pub min_lon: f64,
+    pub min_lat: f64,
+    pub max_lon: f64,
+    pub max_lat: f64,
+}
+
+impl BoundingBox {
+    /// Create a bounding box, validating coordinate ranges and ordering
+    pub fn new(min_lon: f64, min_lat: f64, max_lon: f64, max_lat: f64) -> Result<Self> {
+        for (lon, lat) in [(min_lon, min_lat), (max_lon, max_lat)] {
+            if !(-180.0..=180.0).contains(&lon) || !(-90.0..=90.0).contains(&lat) {
+                return Err(ClassifierError::InvalidCoordinate {
+                    lon,
+                    lat,
+                    station_id: None,
+                    row_index: None,
+                });
+            }
+        }
+        if min_lon > max_lon || min_lat > max_lat {
+            return Err(ClassifierError::InvalidParameter {
+                message: format!(
+                    "Bounding box minimum ({}, {}) exceeds maximum ({}, {})",
+                    min_lon, min_lat, max_lon, max_lat
+                ),
+            });
+        }
+
+        Ok(BoundingBox {
+            min_lon,
+            min_lat,
+            max_lon,
+            max_lat,
+        })
+    }
+
+    /// Check whether a WGS84 coordinate lies inside the box (edges inclusive)
+    pub fn contains(&self, lon: f64, lat: f64) -> bool {
+        (self.min_lon..=self.max_lon).contains(&lon) && (self.min_lat..=self.max_lat).contains(&lat)
+    }
+}
+
+/// Find the pixel window covering a WGS84 bounding box
+///
+/// The box edges are densified before transformation so that curved edges in
+/// projected coordinate systems are enclosed. The window is not clipped to the
+/// raster extent.
+///
+/// # Returns
+/// (pixel, line, width, height) of the covering window
+#[cfg(feature = "gdal")]
+pub fn bbox_pixel_window(
+    bbox: &BoundingBox,
+    transform: &CoordTransform,
+    geo_transform: &[f64; 6],
+) -> Result<(isize, isize, usize, usize)> {
+    bbox_window_with(bbox, |lon, lat| {
+        let (x, y) = transform_coordinate(lon, lat, transform)?;
+        Ok(geo_to_pixel(x, y, geo_transform))
+    })
+}
+
+/// Find the pixel window covering a bounding box, given a WGS84-to-pixel mapping
+pub(crate) fn bbox_window_with(
+    bbox: &BoundingBox,
+    locate: impl Fn(f64, f64) -> Result<(isize, isize)>,
+) -> Result<(isize, isize, usize, usize)> {
+    const EDGE_POINTS: usize = 21;
+
+    let mut min_pixel = isize::MAX;
+    let mut min_line = isize::MAX;
+    let mut max_pixel = isize::MIN;
+    let mut max_line = isize::MIN;
+
+    for i in 0..EDGE_POINTS {
+        let t = i as f64 / (EDGE_POINTS - 1) as f64;
+        let lon = bbox.min_lon + t * (bbox.max_lon - bbox.min_lon);
+        let lat = bbox.min_lat + t * (bbox.max_lat - bbox.min_lat);
+
+        for (lon, lat) in [
+            (lon, bbox.min_lat),
+            (lon, bbox.max_lat),
+            (bbox.min_lon, lat),
+            (bbox.max_lon, lat),
+        ] {
+            let (pixel, line) = locate(lon, lat)?;
+            min_pixel = min_pixel.min(pixel);
+            min_line = min_line.min(line);
+            max_pixel = max_pixel.max(pixel);
+            max_line = max_line.max(line);
+        }
+    }
+
+    Ok((
+        min_pixel,
+        min_line,
+        (max_pixel - min_pixel + 1) as usize,
+        (max_line - min_line + 1) as usize,
+    ))
+}
+
 /// Sample a single pixel value from a raster band
 ///
+/// The pixel is read in the band's own data type, so LCZ products stored as
+/// 16-bit, 32-bit or floating-point bands are sampled without GDAL clamping
+/// their values. The band's nodata value, such as the NaN nodata of
+/// floating-point bands, is returned as its byte value, or 0 if no byte can
+/// hold it.
+///
+/// # Errors
+/// Returns `ClassifierError::UnexpectedRasterValue` with the original value
+/// for any other value that is not an integer from 0 to 255.
+///
 /// # Arguments
 /// * `band` - The raster band to sample from
 /// * `pixel` - X coordinate in pixels
@@ -92,7 +566,70 @@ pub fn geo_to_pixel(x: f64, y: f64, geo_transform: &[f64; 6]) -> (isize, isize)
 ///
 /// # Returns
 /// The pixel value as a u8 (LCZ code)
+#[cfg(feature = "gdal")]
 pub fn sample_raster_value(band: &RasterBand, pixel: isize, line: isize) -> Result<u8> {
+    match band.band_type() {
+        GdalDataType::UInt8 => sample_raster_value_typed::<u8>(band, pixel, line),
+        GdalDataType::UInt16 => sample_code::<u16>(band, pixel, line),
+        GdalDataType::Int16 => sample_code::<i16>(band, pixel, line),
+        GdalDataType::UInt32 => sample_code::<u32>(band, pixel, line),
+        GdalDataType::Int32 => sample_code::<i32>(band, pixel, line),
+        GdalDataType::Float32 => sample_code::<f32>(band, pixel, line),
+        _ => sample_code::<f64>(band, pixel, line),
+    }
+}
+
+/// Sample a pixel in type `T` and convert it to a byte code
+#[cfg(feature = "gdal")]
+fn sample_code<T: GdalType + Copy + Default + Into<f64>>(
+    band: &RasterBand,
+    pixel: isize,
+    line: isize,
+) -> Result<u8> {
+    let value = sample_raster_value_typed::<T>(band, pixel, line)?;
+    value_to_code(value.into(), band.no_data_value())
+}
+
+/// Convert a value of a wider band type to a byte code
+///
+/// The band's nodata value maps to its byte value, or 0 if no byte can hold
+/// it; any other value must be an integer from 0 to 255.
+#[cfg(feature = "gdal")]
+fn value_to_code(value: f64, nodata: Option<f64>) -> Result<u8> {
+    match nodata {
+        Some(nodata) if value == nodata || (value.is_nan() && nodata.is_nan()) => {
+            Ok(nodata_to_u8(nodata).unwrap_or(0))
+        }
+        _ => nodata_to_u8(value).ok_or(ClassifierError::UnexpectedRasterValue {
+            value,
+            station_id: None,
+            row_index: None,
+        }),
+    }
+}
+
+/// Sample a single pixel value from a raster band as type `T`
+///
+/// GDAL converts the band's values to `T`, saturating values outside its
+/// range; pick `T` from `band.band_type()` to read them unchanged. Useful for
+/// non-LCZ rasters such as elevation or continuous land cover fractions.
+///
+/// # Examples
+/// ```no_run
+/// use gdal::Dataset;
+/// use urban_classifier::spatial::sample_raster_value_typed;
+///
+/// let dataset = Dataset::open("path/to/elevation.tif")?;
+/// let band = dataset.rasterband(1)?;
+/// let elevation: f32 = sample_raster_value_typed(&band, 1200, 800)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gdal")]
+pub fn sample_raster_value_typed<T: GdalType + Copy + Default>(
+    band: &RasterBand,
+    pixel: isize,
+    line: isize,
+) -> Result<T> {
     // Get raster dimensions
     let (raster_width, raster_height) = band.size();
 
@@ -105,31 +642,463 @@ pub fn sample_raster_value(band: &RasterBand, pixel: isize, line: isize) -> Resu
                 "Coordinates out of bounds. Raster size: {}x{}, requested: ({}, {})",
                 raster_width, raster_height, pixel, line
             ),
+            station_id: None,
+            row_index: None,
         });
     }
 
     // Read single pixel value
-    let mut buffer: [u8; 1] = [0];
+    let mut buffer = [T::default()];
     band.read_into_slice((pixel, line), (1, 1), (1, 1), &mut buffer, None)
         .map_err(|e| ClassifierError::RasterSampling {
             pixel,
             line,
             message: format!("Failed to read raster value: {}", e),
+            station_id: None,
+            row_index: None,
         })?;
 
     Ok(buffer[0])
 }
 
+/// Sample many pixels from a raster band, reading each raster block once
+///
+/// Requests are grouped by the band's natural block (tile or strip), so
+/// clustered pixels cost one decode per block instead of one per pixel.
+///
+/// # Arguments
+/// * `band` - The raster band to sample from
+/// * `pixels` - (pixel, line) positions to sample, in any order
+///
+/// # Returns
+/// The value at each position in input order, or `None` for positions
+/// outside the raster
+#[cfg(feature = "gdal")]
+pub fn sample_raster_values(
+    band: &RasterBand,
+    pixels: &[(isize, isize)],
+) -> Result<Vec<Option<u8>>> {
+    let (block_width, block_height) = band.block_size();
+    let block_size = (block_width.max(1), block_height.max(1));
+
+    let mut values = vec![None; pixels.len()];
+    for ((block_col, block_row), indices) in group_by_block(pixels, band.size(), block_size) {
+        let window = read_pixel_window(
+            band,
+            (block_col * block_size.0) as isize,
+            (block_row * block_size.1) as isize,
+            block_size.0,
+            block_size.1,
+        )?;
+        for index in indices {
+            let (pixel, line) = pixels[index];
+            values[index] = window.get(pixel, line);
+        }
+    }
+
+    Ok(values)
+}
+
+/// Group the indices of in-bounds pixels by the (column, row) of their block
+#[cfg(feature = "gdal")]
+fn group_by_block(
+    pixels: &[(isize, isize)],
+    raster_size: (usize, usize),
+    block_size: (usize, usize),
+) -> BTreeMap<(usize, usize), Vec<usize>> {
+    let mut blocks: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (index, &(pixel, line)) in pixels.iter().enumerate() {
+        let (Ok(col), Ok(row)) = (usize::try_from(pixel), usize::try_from(line)) else {
+            continue;
+        };
+        if col >= raster_size.0 || row >= raster_size.1 {
+            continue;
+        }
+        blocks
+            .entry((col / block_size.0, row / block_size.1))
+            .or_default()
+            .push(index);
+    }
+    blocks
+}
+
+/// Mean Earth radius in metres (IUGG), used for metric approximations
+pub(crate) const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// A rectangular block of raster pixels read from a band
+///
+/// `x_off` and `y_off` give the position of the top-left pixel in the full raster,
+/// and `data` holds `width * height` values in row-major order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PixelWindow {
+    pub x_off: isize,
+    pub y_off: isize,
+    pub width: usize,
+    pub height: usize,
+    pub data: Vec<u8>,
+}
+
+impl PixelWindow {
+    /// Get the value at an absolute (pixel, line) position, if it lies inside the window
+    pub fn get(&self, pixel: isize, line: isize) -> Option<u8> {
+        let col = pixel - self.x_off;
+        let row = line - self.y_off;
+        if col < 0 || row < 0 || col >= self.width as isize || row >= self.height as isize {
+            return None;
+        }
+        Some(self.data[row as usize * self.width + col as usize])
+    }
+}
+
+/// Read a window of pixels from a raster band, clipped to the raster extent
+///
+/// # Arguments
+/// * `band` - The raster band to read from
+/// * `pixel` - X coordinate of the requested top-left pixel (may be negative)
+/// * `line` - Y coordinate of the requested top-left pixel (may be negative)
+/// * `width` - Requested window width in pixels
+/// * `height` - Requested window height in pixels
+///
+/// # Returns
+/// The in-bounds part of the requested window. The window is empty when the
+/// request lies entirely outside the raster. Values are converted to bytes
+/// as by `sample_raster_value`, failing on the first value that cannot be.
+#[cfg(feature = "gdal")]
+pub fn read_pixel_window(
+    band: &RasterBand,
+    pixel: isize,
+    line: isize,
+    width: usize,
+    height: usize,
+) -> Result<PixelWindow> {
+    let (raster_width, raster_height) = band.size();
+
+    let x_start = pixel.max(0);
+    let y_start = line.max(0);
+    let x_end = (pixel + width as isize).min(raster_width as isize);
+    let y_end = (line + height as isize).min(raster_height as isize);
+
+    if x_end <= x_start || y_end <= y_start {
+        return Ok(PixelWindow {
+            x_off: x_start,
+            y_off: y_start,
+            width: 0,
+            height: 0,
+            data: Vec::new(),
+        });
+    }
+
+    let clipped = ((x_end - x_start) as usize, (y_end - y_start) as usize);
+    let offset = (x_start, y_start);
+    let data = match band.band_type() {
+        GdalDataType::UInt8 => band
+            .read_as::<u8>(offset, clipped, clipped, None)
+            .map(|buffer| buffer.data)
+            .map_err(|e| window_read_error(offset, clipped, e)),
+        GdalDataType::UInt16 => read_codes::<u16>(band, offset, clipped),
+        GdalDataType::Int16 => read_codes::<i16>(band, offset, clipped),
+        GdalDataType::UInt32 => read_codes::<u32>(band, offset, clipped),
+        GdalDataType::Int32 => read_codes::<i32>(band, offset, clipped),
+        GdalDataType::Float32 => read_codes::<f32>(band, offset, clipped),
+        _ => read_codes::<f64>(band, offset, clipped),
+    }?;
+
+    Ok(PixelWindow {
+        x_off: x_start,
+        y_off: y_start,
+        width: clipped.0,
+        height: clipped.1,
+        data,
+    })
+}
+
+/// Read a window in type `T` and convert each value to a byte code, as
+/// `sample_raster_value` does for single pixels
+#[cfg(feature = "gdal")]
+fn read_codes<T: GdalType + Copy + Into<f64>>(
+    band: &RasterBand,
+    offset: (isize, isize),
+    size: (usize, usize),
+) -> Result<Vec<u8>> {
+    let buffer = band
+        .read_as::<T>(offset, size, size, None)
+        .map_err(|e| window_read_error(offset, size, e))?;
+    let nodata = band.no_data_value();
+    buffer
+        .data
+        .into_iter()
+        .map(|value| value_to_code(value.into(), nodata))
+        .collect()
+}
+
+/// Wrap a failed window read in a sampling error at the window's origin
+#[cfg(feature = "gdal")]
+fn window_read_error(
+    (pixel, line): (isize, isize),
+    (width, height): (usize, usize),
+    e: gdal::errors::GdalError,
+) -> ClassifierError {
+    ClassifierError::RasterSampling {
+        pixel,
+        line,
+        message: format!("Failed to read {}x{} raster window: {}", width, height, e),
+        station_id: None,
+        row_index: None,
+    }
+}
+
+/// Read a window of pixels from a raster band into an array of the requested shape
+///
+/// Unlike `read_pixel_window`, the window is not clipped: pixels outside the
+/// raster extent are filled with the band's nodata value, or 0 where the band
+/// has none (or it does not fit in a byte), so the array always has the
+/// requested shape and position.
+///
+/// # Arguments
+/// * `band` - The raster band to read from
+/// * `pixel` - X coordinate of the top-left pixel (may be negative)
+/// * `line` - Y coordinate of the top-left pixel (may be negative)
+/// * `width` - Window width in pixels
+/// * `height` - Window height in pixels
+///
+/// # Returns
+/// Values indexed by [row, column], with `[0, 0]` at (`pixel`, `line`)
+#[cfg(feature = "gdal")]
+pub fn sample_raster_window(
+    band: &RasterBand,
+    pixel: isize,
+    line: isize,
+    width: usize,
+    height: usize,
+) -> Result<Array2<u8>> {
+    let window = read_pixel_window(band, pixel, line, width, height)?;
+    let fill = band.no_data_value().and_then(nodata_to_u8).unwrap_or(0);
+    Ok(embed_window(&window, pixel, line, width, height, fill))
+}
+
+/// Convert a band's nodata value to a byte, or `None` if no byte can hold it
+#[cfg(any(feature = "gdal", feature = "no-gdal"))]
+pub(crate) fn nodata_to_u8(nodata: f64) -> Option<u8> {
+    ((0.0..=255.0).contains(&nodata) && nodata.fract() == 0.0).then_some(nodata as u8)
+}
+
+/// Place a clipped window into a `height` x `width` array starting at
+/// (`pixel`, `line`), filling pixels the window does not cover with `fill`
+#[cfg(feature = "gdal")]
+fn embed_window(
+    window: &PixelWindow,
+    pixel: isize,
+    line: isize,
+    width: usize,
+    height: usize,
+    fill: u8,
+) -> Array2<u8> {
+    Array2::from_shape_fn((height, width), |(row, col)| {
+        window
+            .get(pixel + col as isize, line + row as isize)
+            .unwrap_or(fill)
+    })
+}
+
+/// Units of a raster CRS, as needed to convert metric distances to pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CrsUnits {
+    /// Angular units in degrees, as used by geographic CRSs
+    Degrees,
+    /// Linear units of the given length in metres, as used by projected CRSs
+    Linear(f64),
+}
+
+impl CrsUnits {
+    /// Linear units of one metre
+    pub const METRES: CrsUnits = CrsUnits::Linear(1.0);
+}
+
+/// Convert a radius in metres into pixel radii along the X and Y axes
+///
+/// For geographic rasters the pixel size is in degrees, so the metric size of a
+/// pixel is approximated on a sphere at the given latitude. For projected
+/// rasters the pixel size is scaled by the length of the CRS unit, so rasters
+/// in feet are handled. Pixel sizes are the lengths of the pixel edges, so
+/// rotated rasters are handled. The result is always at least zero pixels.
+///
+/// `RasterMetadata::buffer_radius_pixels` looks up the units of a raster's CRS.
+///
+/// # Examples
+/// ```
+/// use urban_classifier::spatial::{buffer_radius_pixels, CrsUnits};
+///
+/// // 100 m pixels
+/// let geo_transform = [0.0, 100.0, 0.0, 0.0, 0.0, -100.0];
+/// assert_eq!(buffer_radius_pixels(500.0, 51.5, &geo_transform, CrsUnits::METRES), (5, 5));
+/// ```
+pub fn buffer_radius_pixels(
+    radius_m: f64,
+    lat: f64,
+    geo_transform: &[f64; 6],
+    units: CrsUnits,
+) -> (usize, usize) {
+    let (pixel_width_m, pixel_height_m) = pixel_size_m(geo_transform, lat, units);
+
+    let rx = (radius_m / pixel_width_m).round().max(0.0) as usize;
+    let ry = (radius_m / pixel_height_m).round().max(0.0) as usize;
+
+    (rx, ry)
+}
+
+/// Lengths of the pixel edges in metres at latitude `lat`
+///
+/// Geographic pixel widths are approximated on a sphere, as in
+/// `buffer_radius_pixels`.
+pub fn pixel_size_m(geo_transform: &[f64; 6], lat: f64, units: CrsUnits) -> (f64, f64) {
+    let (pixel_width, pixel_height) = pixel_size(geo_transform);
+    match units {
+        CrsUnits::Degrees => {
+            let metres_per_degree = EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
+            let cos_lat = lat.to_radians().cos().max(1e-6);
+            (
+                pixel_width * metres_per_degree * cos_lat,
+                pixel_height * metres_per_degree,
+            )
+        }
+        CrsUnits::Linear(metres_per_unit) => (
+            pixel_width * metres_per_unit,
+            pixel_height * metres_per_unit,
+        ),
+    }
+}
+
+/// Area of one pixel in square metres, for a pixel centred at latitude `lat`
+///
+/// For geographic rasters a degree of longitude shrinks with the cosine of the
+/// latitude, so a pixel at 60° covers half the area of one at the equator.
+/// Projected rasters use the area on the map plane, scaled by the CRS unit.
+pub fn pixel_area_m2(geo_transform: &[f64; 6], lat: f64, units: CrsUnits) -> f64 {
+    let area = (geo_transform[1] * geo_transform[5] - geo_transform[2] * geo_transform[4]).abs();
+    match units {
+        CrsUnits::Degrees => {
+            let metres_per_degree = EARTH_RADIUS_M * std::f64::consts::PI / 180.0;
+            area * metres_per_degree * metres_per_degree * lat.to_radians().cos().max(0.0)
+        }
+        CrsUnits::Linear(metres_per_unit) => area * metres_per_unit * metres_per_unit,
+    }
+}
+
+/// Lengths of the pixel edges along the raster's X and Y axes, in CRS units
+///
+/// Equal to the absolute pixel width and height for north-up rasters.
+pub fn pixel_size(geo_transform: &[f64; 6]) -> (f64, f64) {
+    (
+        geo_transform[1].hypot(geo_transform[4]),
+        geo_transform[2].hypot(geo_transform[5]),
+    )
+}
+
+/// Semi-major axis of the WGS84 ellipsoid in metres
+const WGS84_A: f64 = 6_378_137.0;
+
+/// Flattening of the WGS84 ellipsoid
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// Great-circle distance between two WGS84 points in metres, on a sphere
+///
+/// Uses the mean Earth radius; accurate to about 0.5%, which is ample for
+/// station pairing and neighbourhood searches.
+pub fn haversine_distance_m(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let dlat = (lat2 - lat1).to_radians();
+    let dlon = (lon2 - lon1).to_radians();
+    let a = (dlat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (dlon / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_M * a.sqrt().min(1.0).asin()
+}
+
+/// Geodesic distance between two WGS84 points in metres, on the ellipsoid
+///
+/// Vincenty's inverse formula, accurate to well under a millimetre.
+///
+/// # Returns
+/// The distance, or `None` if the iteration does not converge, which only
+/// happens for nearly antipodal points; fall back to `haversine_distance_m`
+pub fn vincenty_distance_m(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> Option<f64> {
+    let b = WGS84_A * (1.0 - WGS84_F);
+    let l = (lon2 - lon1).to_radians();
+    let u1 = ((1.0 - WGS84_F) * lat1.to_radians().tan()).atan();
+    let u2 = ((1.0 - WGS84_F) * lat2.to_radians().tan()).atan();
+    let (sin_u1, cos_u1) = u1.sin_cos();
+    let (sin_u2, cos_u2) = u2.sin_cos();
+
+    let mut lambda = l;
+    for _ in 0..200 {
+        let (sin_lambda, cos_lambda) = lambda.sin_cos();
+        let sin_sigma = (cos_u2 * sin_lambda).hypot(cos_u1 * sin_u2 - sin_u1 * cos_u2 * cos_lambda);
+        if sin_sigma == 0.0 {
+            // Coincident points
+            return Some(0.0);
+        }
+        let cos_sigma = sin_u1 * sin_u2 + cos_u1 * cos_u2 * cos_lambda;
+        let sigma = sin_sigma.atan2(cos_sigma);
+        let sin_alpha = cos_u1 * cos_u2 * sin_lambda / sin_sigma;
+        let cos2_alpha = 1.0 - sin_alpha * sin_alpha;
+        // Zero on the equator, where cos2_alpha is zero
+        let cos_2sigma_m = if cos2_alpha == 0.0 {
+            0.0
+        } else {
+            cos_sigma - 2.0 * sin_u1 * sin_u2 / cos2_alpha
+        };
+        let c = WGS84_F / 16.0 * cos2_alpha * (4.0 + WGS84_F * (4.0 - 3.0 * cos2_alpha));
+        let previous = lambda;
+        lambda = l
+            + (1.0 - c)
+                * WGS84_F
+                * sin_alpha
+                * (sigma
+                    + c * sin_sigma
+                        * (cos_2sigma_m + c * cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))));
+
+        if (lambda - previous).abs() < 1e-12 {
+            let u_sq = cos2_alpha * (WGS84_A * WGS84_A - b * b) / (b * b);
+            let big_a =
+                1.0 + u_sq / 16384.0 * (4096.0 + u_sq * (-768.0 + u_sq * (320.0 - 175.0 * u_sq)));
+            let big_b = u_sq / 1024.0 * (256.0 + u_sq * (-128.0 + u_sq * (74.0 - 47.0 * u_sq)));
+            let delta_sigma = big_b
+                * sin_sigma
+                * (cos_2sigma_m
+                    + big_b / 4.0
+                        * (cos_sigma * (-1.0 + 2.0 * cos_2sigma_m.powi(2))
+                            - big_b / 6.0
+                                * cos_2sigma_m
+                                * (-3.0 + 4.0 * sin_sigma.powi(2))
+                                * (-3.0 + 4.0 * cos_2sigma_m.powi(2))));
+            return Some(b * big_a * (sigma - delta_sigma));
+        }
+    }
+    None
+}
+
+/// Initial bearing from the first WGS84 point towards the second, in degrees
+///
+/// Measured clockwise from north in `[0, 360)`, along the great circle on a
+/// sphere. Zero for coincident points.
+pub fn initial_bearing(lon1: f64, lat1: f64, lon2: f64, lat2: f64) -> f64 {
+    let (lat1, lat2) = (lat1.to_radians(), lat2.to_radians());
+    let dlon = (lon2 - lon1).to_radians();
+    let y = dlon.sin() * lat2.cos();
+    let x = lat1.cos() * lat2.sin() - lat1.sin() * lat2.cos() * dlon.cos();
+    y.atan2(x).to_degrees().rem_euclid(360.0)
+}
+
 /// Validate that a geotransform array contains reasonable values
 ///
 /// Checks for:
-/// - Non-zero pixel dimensions
+/// - Non-zero pixel area, so that the transformation can be inverted
 /// - Finite (not NaN or infinite) values
 pub fn validate_geo_transform(geo_transform: &[f64; 6]) -> Result<()> {
-    // Check that pixel width and height are non-zero
-    if geo_transform[1] == 0.0 || geo_transform[5] == 0.0 {
+    // Check that the pixels have non-zero area, i.e. the affine is invertible
+    let determinant = geo_transform[1] * geo_transform[5] - geo_transform[2] * geo_transform[4];
+    if determinant == 0.0 {
         return Err(ClassifierError::GdalError {
             message: "Invalid geotransform: zero pixel size".to_string(),
+            source: None,
         });
     }
 
@@ -141,6 +1110,7 @@ pub fn validate_geo_transform(geo_transform: &[f64; 6]) -> Result<()> {
                     "Invalid geotransform: non-finite value at index {}: {}",
                     i, value
                 ),
+                source: None,
             });
         }
     }
@@ -148,10 +1118,156 @@ pub fn validate_geo_transform(geo_transform: &[f64; 6]) -> Result<()> {
     Ok(())
 }
 
+/// Size in pixels that automatically chosen overviews shrink the raster to
+const OVERVIEW_TARGET_SIZE: usize = 256;
+
+/// Overview factors for a raster, as chosen by gdaladdo without explicit levels
+///
+/// Powers of two up to the first factor at which the larger raster dimension
+/// fits in 256 pixels. Empty for rasters that already fit.
+pub fn default_overview_levels(width: usize, height: usize) -> Vec<u32> {
+    let mut levels = Vec::new();
+    let mut factor = 1;
+    while width.max(height).div_ceil(factor) > OVERVIEW_TARGET_SIZE {
+        factor *= 2;
+        levels.push(factor as u32);
+    }
+    levels
+}
+
+/// Build overviews of the raster file at `path`, like `gdaladdo -r mode`
+///
+/// Overviews are resampled with the mode, so each overview pixel holds the
+/// most frequent class of the pixels it covers and coarse reads still return
+/// valid LCZ codes. GeoTIFFs store the overviews internally; formats that
+/// cannot be updated get an external `.ovr` file.
+///
+/// # Arguments
+/// * `path` - Raster file to add overviews to
+/// * `levels` - Decimation factors such as `[2, 4, 8, 16]`; an empty slice
+///   uses `default_overview_levels`
+///
+/// # Returns
+/// The factors of the overviews built
+///
+/// # Errors
+/// Returns `ClassifierError::InvalidParameter` for factors below 2,
+/// `ClassifierError::FileNotFound` if `path` does not exist, and a GDAL error
+/// if the overviews cannot be written.
+///
+/// # Examples
+/// ```no_run
+/// use urban_classifier::spatial::build_overviews;
+///
+/// let levels = build_overviews("path/to/wudapt.tif", &[])?;
+/// println!("Built overviews at factors {:?}", levels);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gdal")]
+pub fn build_overviews<P: AsRef<Path>>(path: P, levels: &[u32]) -> Result<Vec<u32>> {
+    if let Some(level) = levels.iter().find(|&&level| level < 2) {
+        return Err(ClassifierError::InvalidParameter {
+            message: format!("Overview factors must be at least 2, got {}", level),
+        });
+    }
+    let path = path.as_ref();
+    if !path.exists() {
+        return Err(ClassifierError::FileNotFound {
+            path: path.to_string_lossy().to_string(),
+        });
+    }
+
+    let options = DatasetOptions {
+        open_flags: GdalOpenFlags::GDAL_OF_RASTER | GdalOpenFlags::GDAL_OF_UPDATE,
+        ..Default::default()
+    };
+    let mut dataset = Dataset::open_ex(path, options)?;
+    let levels = if levels.is_empty() {
+        let (width, height) = dataset.raster_size();
+        default_overview_levels(width, height)
+    } else {
+        levels.to_vec()
+    };
+    if levels.is_empty() {
+        return Ok(levels);
+    }
+
+    let factors: Vec<i32> = levels.iter().map(|&level| level as i32).collect();
+    dataset.build_overviews("MODE", &factors, &[])?;
+    Ok(levels)
+}
+
+/// Creation options of Cloud-Optimized GeoTIFFs written by `convert_to_cog`
+#[cfg(feature = "gdal")]
+const COG_OPTIONS: [RasterCreationOption<'static>; 4] = [
+    RasterCreationOption {
+        key: "COMPRESS",
+        value: "DEFLATE",
+    },
+    RasterCreationOption {
+        key: "BLOCKSIZE",
+        value: "512",
+    },
+    RasterCreationOption {
+        key: "OVERVIEW_RESAMPLING",
+        value: "MODE",
+    },
+    RasterCreationOption {
+        key: "BIGTIFF",
+        value: "IF_SAFER",
+    },
+];
+
+/// Convert the raster file at `input` to a Cloud-Optimized GeoTIFF at `output`
+///
+/// The output is tiled in 512 x 512 blocks, DEFLATE-compressed and carries
+/// mode-resampled overviews down to a single block, with the layout that lets
+/// remote reads fetch individual blocks by HTTP range request. Existing
+/// overviews of `input` are not reused. Requires GDAL 3.2 or later.
+///
+/// # Errors
+/// Returns `ClassifierError::FileNotFound` if `input` does not exist and a
+/// GDAL error if it cannot be read or `output` cannot be written.
+///
+/// # Examples
+/// ```no_run
+/// use urban_classifier::spatial::convert_to_cog;
+/// use urban_classifier::UrbanClassifier;
+///
+/// convert_to_cog("path/to/lcz_generator.tif", "lcz_cog.tif")?;
+/// let classifier = UrbanClassifier::new("lcz_cog.tif")?.with_tile_cache(256);
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[cfg(feature = "gdal")]
+pub fn convert_to_cog<P: AsRef<Path>, Q: AsRef<Path>>(input: P, output: Q) -> Result<()> {
+    let input = input.as_ref();
+    if !input.exists() {
+        return Err(ClassifierError::FileNotFound {
+            path: input.to_string_lossy().to_string(),
+        });
+    }
+
+    let source = Dataset::open(input)?;
+    let driver = DriverManager::get_driver_by_name("COG")?;
+    source.create_copy(&driver, output.as_ref(), &COG_OPTIONS)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Test that transforms default to traditional GIS axis order
+    #[cfg(feature = "gdal")]
+    #[test]
+    fn test_axis_order_default() {
+        assert_eq!(AxisOrder::default(), AxisOrder::TraditionalGis);
+        assert_eq!(
+            AxisOrder::default().strategy(),
+            gdal_sys::OSRAxisMappingStrategy::OAMS_TRADITIONAL_GIS_ORDER
+        );
+    }
+
     /// Test validation of WGS84 coordinate bounds
     #[test]
     fn test_validate_coordinates() {
@@ -164,6 +1280,62 @@ mod tests {
         assert!(!(-90.0..=90.0).contains(&91.0));
     }
 
+    /// Test that batch transformation matches per-point transformation
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_transform_coordinates() {
+        let transform =
+            create_wgs84_to_raster_transform(&SpatialRef::from_epsg(3857).unwrap()).unwrap();
+        let lons = [-0.1278, 2.3522, 139.6917];
+        let lats = [51.5074, 48.8566, 35.6895];
+
+        let batch = transform_coordinates(&lons, &lats, &transform).unwrap();
+        for ((lon, lat), (x, y)) in lons.iter().zip(&lats).zip(&batch) {
+            let single = transform_coordinate(*lon, *lat, &transform).unwrap();
+            assert!((single.0 - x).abs() < 1e-6 && (single.1 - y).abs() < 1e-6);
+        }
+
+        assert!(transform_coordinates(&[], &[], &transform)
+            .unwrap()
+            .is_empty());
+        assert!(matches!(
+            transform_coordinates(&[0.0, 200.0], &[0.0, 0.0], &transform),
+            Err(ClassifierError::InvalidCoordinate { lon, .. }) if lon == 200.0
+        ));
+        assert!(matches!(
+            transform_coordinates(&[0.0], &[], &transform),
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+    }
+
+    /// Test that EPSG, WKT and PROJ specifications give the same transform
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_create_transform() {
+        let wgs84 = SpatialRef::from_epsg(4326).unwrap();
+        let bng = SpatialRef::from_epsg(27700).unwrap();
+        let specs = [
+            CrsSpec::Epsg(27700),
+            CrsSpec::Wkt(bng.to_wkt().unwrap()),
+            CrsSpec::Proj(bng.to_proj4().unwrap()),
+        ];
+
+        // Approximately Trafalgar Square in British National Grid
+        for spec in &specs {
+            let transform = create_transform(spec, &wgs84).unwrap();
+            let mut xs = [530_000.0];
+            let mut ys = [180_500.0];
+            transform_to_wgs84(&mut xs, &mut ys, &transform).unwrap();
+            assert!((xs[0] + 0.127).abs() < 0.01, "{:?}", spec);
+            assert!((ys[0] - 51.508).abs() < 0.01, "{:?}", spec);
+        }
+
+        assert_eq!(CrsSpec::from(&RasterCrs::Wgs84), CrsSpec::WGS84);
+        assert!(CrsSpec::Proj("+proj=nonsense".to_string())
+            .to_spatial_ref()
+            .is_err());
+    }
+
     /// Test conversion from geographic coordinates to pixel coordinates
     #[test]
     fn test_geo_to_pixel() {
@@ -179,6 +1351,187 @@ mod tests {
         let (pixel, line) = geo_to_pixel(105.0, 195.0, &geo_transform);
         assert_eq!(pixel, 5);
         assert_eq!(line, 5);
+
+        // Points just outside the origin must not truncate into pixel 0
+        let (pixel, line) = geo_to_pixel(99.5, 200.5, &geo_transform);
+        assert_eq!(pixel, -1);
+        assert_eq!(line, -1);
+    }
+
+    /// Test fractional pixel coordinates and both rounding strategies
+    #[test]
+    fn test_geo_to_pixel_rounding() {
+        let geo_transform = [100.0, 0.5, 0.0, 200.0, 0.0, -0.5];
+
+        assert_eq!(geo_to_pixel_f(101.25, 198.75, &geo_transform), (2.5, 2.5));
+
+        let (x, y) = (101.4, 199.6);
+        let (pixel, line) = geo_to_pixel_f(x, y, &geo_transform);
+        assert!((pixel - 2.8).abs() < 1e-9);
+        assert!((line - 0.8).abs() < 1e-9);
+        assert_eq!(
+            geo_to_pixel_with(x, y, &geo_transform, PixelRounding::Floor),
+            (2, 0)
+        );
+        assert_eq!(
+            geo_to_pixel_with(x, y, &geo_transform, PixelRounding::Nearest),
+            (3, 1)
+        );
+        assert_eq!(
+            geo_to_pixel_with(99.9, 200.1, &geo_transform, PixelRounding::Nearest),
+            (0, 0)
+        );
+        assert_eq!(PixelRounding::default(), PixelRounding::Floor);
+    }
+
+    /// Test absolute lookups into a pixel window
+    #[test]
+    fn test_pixel_window_get() {
+        let window = PixelWindow {
+            x_off: 10,
+            y_off: 20,
+            width: 2,
+            height: 2,
+            data: vec![1, 2, 3, 4],
+        };
+
+        assert_eq!(window.get(10, 20), Some(1));
+        assert_eq!(window.get(11, 21), Some(4));
+        assert_eq!(window.get(9, 20), None);
+        assert_eq!(window.get(12, 20), None);
+    }
+
+    /// Test grouping of sample positions by raster block
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_group_by_block() {
+        let pixels = [(0, 0), (300, 10), (5, 280), (1, 1), (-1, 0), (400, 0)];
+        let blocks = group_by_block(&pixels, (400, 300), (256, 256));
+
+        assert_eq!(blocks.len(), 3);
+        assert_eq!(blocks[&(0, 0)], vec![0, 3]);
+        assert_eq!(blocks[&(1, 0)], vec![1]);
+        assert_eq!(blocks[&(0, 1)], vec![2]);
+    }
+
+    /// Test that partially outside windows keep their shape and are filled
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_embed_window() {
+        let window = PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width: 2,
+            height: 2,
+            data: vec![1, 2, 3, 4],
+        };
+        let array = embed_window(&window, -1, 1, 3, 2, 0);
+        assert_eq!(array, ndarray::array![[0, 3, 4], [0, 0, 0]]);
+    }
+
+    /// Test metric buffer conversion for projected and geographic rasters
+    #[test]
+    fn test_buffer_radius_pixels() {
+        // 100 m pixels in a projected CRS
+        let projected = [0.0, 100.0, 0.0, 0.0, 0.0, -100.0];
+        assert_eq!(
+            buffer_radius_pixels(500.0, 51.5, &projected, CrsUnits::METRES),
+            (5, 5)
+        );
+
+        // 100 ft pixels cover 30.48 m each
+        assert_eq!(
+            buffer_radius_pixels(304.8, 40.0, &projected, CrsUnits::Linear(0.3048)),
+            (10, 10)
+        );
+
+        // ~100 m pixels at the equator in a geographic CRS
+        let geographic = [0.0, 0.0009, 0.0, 0.0, 0.0, -0.0009];
+        assert_eq!(
+            buffer_radius_pixels(1000.0, 0.0, &geographic, CrsUnits::Degrees),
+            (10, 10)
+        );
+
+        // Pixels narrow towards the poles, so more columns cover the same distance
+        let (rx, ry) = buffer_radius_pixels(1000.0, 60.0, &geographic, CrsUnits::Degrees);
+        assert_eq!(ry, 10);
+        assert_eq!(rx, 20);
+    }
+
+    /// Test pixel areas on projected and geographic grids
+    #[test]
+    fn test_pixel_area_m2() {
+        let projected = [0.0, 100.0, 0.0, 0.0, 0.0, -100.0];
+        assert_eq!(pixel_area_m2(&projected, 51.5, CrsUnits::METRES), 10_000.0);
+        assert!((pixel_area_m2(&projected, 0.0, CrsUnits::Linear(0.3048)) - 929.0304).abs() < 1e-9);
+
+        let geographic = [0.0, 0.01, 0.0, 0.0, 0.0, -0.01];
+        let equator = pixel_area_m2(&geographic, 0.0, CrsUnits::Degrees);
+        assert!((equator - 1_236_000.0).abs() < 1_000.0);
+        let sixty = pixel_area_m2(&geographic, 60.0, CrsUnits::Degrees);
+        assert!((sixty / equator - 0.5).abs() < 1e-9);
+    }
+
+    /// Test spherical and ellipsoidal distances and bearings
+    #[test]
+    fn test_great_circle() {
+        // One degree of longitude at the equator is ~111.2 km on the sphere
+        let haversine = haversine_distance_m(0.0, 0.0, 1.0, 0.0);
+        assert!((haversine - 111_195.0).abs() < 10.0);
+        assert_eq!(haversine_distance_m(2.0, 48.0, 2.0, 48.0), 0.0);
+
+        // ...and 111.32 km on the ellipsoid
+        let vincenty = vincenty_distance_m(0.0, 0.0, 1.0, 0.0).unwrap();
+        assert!((vincenty - 111_319.49).abs() < 0.01);
+        assert_eq!(vincenty_distance_m(2.0, 48.0, 2.0, 48.0), Some(0.0));
+
+        // London to Paris, 343.9 km, within 0.5% on the sphere
+        let (london, paris) = ((-0.1278, 51.5074), (2.3522, 48.8566));
+        let geodesic = vincenty_distance_m(london.0, london.1, paris.0, paris.1).unwrap();
+        let spherical = haversine_distance_m(london.0, london.1, paris.0, paris.1);
+        assert!((geodesic - 343_900.0).abs() < 1_000.0);
+        assert!((spherical / geodesic - 1.0).abs() < 0.005);
+
+        for (to, expected) in [((0.0, 1.0), 0.0), ((1.0, 0.0), 90.0), ((0.0, -1.0), 180.0)] {
+            assert!((initial_bearing(0.0, 0.0, to.0, to.1) - expected).abs() < 1e-9);
+        }
+        assert!((initial_bearing(1.0, 0.0, 0.0, 0.0) - 270.0).abs() < 1e-9);
+        assert!((initial_bearing(london.0, london.1, paris.0, paris.1) - 148.1).abs() < 0.5);
+    }
+
+    /// Test wrapping longitudes across the antimeridian
+    #[test]
+    fn test_wrap_longitude() {
+        assert_eq!(normalize_longitude(180.0), -180.0);
+        assert_eq!(normalize_longitude(190.0), -170.0);
+        assert_eq!(normalize_longitude(-190.0), 170.0);
+        assert_eq!(normalize_longitude(-0.5), -0.5);
+        assert_eq!(wrap_longitude(-179.0, 170.0), 181.0);
+        assert_eq!(wrap_longitude(175.0, 170.0), 175.0);
+        assert_eq!(wrap_longitude(-10.0, 0.0), 350.0);
+    }
+
+    /// Test pixel centre coordinates for north-up and rotated grids
+    #[test]
+    fn test_pixel_center_to_geo() {
+        let geo_transform = [100.0, 1.0, 0.0, 200.0, 0.0, -1.0];
+        assert_eq!(pixel_center_to_geo(0, 0, &geo_transform), (100.5, 199.5));
+        assert_eq!(pixel_center_to_geo(5, 5, &geo_transform), (105.5, 194.5));
+
+        let rotated = [0.0, 1.0, 0.5, 0.0, 0.5, -1.0];
+        assert_eq!(pixel_center_to_geo(1, 1, &rotated), (2.25, -0.75));
+    }
+
+    /// Test bounding box validation and containment
+    #[test]
+    fn test_bounding_box() {
+        let bbox = BoundingBox::new(-1.0, 51.0, 1.0, 52.0).unwrap();
+        assert!(bbox.contains(0.0, 51.5));
+        assert!(bbox.contains(1.0, 52.0));
+        assert!(!bbox.contains(1.5, 51.5));
+
+        assert!(BoundingBox::new(1.0, 51.0, -1.0, 52.0).is_err());
+        assert!(BoundingBox::new(-1.0, 51.0, 1.0, 95.0).is_err());
     }
 
     /// Test validation of geotransform arrays
@@ -195,5 +1548,146 @@ mod tests {
         // Invalid transform with NaN
         let nan_transform = [100.0, f64::NAN, 0.0, 200.0, 0.0, -1.0];
         assert!(validate_geo_transform(&nan_transform).is_err());
+
+        // A raster rotated by 90 degrees has zero width and height terms
+        let rotated_transform = [100.0, 0.0, 1.0, 200.0, 1.0, 0.0];
+        assert!(validate_geo_transform(&rotated_transform).is_ok());
+
+        // Collinear pixel edges cannot be inverted
+        let degenerate_transform = [100.0, 1.0, 2.0, 200.0, 1.0, 2.0];
+        assert!(validate_geo_transform(&degenerate_transform).is_err());
+    }
+
+    /// Test that geo_to_pixel inverts rotated and skewed geotransforms
+    #[test]
+    fn test_geo_to_pixel_rotated() {
+        let angle = 30f64.to_radians();
+        let (size, origin) = (10.0, (1000.0, 5000.0));
+        let transforms = [
+            // Rotated by 30 degrees
+            [
+                origin.0,
+                size * angle.cos(),
+                size * angle.sin(),
+                origin.1,
+                size * angle.sin(),
+                -size * angle.cos(),
+            ],
+            // Rotated by 90 degrees
+            [origin.0, 0.0, size, origin.1, size, 0.0],
+            // Sheared
+            [origin.0, size, 3.0, origin.1, 0.0, -size],
+        ];
+
+        for geo_transform in &transforms {
+            for (pixel, line) in [(0, 0), (7, 3), (120, 45)] {
+                let (x, y) = pixel_center_to_geo(pixel, line, geo_transform);
+                assert_eq!(geo_to_pixel(x, y, geo_transform), (pixel, line));
+            }
+            let (width, height) = pixel_size(geo_transform);
+            assert!((width - size).abs() < 1e-9 || (height - size).abs() < 1e-9);
+        }
+        assert_eq!(pixel_size(&[0.0, 2.0, 0.0, 0.0, 0.0, -3.0]), (2.0, 3.0));
+    }
+
+    /// Test automatic overview factors and building mode overviews
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_build_overviews() {
+        assert_eq!(default_overview_levels(200, 100), Vec::<u32>::new());
+        assert_eq!(default_overview_levels(1000, 300), vec![2, 4]);
+        assert_eq!(default_overview_levels(43200, 21600).last(), Some(&256));
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lcz.tif");
+        let driver = gdal::DriverManager::get_driver_by_name("GTiff").unwrap();
+        driver
+            .create_with_band_type::<u8, _>(&path, 600, 400, 1)
+            .unwrap();
+
+        assert!(matches!(
+            build_overviews(&path, &[2, 1]),
+            Err(ClassifierError::InvalidParameter { .. })
+        ));
+        assert_eq!(build_overviews(&path, &[]).unwrap(), vec![2, 4]);
+        let dataset = Dataset::open(&path).unwrap();
+        assert_eq!(dataset.rasterband(1).unwrap().overview_count().unwrap(), 2);
+    }
+
+    /// Test that converted files are tiled COGs with overviews
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_convert_to_cog() {
+        use gdal::Metadata;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (input, output) = (dir.path().join("lcz.tif"), dir.path().join("cog.tif"));
+        let driver = DriverManager::get_driver_by_name("GTiff").unwrap();
+        driver
+            .create_with_band_type::<u8, _>(&input, 1200, 600, 1)
+            .unwrap();
+
+        assert!(matches!(
+            convert_to_cog(dir.path().join("missing.tif"), &output),
+            Err(ClassifierError::FileNotFound { .. })
+        ));
+        convert_to_cog(&input, &output).unwrap();
+
+        let cog = Dataset::open(&output).unwrap();
+        assert_eq!(
+            cog.metadata_item("LAYOUT", "IMAGE_STRUCTURE").as_deref(),
+            Some("COG")
+        );
+        let band = cog.rasterband(1).unwrap();
+        assert_eq!(band.block_size(), (512, 512));
+        assert!(band.overview_count().unwrap() >= 1);
+    }
+
+    /// Test that bands are sampled in their own data type
+    #[test]
+    #[cfg(feature = "gdal")]
+    fn test_sample_typed_bands() {
+        use gdal::raster::Buffer;
+
+        let driver = gdal::DriverManager::get_driver_by_name("MEM").unwrap();
+        let dataset = driver.create_with_band_type::<u16, _>("", 3, 1, 1).unwrap();
+        let mut band = dataset.rasterband(1).unwrap();
+        band.write((0, 0), (3, 1), &Buffer::new((3, 1), vec![5u16, 300, 1017]))
+            .unwrap();
+        assert_eq!(sample_raster_value_typed::<u16>(&band, 1, 0).unwrap(), 300);
+        assert_eq!(sample_raster_value(&band, 0, 0).unwrap(), 5);
+        assert!(matches!(
+            sample_raster_value(&band, 1, 0),
+            Err(ClassifierError::UnexpectedRasterValue { value, .. }) if value == 300.0
+        ));
+
+        let dataset = driver.create_with_band_type::<f32, _>("", 3, 1, 1).unwrap();
+        let mut band = dataset.rasterband(1).unwrap();
+        band.write(
+            (0, 0),
+            (3, 1),
+            &Buffer::new((3, 1), vec![14.0f32, f32::NAN, 2.5]),
+        )
+        .unwrap();
+        assert_eq!(sample_raster_value(&band, 0, 0).unwrap(), 14);
+        assert!(matches!(
+            sample_raster_value(&band, 1, 0),
+            Err(ClassifierError::UnexpectedRasterValue { value, .. }) if value.is_nan()
+        ));
+        assert!(matches!(
+            sample_raster_value(&band, 2, 0),
+            Err(ClassifierError::UnexpectedRasterValue { value, .. }) if value == 2.5
+        ));
+        assert!(sample_raster_value_typed::<f32>(&band, 3, 0).is_err());
+
+        // The band's nodata value is not an unexpected value
+        band.set_no_data_value(Some(f64::NAN)).unwrap();
+        assert_eq!(sample_raster_value(&band, 1, 0).unwrap(), 0);
+        let window = read_pixel_window(&band, 0, 0, 2, 1).unwrap();
+        assert_eq!(window.data, vec![14, 0]);
+        assert!(matches!(
+            read_pixel_window(&band, 0, 0, 3, 1),
+            Err(ClassifierError::UnexpectedRasterValue { value, .. }) if value == 2.5
+        ));
     }
 }
@@ -0,0 +1,123 @@
+//! Positional Stability of Classifications
+//!
+//! Station metadata is often only precise to a few hundred metres. This module
+//! supports estimating how sensitive a station's class is to that uncertainty by
+//! re-sampling the raster at perturbed positions around the nominal coordinate
+//! and measuring how often the class agrees with the nominal one.
+//!
+//! # Perturbation Pattern
+//!
+//! Offsets follow a Vogel (sunflower) spiral, which spreads points evenly over a
+//! disc of the configured radius. The pattern is deterministic, so repeated runs
+//! give identical stability scores.
+
+use crate::spatial::EARTH_RADIUS_M;
+
+/// Golden angle in radians, used to space spiral points
+const GOLDEN_ANGLE: f64 = 2.399_963_229_728_653;
+
+/// Configuration for positional stability scoring
+#[derive(Debug, Clone, PartialEq)]
+pub struct StabilityConfig {
+    /// Maximum perturbation distance in metres
+    pub radius_m: f64,
+    /// Number of perturbed positions sampled per station
+    pub samples: usize,
+    /// Stations agreeing less often than this fraction are flagged as unstable
+    pub min_agreement: f64,
+}
+
+impl Default for StabilityConfig {
+    fn default() -> Self {
+        StabilityConfig {
+            radius_m: 150.0,
+            samples: 20,
+            min_agreement: 0.8,
+        }
+    }
+}
+
+/// Generate evenly spread (east, north) offsets in metres within a disc
+pub fn jitter_offsets(samples: usize, radius_m: f64) -> Vec<(f64, f64)> {
+    (0..samples)
+        .map(|k| {
+            let r = radius_m * ((k as f64 + 0.5) / samples as f64).sqrt();
+            let theta = k as f64 * GOLDEN_ANGLE;
+            (r * theta.cos(), r * theta.sin())
+        })
+        .collect()
+}
+
+/// Move a WGS84 coordinate by an (east, north) offset in metres
+///
+/// Uses a local spherical approximation, which is accurate for offsets much
+/// smaller than the Earth's radius.
+pub fn offset_lonlat(lon: f64, lat: f64, east_m: f64, north_m: f64) -> (f64, f64) {
+    let dlat = (north_m / EARTH_RADIUS_M).to_degrees();
+    let cos_lat = lat.to_radians().cos().max(1e-6);
+    let dlon = (east_m / (EARTH_RADIUS_M * cos_lat)).to_degrees();
+
+    let mut new_lon = lon + dlon;
+    if new_lon > 180.0 {
+        new_lon -= 360.0;
+    } else if new_lon < -180.0 {
+        new_lon += 360.0;
+    }
+
+    (new_lon, lat + dlat)
+}
+
+/// Fraction of perturbed samples whose class matches the nominal class
+///
+/// Failed samples (`None`, e.g. outside the raster) count as disagreements.
+/// Returns `None` when there are no samples.
+pub fn agreement_fraction(nominal: u8, samples: &[Option<u8>]) -> Option<f64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let agreeing = samples.iter().filter(|&&s| s == Some(nominal)).count();
+    Some(agreeing as f64 / samples.len() as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that all offsets lie within the requested radius
+    #[test]
+    fn test_jitter_offsets_within_radius() {
+        let offsets = jitter_offsets(50, 150.0);
+        assert_eq!(offsets.len(), 50);
+
+        for (east, north) in &offsets {
+            assert!((east * east + north * north).sqrt() <= 150.0);
+        }
+
+        // Deterministic pattern
+        assert_eq!(offsets, jitter_offsets(50, 150.0));
+    }
+
+    /// Test metric offsets against known degree distances
+    #[test]
+    fn test_offset_lonlat() {
+        let (lon, lat) = offset_lonlat(0.0, 0.0, 0.0, 111_195.08);
+        assert!(lon.abs() < 1e-12);
+        assert!((lat - 1.0).abs() < 1e-6);
+
+        // A degree of longitude is half as long at 60°N
+        let (lon, _) = offset_lonlat(0.0, 60.0, 55_597.54, 0.0);
+        assert!((lon - 1.0).abs() < 1e-6);
+
+        // Offsets across the antimeridian wrap around
+        let (lon, _) = offset_lonlat(179.9999, 0.0, 1000.0, 0.0);
+        assert!(lon < -179.0);
+    }
+
+    /// Test agreement fraction with failed samples
+    #[test]
+    fn test_agreement_fraction() {
+        let samples = [Some(6), Some(6), Some(9), None];
+        assert_eq!(agreement_fraction(6, &samples), Some(0.5));
+        assert_eq!(agreement_fraction(6, &[]), None);
+    }
+}
@@ -0,0 +1,332 @@
+//! Test Utilities (`test-utils` feature)
+//!
+//! Lets downstream crates unit-test pipelines built on `UrbanClassifier`
+//! without shipping GeoTIFF fixtures. Both helpers produce real classifiers
+//! through `UrbanClassifier::from_source`, so every code path after raster
+//! access (column handling, overrides, validation modes, composition) is the
+//! one used in production. Neither calls into GDAL, and the feature does not
+//! need the `gdal` feature, so tests can run with
+//! `--no-default-features --features test-utils` on machines without libgdal.
+//!
+//! # Helpers
+//!
+//! - `MockClassifier`: A global WGS84 grid whose LCZ is fixed or computed from
+//!   each pixel's centre coordinate
+//! - `InMemorySource`: A small explicit raster held in an `ndarray::Array2`
+//!
+//! # Examples
+//! ```
+//! use urban_classifier::test_utils::MockClassifier;
+//! use urban_classifier::Lcz;
+//!
+//! // Everything west of the prime meridian is compact mid-rise
+//! let classifier = MockClassifier::from_fn(|lon, _lat| {
+//!     if lon < 0.0 { Lcz::CompactMidRise } else { Lcz::LowPlants }
+//! })
+//! .build()?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::classifier::UrbanClassifier;
+use crate::error::{ClassifierError, Result};
+use crate::lcz::Lcz;
+use crate::preload::PreloadedBand;
+use crate::source::{RasterCrs, RasterMetadata, RasterSource};
+use crate::spatial::{pixel_center_to_geo, PixelWindow};
+use ndarray::Array2;
+
+/// Default pixel size of a mock raster in degrees, close to WUDAPT's 100 m
+const DEFAULT_RESOLUTION: f64 = 0.001;
+
+/// Block size reported to the tile cache
+const MOCK_BLOCK_SIZE: (usize, usize) = (256, 256);
+
+type LczFn = dyn Fn(f64, f64) -> Lcz + Send + Sync;
+
+/// Global WGS84 raster with a fixed or function-defined LCZ per coordinate
+///
+/// Values are computed on demand, so no raster is held in memory.
+/// `Lcz::Unknown(code)` produces the raw value `code`, which is useful for
+/// exercising nodata mappings and validation modes.
+pub struct MockClassifier {
+    lcz_at: Box<LczFn>,
+    resolution: f64,
+}
+
+impl MockClassifier {
+    /// Classify every coordinate as the same LCZ
+    pub fn constant(lcz: Lcz) -> Self {
+        Self::from_fn(move |_, _| lcz)
+    }
+
+    /// Classify each pixel by calling `lcz_at(lon, lat)` at its centre
+    pub fn from_fn<F>(lcz_at: F) -> Self
+    where
+        F: Fn(f64, f64) -> Lcz + Send + Sync + 'static,
+    {
+        MockClassifier {
+            lcz_at: Box::new(lcz_at),
+            resolution: DEFAULT_RESOLUTION,
+        }
+    }
+
+    /// Set the pixel size in degrees, which controls buffer sizes in pixels
+    pub fn with_resolution(mut self, degrees: f64) -> Self {
+        self.resolution = degrees;
+        self
+    }
+
+    /// Create a classifier sampling this mock raster
+    ///
+    /// # Errors
+    /// Returns `ClassifierError::InvalidParameter` if the resolution is not a
+    /// positive number of degrees dividing the globe into at least one pixel.
+    pub fn build(self) -> Result<UrbanClassifier> {
+        if !(self.resolution > 0.0 && self.resolution <= 180.0) {
+            return Err(ClassifierError::InvalidParameter {
+                message: format!(
+                    "Mock raster resolution must be in (0, 180] degrees, got {}",
+                    self.resolution
+                ),
+            });
+        }
+        UrbanClassifier::from_source(self)
+    }
+
+    fn value_at(&self, pixel: isize, line: isize, geo_transform: &[f64; 6]) -> u8 {
+        let (lon, lat) = pixel_center_to_geo(pixel, line, geo_transform);
+        (self.lcz_at)(lon, lat).to_code()
+    }
+}
+
+impl RasterSource for MockClassifier {
+    fn metadata(&self) -> Result<RasterMetadata> {
+        Ok(RasterMetadata {
+            width: (360.0 / self.resolution).round() as usize,
+            height: (180.0 / self.resolution).round() as usize,
+            geo_transform: [-180.0, self.resolution, 0.0, 90.0, 0.0, -self.resolution],
+            crs: RasterCrs::Wgs84,
+            block_size: MOCK_BLOCK_SIZE,
+            nodata: None,
+        })
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> Result<u8> {
+        let metadata = self.metadata()?;
+        if pixel < 0
+            || line < 0
+            || pixel >= metadata.width as isize
+            || line >= metadata.height as isize
+        {
+            return Err(ClassifierError::RasterSampling {
+                pixel,
+                line,
+                message: "Coordinates out of bounds of the mock raster".to_string(),
+                station_id: None,
+                row_index: None,
+            });
+        }
+        Ok(self.value_at(pixel, line, &metadata.geo_transform))
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        let metadata = self.metadata()?;
+        let x_start = pixel.max(0);
+        let y_start = line.max(0);
+        let x_end = (pixel + width as isize)
+            .min(metadata.width as isize)
+            .max(x_start);
+        let y_end = (line + height as isize)
+            .min(metadata.height as isize)
+            .max(y_start);
+
+        let mut data = Vec::with_capacity(((x_end - x_start) * (y_end - y_start)) as usize);
+        for y in y_start..y_end {
+            for x in x_start..x_end {
+                data.push(self.value_at(x, y, &metadata.geo_transform));
+            }
+        }
+
+        Ok(PixelWindow {
+            x_off: x_start,
+            y_off: y_start,
+            width: (x_end - x_start) as usize,
+            height: (y_end - y_start) as usize,
+            data,
+        })
+    }
+}
+
+/// Explicit raster of raw LCZ values held in memory
+///
+/// # Examples
+/// ```
+/// use ndarray::array;
+/// use urban_classifier::test_utils::InMemorySource;
+/// use urban_classifier::UrbanClassifier;
+///
+/// // 2x2 pixels of 1 degree with the top-left corner at (-1, 52)
+/// let source = InMemorySource::new(array![[2, 6], [9, 14]], [-1.0, 1.0, 0.0, 52.0, 0.0, -1.0]);
+/// let classifier = UrbanClassifier::from_source(source)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub struct InMemorySource {
+    band: PreloadedBand,
+    metadata: RasterMetadata,
+}
+
+impl InMemorySource {
+    /// Wrap values indexed by [row, column] with a WGS84 geotransform
+    pub fn new(data: Array2<u8>, geo_transform: [f64; 6]) -> Self {
+        let (height, width) = data.dim();
+        let window = PixelWindow {
+            x_off: 0,
+            y_off: 0,
+            width,
+            height,
+            data: data.iter().copied().collect(),
+        };
+
+        InMemorySource {
+            band: PreloadedBand::from_window(window, (width, height))
+                .expect("window shape matches the array it was built from"),
+            metadata: RasterMetadata {
+                width,
+                height,
+                geo_transform,
+                crs: RasterCrs::Wgs84,
+                block_size: (width.max(1), 1),
+                nodata: None,
+            },
+        }
+    }
+
+    /// Interpret the geotransform in another CRS
+    pub fn with_crs(mut self, crs: RasterCrs) -> Self {
+        self.metadata.crs = crs;
+        self
+    }
+
+    /// Mark a raw value as nodata in the source's metadata
+    pub fn with_nodata(mut self, value: u8) -> Self {
+        self.metadata.nodata = Some(value);
+        self
+    }
+}
+
+impl RasterSource for InMemorySource {
+    fn metadata(&self) -> Result<RasterMetadata> {
+        Ok(self.metadata.clone())
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> Result<u8> {
+        self.band
+            .get(pixel, line)
+            .ok_or_else(|| ClassifierError::RasterSampling {
+                pixel,
+                line,
+                message: format!(
+                    "Coordinates out of bounds. Raster size: {}x{}, requested: ({}, {})",
+                    self.metadata.width, self.metadata.height, pixel, line
+                ),
+                station_id: None,
+                row_index: None,
+            })
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> Result<PixelWindow> {
+        // The whole raster is held, so every clipped request can be served
+        Ok(self
+            .band
+            .window(pixel, line, width, height)
+            .expect("in-memory source holds the full raster"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ndarray::array;
+    use polars::prelude::*;
+
+    fn stations() -> DataFrame {
+        df! {
+            "station_id" => ["W", "E"],
+            "longitude" => [-0.5, 0.5],
+            "latitude" => [51.5, 51.5],
+        }
+        .unwrap()
+    }
+
+    /// Test that a function-defined mock classifies by coordinate
+    #[test]
+    fn test_mock_from_fn() {
+        let classifier = MockClassifier::from_fn(|lon, _| {
+            if lon < 0.0 {
+                Lcz::CompactMidRise
+            } else {
+                Lcz::LowPlants
+            }
+        })
+        .build()
+        .unwrap();
+
+        let result = classifier
+            .run_classification(&stations(), "station_id", "longitude", "latitude", None)
+            .unwrap();
+        let codes: Vec<Option<u32>> = result
+            .column("lcz_code")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(codes, vec![Some(2), Some(14)]);
+    }
+
+    /// Test that invalid mock resolutions are rejected
+    #[test]
+    fn test_mock_resolution() {
+        assert!(MockClassifier::constant(Lcz::Water)
+            .with_resolution(0.0)
+            .build()
+            .is_err());
+
+        let source = MockClassifier::constant(Lcz::Water).with_resolution(1.0);
+        let metadata = source.metadata().unwrap();
+        assert_eq!((metadata.width, metadata.height), (360, 180));
+        assert_eq!(source.sample(0, 0).unwrap(), 17);
+    }
+
+    /// Test sampling and clipped windows of an in-memory source
+    #[test]
+    fn test_in_memory_source() {
+        let source =
+            InMemorySource::new(array![[2, 6], [9, 14]], [-1.0, 1.0, 0.0, 52.0, 0.0, -1.0]);
+        assert_eq!(source.sample(1, 1).unwrap(), 14);
+        assert!(source.sample(2, 0).is_err());
+
+        let window = source.read_window(-1, 0, 3, 1).unwrap();
+        assert_eq!((window.x_off, window.width), (0, 2));
+        assert_eq!(window.data, vec![2, 6]);
+
+        let classifier = UrbanClassifier::from_source(source).unwrap();
+        let result = classifier
+            .run_classification(&stations(), "station_id", "longitude", "latitude", None)
+            .unwrap();
+        let names = result.column("lcz_name").unwrap();
+        assert_eq!(names.str().unwrap().get(0), Some("Compact midrise"));
+    }
+}
@@ -0,0 +1,47 @@
+//! Optional `tracing` Instrumentation
+//!
+//! Classification stages are wrapped in spans and emit debug events when the
+//! `tracing` feature is enabled. Without the feature these macros expand to
+//! nothing, so instrumented code carries no runtime cost or dependency.
+
+/// Emit a `tracing::debug!` event when the `tracing` feature is enabled
+macro_rules! debug_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::debug!($($arg)*);
+    };
+}
+
+/// Emit a `tracing::trace!` event when the `tracing` feature is enabled
+macro_rules! trace_event {
+    ($($arg:tt)*) => {
+        #[cfg(feature = "tracing")]
+        tracing::trace!($($arg)*);
+    };
+}
+
+/// Enter a debug-level span for a classification stage until the guard is dropped
+///
+/// With a subscriber configured to record span closes (e.g.
+/// `FmtSpan::CLOSE`), each stage reports its wall time.
+macro_rules! stage_span {
+    ($name:expr) => {{
+        #[cfg(feature = "tracing")]
+        let guard = tracing::debug_span!("stage", name = $name).entered();
+        #[cfg(not(feature = "tracing"))]
+        let guard = $crate::trace::NoSpan;
+        guard
+    }};
+}
+
+pub(crate) use {debug_event, stage_span, trace_event};
+
+/// Stand-in for an entered span when the `tracing` feature is disabled
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+// Lets stages end with an explicit `drop(stage)` whether or not tracing is enabled
+#[cfg(not(feature = "tracing"))]
+impl Drop for NoSpan {
+    fn drop(&mut self) {}
+}
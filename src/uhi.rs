@@ -0,0 +1,148 @@
+//! Expected Urban Heat Island Intensity (`uhi` feature)
+//!
+//! Screening studies often need a first estimate of the thermal bias a
+//! station's surroundings introduce before any data are analysed. This module
+//! provides typical ranges of nocturnal, clear-sky, calm-wind air temperature
+//! differences between each LCZ and LCZ D (low plants), compiled from the
+//! LCZ temperature differences reported in the literature.
+//!
+//! The values are indicative only: observed differences vary with season,
+//! weather, regional climate and city size, and water (LCZ G) in particular
+//! depends strongly on season.
+//!
+//! References: Stewart, I. D., Oke, T. R. and Krayenhoff, E. S.: Evaluation of
+//! the 'local climate zone' scheme using temperature observations and model
+//! simulations, Int. J. Climatol., 34, 1062-1080,
+//! https://doi.org/10.1002/joc.3746, 2014; Fenner, D. et al.: Intra and inter
+//! 'local climate zone' variability of air temperature as observed by crowdsourced
+//! citizen weather stations in Berlin, Germany, Meteorol. Z., 26, 525-547, 2017.
+
+use crate::error::Result;
+use crate::lcz::Lcz;
+use polars::prelude::*;
+
+/// Typical nocturnal temperature difference to LCZ D in °C
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UhiIntensity {
+    /// Lower end of the typical range
+    pub min: f64,
+    /// Upper end of the typical range
+    pub max: f64,
+}
+
+impl UhiIntensity {
+    const fn new(min: f64, max: f64) -> Self {
+        UhiIntensity { min, max }
+    }
+
+    /// Centre of the typical range
+    pub fn midpoint(&self) -> f64 {
+        (self.min + self.max) / 2.0
+    }
+}
+
+/// Expected intensities of LCZ 1-10 and A-G, indexed by code - 1
+const INTENSITIES: [UhiIntensity; 17] = [
+    UhiIntensity::new(5.0, 7.0),
+    UhiIntensity::new(4.0, 6.0),
+    UhiIntensity::new(3.0, 5.0),
+    UhiIntensity::new(3.0, 5.0),
+    UhiIntensity::new(2.5, 4.0),
+    UhiIntensity::new(1.5, 3.0),
+    UhiIntensity::new(3.0, 5.0),
+    UhiIntensity::new(2.0, 4.0),
+    UhiIntensity::new(0.5, 2.0),
+    UhiIntensity::new(2.0, 4.0),
+    UhiIntensity::new(0.5, 2.0),
+    UhiIntensity::new(0.0, 1.5),
+    UhiIntensity::new(0.0, 1.0),
+    UhiIntensity::new(0.0, 0.0),
+    UhiIntensity::new(1.0, 3.0),
+    UhiIntensity::new(-1.0, 0.5),
+    UhiIntensity::new(0.0, 4.0),
+];
+
+impl Lcz {
+    /// Typical nocturnal UHI intensity relative to LCZ D, or `None` for
+    /// unknown codes
+    ///
+    /// # Examples
+    /// ```
+    /// use urban_classifier::Lcz;
+    ///
+    /// let compact = Lcz::CompactMidRise.expected_uhi().unwrap();
+    /// assert!(compact.min > Lcz::OpenLowRise.expected_uhi().unwrap().min);
+    /// assert_eq!(Lcz::LowPlants.expected_uhi().unwrap().max, 0.0);
+    /// ```
+    pub fn expected_uhi(&self) -> Option<UhiIntensity> {
+        match self {
+            Lcz::Unknown(_) => None,
+            lcz => Some(INTENSITIES[usize::from(lcz.to_code()) - 1]),
+        }
+    }
+}
+
+/// Add `uhi_min_c` and `uhi_max_c` columns with the expected intensity of
+/// each station's class to a classification result
+///
+/// # Errors
+/// Returns a Polars error if `classified` has no `lcz_code` column.
+///
+/// # Examples
+/// ```no_run
+/// use urban_classifier::uhi::annotate_expected_uhi;
+/// use urban_classifier::UrbanClassifier;
+///
+/// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+/// # let stations = polars::prelude::DataFrame::default();
+/// let annotated = annotate_expected_uhi(&classifier.classify(&stations)?)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn annotate_expected_uhi(classified: &DataFrame) -> Result<DataFrame> {
+    let intensities: Vec<Option<UhiIntensity>> = classified
+        .column("lcz_code")?
+        .u32()?
+        .into_iter()
+        .map(|code| code.and_then(|code| Lcz::from_code(code as u8).expected_uhi()))
+        .collect();
+    let minima: Vec<Option<f64>> = intensities.iter().map(|i| i.map(|i| i.min)).collect();
+    let maxima: Vec<Option<f64>> = intensities.iter().map(|i| i.map(|i| i.max)).collect();
+
+    let mut annotated = classified.clone();
+    annotated.hstack_mut(&[
+        Series::new("uhi_min_c", minima),
+        Series::new("uhi_max_c", maxima),
+    ])?;
+    Ok(annotated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test the table against the LCZ D reference and the annotated columns
+    #[test]
+    fn test_expected_uhi() {
+        for lcz in Lcz::iter() {
+            let intensity = lcz.expected_uhi().unwrap();
+            assert!(intensity.min <= intensity.max, "{}", lcz);
+        }
+        assert_eq!(Lcz::LowPlants.expected_uhi().unwrap().midpoint(), 0.0);
+        assert_eq!(Lcz::Unknown(0).expected_uhi(), None);
+
+        let classified = df! {
+            "station_id" => ["A", "B", "C"],
+            "lcz_code" => [Some(2u32), None, Some(99)],
+        }
+        .unwrap();
+        let annotated = annotate_expected_uhi(&classified).unwrap();
+        let minima: Vec<Option<f64>> = annotated
+            .column("uhi_min_c")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(minima, vec![Some(4.0), None, None]);
+    }
+}
@@ -0,0 +1,226 @@
+//! Pre-flight Station Validation
+//!
+//! This module checks station coordinates against the loaded raster before any
+//! sampling happens, so that data problems can be reported all at once instead
+//! of surfacing as the first failing station during classification.
+//!
+//! # Checks
+//!
+//! Each row is assigned at most one issue, checked in this order:
+//! - **Null coordinate**: Longitude or latitude is null
+//! - **Invalid coordinate**: Non-finite, or outside -180..180 / -90..90
+//! - **Outside extent**: Falls outside the raster (or cannot be transformed into its CRS)
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use crate::source::PixelLocator;
+use polars::prelude::*;
+use std::fmt;
+
+/// Category of problem found for a station
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum StationIssue {
+    /// Longitude or latitude is null
+    NullCoordinate,
+    /// Coordinate is not finite or outside the valid WGS84 range
+    InvalidCoordinate,
+    /// Coordinate lies outside the raster extent
+    OutsideExtent,
+}
+
+impl fmt::Display for StationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            StationIssue::NullCoordinate => "null coordinate",
+            StationIssue::InvalidCoordinate => "invalid coordinate",
+            StationIssue::OutsideExtent => "outside raster extent",
+        };
+        f.write_str(label)
+    }
+}
+
+/// Result of validating station coordinates against the raster
+///
+/// Stations are identified by their ID, or by `row {index}` when the ID is null.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StationValidationReport {
+    /// Number of rows checked
+    pub total: usize,
+    /// Stations with a null longitude or latitude
+    pub null_coordinates: Vec<String>,
+    /// Stations with non-finite or out-of-range coordinates
+    pub invalid_coordinates: Vec<String>,
+    /// Stations outside the raster extent
+    pub outside_extent: Vec<String>,
+}
+
+impl StationValidationReport {
+    /// Stations affected by an issue
+    pub fn stations(&self, issue: StationIssue) -> &[String] {
+        match issue {
+            StationIssue::NullCoordinate => &self.null_coordinates,
+            StationIssue::InvalidCoordinate => &self.invalid_coordinates,
+            StationIssue::OutsideExtent => &self.outside_extent,
+        }
+    }
+
+    /// Number of stations affected by an issue
+    pub fn count(&self, issue: StationIssue) -> usize {
+        self.stations(issue).len()
+    }
+
+    /// Number of stations without any issue
+    pub fn valid_count(&self) -> usize {
+        self.total
+            - self.null_coordinates.len()
+            - self.invalid_coordinates.len()
+            - self.outside_extent.len()
+    }
+
+    /// Check whether every station passed validation
+    pub fn is_valid(&self) -> bool {
+        self.valid_count() == self.total
+    }
+
+    fn record(&mut self, issue: StationIssue, station: String) {
+        match issue {
+            StationIssue::NullCoordinate => self.null_coordinates.push(station),
+            StationIssue::InvalidCoordinate => self.invalid_coordinates.push(station),
+            StationIssue::OutsideExtent => self.outside_extent.push(station),
+        }
+    }
+}
+
+/// Check a coordinate for null and range problems
+fn coordinate_issue(lon: Option<f64>, lat: Option<f64>) -> Option<StationIssue> {
+    match (lon, lat) {
+        (Some(lon), Some(lat)) => {
+            let valid = (-180.0..=180.0).contains(&lon) && (-90.0..=90.0).contains(&lat);
+            (!valid).then_some(StationIssue::InvalidCoordinate)
+        }
+        _ => Some(StationIssue::NullCoordinate),
+    }
+}
+
+impl UrbanClassifier {
+    /// Check all station coordinates against the raster without sampling it
+    ///
+    /// # Arguments
+    /// * `stations_df` - DataFrame containing station data
+    /// * `station_id_col` - Name of the column containing station IDs
+    /// * `lon_col` - Name of the column containing longitude values
+    /// * `lat_col` - Name of the column containing latitude values
+    ///
+    /// # Returns
+    /// A report listing the stations affected by each issue. Only schema
+    /// problems (missing or non-numeric columns) are returned as errors.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B"],
+    ///     "longitude" => [-0.1278, 200.0],
+    ///     "latitude" => [51.5074, 51.0],
+    /// }?;
+    /// let report = classifier.validate_stations(&stations, "station_id", "longitude", "latitude")?;
+    /// assert_eq!(report.invalid_coordinates, vec!["B".to_string()]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn validate_stations(
+        &self,
+        stations_df: &DataFrame,
+        station_id_col: &str,
+        lon_col: &str,
+        lat_col: &str,
+    ) -> Result<StationValidationReport> {
+        self.validate_dataframe_schema(stations_df, station_id_col, lon_col, lat_col)?;
+
+        let ids = stations_df
+            .column(station_id_col)?
+            .cast(&DataType::String)?;
+        let lons = stations_df.column(lon_col)?.cast(&DataType::Float64)?;
+        let lats = stations_df.column(lat_col)?.cast(&DataType::Float64)?;
+
+        let metadata = self.source_metadata();
+        let locator = PixelLocator::new(metadata)?;
+        let (width, height) = (metadata.width, metadata.height);
+
+        let mut report = StationValidationReport {
+            total: stations_df.height(),
+            ..Default::default()
+        };
+
+        let rows = ids.str()?.into_iter().zip(lons.f64()?).zip(lats.f64()?);
+        for (i, ((id, lon), lat)) in rows.enumerate() {
+            let station = id.map_or_else(|| format!("row {}", i), str::to_string);
+
+            let issue = coordinate_issue(lon, lat).or_else(|| {
+                let (lon, lat) = (lon?, lat?);
+                let inside = locator
+                    .locate(lon, lat)
+                    .map(|(pixel, line)| {
+                        (0..width as isize).contains(&pixel) && (0..height as isize).contains(&line)
+                    })
+                    .unwrap_or(false);
+                (!inside).then_some(StationIssue::OutsideExtent)
+            });
+
+            if let Some(issue) = issue {
+                report.record(issue, station);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test null and range checks on individual coordinates
+    #[test]
+    fn test_coordinate_issue() {
+        assert_eq!(coordinate_issue(Some(-0.12), Some(51.5)), None);
+        assert_eq!(
+            coordinate_issue(None, Some(51.5)),
+            Some(StationIssue::NullCoordinate)
+        );
+        assert_eq!(
+            coordinate_issue(Some(181.0), Some(0.0)),
+            Some(StationIssue::InvalidCoordinate)
+        );
+        assert_eq!(
+            coordinate_issue(Some(f64::NAN), Some(0.0)),
+            Some(StationIssue::InvalidCoordinate)
+        );
+    }
+
+    /// Test per-issue counts and overall validity
+    #[test]
+    fn test_report_counts() {
+        let mut report = StationValidationReport {
+            total: 4,
+            ..Default::default()
+        };
+        assert!(report.is_valid());
+
+        report.record(StationIssue::NullCoordinate, "row 1".to_string());
+        report.record(StationIssue::OutsideExtent, "C".to_string());
+
+        assert!(!report.is_valid());
+        assert_eq!(report.valid_count(), 2);
+        assert_eq!(report.count(StationIssue::NullCoordinate), 1);
+        assert_eq!(report.count(StationIssue::InvalidCoordinate), 0);
+        assert_eq!(report.stations(StationIssue::OutsideExtent), ["C"]);
+        assert_eq!(
+            StationIssue::OutsideExtent.to_string(),
+            "outside raster extent"
+        );
+    }
+}
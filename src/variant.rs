@@ -0,0 +1,266 @@
+//! LCZ Subclasses and Mixed Classes
+//!
+//! Stewart and Oke (2012) extend the 17 classes with subclasses: a built
+//! class mixed with a second class, written `LCZ 2_3` (or `LCZ 2₃`), a class
+//! with a second land cover, e.g. `LCZ E_B` for bare rock with scattered
+//! trees, and seasonal or ephemeral surface states written as lowercase
+//! suffixes, e.g. `LCZ Ab` for dense leafless trees or `LCZ Ds` for snow on
+//! low plants.
+//!
+//! `LczVariant` carries these annotations alongside the parent class. Every
+//! operation that only understands the 17 classes falls back to the parent:
+//! `Lcz::from(variant)` and `LczVariant::code` give the parent class, so a
+//! variant can be used wherever an `Lcz` or an override code is expected.
+//!
+//! # Notation
+//!
+//! The parent label (`1`-`10` or `A`-`G`, optionally after `LCZ`) is followed
+//! by an optional subclass label, separated by `_` or written as subscript
+//! digits, and then by any of the surface state letters `b`, `s`, `d` and
+//! `w`. Subclass letters must be uppercase to distinguish them from states.
+//!
+//! ```
+//! use urban_classifier::variant::{LczVariant, SurfaceState};
+//! use urban_classifier::Lcz;
+//!
+//! let variant: LczVariant = "LCZ 6_Bs".parse()?;
+//! assert_eq!(variant.class, Lcz::OpenLowRise);
+//! assert_eq!(variant.subclass, Some(Lcz::ScatteredTrees));
+//! assert_eq!(variant.states, vec![SurfaceState::Snow]);
+//! assert_eq!(Lcz::from(variant), Lcz::OpenLowRise);
+//! # Ok::<(), urban_classifier::lcz::ParseLczError>(())
+//! ```
+
+use crate::lcz::{Lcz, ParseLczError};
+use std::fmt;
+use std::str::FromStr;
+
+/// Seasonal or ephemeral state of the land cover
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SurfaceState {
+    /// Deciduous trees without leaves (`b`)
+    BareTrees,
+    /// Snow cover (`s`)
+    Snow,
+    /// Dry ground (`d`)
+    DryGround,
+    /// Wet or waterlogged ground (`w`)
+    WetGround,
+}
+
+impl SurfaceState {
+    /// The suffix letter of this state
+    pub fn letter(&self) -> char {
+        match self {
+            SurfaceState::BareTrees => 'b',
+            SurfaceState::Snow => 's',
+            SurfaceState::DryGround => 'd',
+            SurfaceState::WetGround => 'w',
+        }
+    }
+
+    /// Look up a state by its suffix letter
+    pub fn from_letter(letter: char) -> Option<Self> {
+        match letter {
+            'b' => Some(SurfaceState::BareTrees),
+            's' => Some(SurfaceState::Snow),
+            'd' => Some(SurfaceState::DryGround),
+            'w' => Some(SurfaceState::WetGround),
+            _ => None,
+        }
+    }
+}
+
+/// An LCZ class with optional subclass and surface state annotations
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LczVariant {
+    /// The parent class, used wherever only the 17 classes are understood
+    pub class: Lcz,
+    /// Second class mixed into the parent, e.g. `3` in `LCZ 2_3`
+    pub subclass: Option<Lcz>,
+    /// Surface states in the order written, without duplicates
+    pub states: Vec<SurfaceState>,
+}
+
+impl LczVariant {
+    /// Create a variant of `class` without annotations
+    pub fn new(class: Lcz) -> Self {
+        LczVariant {
+            class,
+            subclass: None,
+            states: Vec::new(),
+        }
+    }
+
+    /// Set the class mixed into the parent
+    pub fn with_subclass(mut self, subclass: Lcz) -> Self {
+        self.subclass = Some(subclass);
+        self
+    }
+
+    /// Add a surface state; states already present are ignored
+    pub fn with_state(mut self, state: SurfaceState) -> Self {
+        if !self.states.contains(&state) {
+            self.states.push(state);
+        }
+        self
+    }
+
+    /// Check whether the variant carries any annotation beyond its parent class
+    pub fn is_annotated(&self) -> bool {
+        self.subclass.is_some() || !self.states.is_empty()
+    }
+
+    /// Numeric code of the parent class, e.g. for manual overrides
+    pub fn code(&self) -> u8 {
+        self.class.to_code()
+    }
+}
+
+impl From<Lcz> for LczVariant {
+    fn from(class: Lcz) -> Self {
+        LczVariant::new(class)
+    }
+}
+
+impl From<LczVariant> for Lcz {
+    fn from(variant: LczVariant) -> Self {
+        variant.class
+    }
+}
+
+impl fmt::Display for LczVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = |lcz: Lcz| {
+            lcz.letter()
+                .map_or_else(|| lcz.to_code().to_string(), str::to_string)
+        };
+        write!(f, "LCZ {}", label(self.class))?;
+        if let Some(subclass) = self.subclass {
+            write!(f, "_{}", label(subclass))?;
+        }
+        for state in &self.states {
+            write!(f, "{}", state.letter())?;
+        }
+        Ok(())
+    }
+}
+
+/// Split a leading class label off `text`; `uppercase` rejects lowercase letters
+fn take_label(text: &str, uppercase: bool) -> Option<(Lcz, &str)> {
+    if let Some(rest) = text.strip_prefix("10") {
+        return Some((Lcz::HeavyIndustry, rest));
+    }
+    let first = text.chars().next()?;
+    if uppercase && first.is_ascii_lowercase() {
+        return None;
+    }
+    let lcz = Lcz::from_letter(&text[..first.len_utf8()])?;
+    Some((lcz, &text[first.len_utf8()..]))
+}
+
+impl FromStr for LczVariant {
+    type Err = ParseLczError;
+
+    /// Parse a class with optional subclass and surface states, e.g. `2_3`,
+    /// `LCZ 2₃`, `E_B` or `Ab`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseLczError {
+            input: s.to_string(),
+        };
+
+        let label = s.trim();
+        let label = match label.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("lcz") => label[3..].trim_start(),
+            _ => label,
+        };
+
+        // Subscript digits start the subclass, as if preceded by '_'
+        let mut text = String::with_capacity(label.len());
+        let mut in_subscript = false;
+        for c in label.chars() {
+            if ('₀'..='₉').contains(&c) {
+                if !in_subscript && !text.ends_with('_') {
+                    text.push('_');
+                }
+                in_subscript = true;
+                text.push(char::from(b'0' + (c as u32 - '₀' as u32) as u8));
+            } else {
+                text.push(c);
+            }
+        }
+
+        let (class, rest) = take_label(&text, false).ok_or_else(error)?;
+        let mut variant = LczVariant::new(class);
+
+        let (explicit, rest) = match rest.strip_prefix('_') {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let rest = match take_label(rest, true) {
+            Some((subclass, rest)) => {
+                variant.subclass = Some(subclass);
+                rest
+            }
+            None if explicit => return Err(error()),
+            None => rest,
+        };
+
+        for c in rest.chars() {
+            let state = SurfaceState::from_letter(c).ok_or_else(error)?;
+            variant = variant.with_state(state);
+        }
+        Ok(variant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test parsing of subclasses, mixed classes and surface states
+    #[test]
+    fn test_parse_variant() {
+        let mixed: LczVariant = "LCZ 2_3".parse().unwrap();
+        assert_eq!(
+            mixed,
+            LczVariant::new(Lcz::CompactMidRise).with_subclass(Lcz::CompactLowRise)
+        );
+        assert_eq!("2₃".parse::<LczVariant>(), Ok(mixed));
+        assert_eq!(
+            "10_8".parse::<LczVariant>().unwrap().subclass,
+            Some(Lcz::LargeLowRise)
+        );
+
+        let rock_with_trees: LczVariant = "E_B".parse().unwrap();
+        assert_eq!(rock_with_trees.subclass, Some(Lcz::ScatteredTrees));
+        assert_eq!("EB".parse::<LczVariant>(), Ok(rock_with_trees));
+
+        let leafless: LczVariant = "Ab".parse().unwrap();
+        assert_eq!(leafless.class, Lcz::DenseTrees);
+        assert_eq!(leafless.subclass, None);
+        assert_eq!(leafless.states, vec![SurfaceState::BareTrees]);
+
+        let plain: LczVariant = "lcz 6".parse().unwrap();
+        assert!(!plain.is_annotated());
+        assert_eq!(plain.code(), 6);
+
+        for invalid in ["", "H", "2_", "2_x", "6Bq", "LCZ"] {
+            assert!(invalid.parse::<LczVariant>().is_err(), "{}", invalid);
+        }
+    }
+
+    /// Test that variants display in a form that parses back
+    #[test]
+    fn test_display_variant() {
+        let variant = LczVariant::new(Lcz::OpenLowRise)
+            .with_subclass(Lcz::ScatteredTrees)
+            .with_state(SurfaceState::Snow)
+            .with_state(SurfaceState::Snow)
+            .with_state(SurfaceState::WetGround);
+        assert_eq!(variant.to_string(), "LCZ 6_Bsw");
+        assert_eq!(variant.to_string().parse(), Ok(variant.clone()));
+        assert_eq!(Lcz::from(variant), Lcz::OpenLowRise);
+        assert_eq!(LczVariant::from(Lcz::Water).to_string(), "LCZ G");
+    }
+}
@@ -0,0 +1,429 @@
+//! Data-Quality Warnings
+//!
+//! Some stations classify without error but are probably wrong. After a run,
+//! `UrbanClassifier::classify_with_warnings` checks the stations for common
+//! data problems and returns them as a `ClassificationWarnings` report
+//! alongside the result, leaving the classes themselves unchanged.
+//!
+//! # Checks
+//!
+//! - **Null island**: The station lies at exactly (0, 0), usually a missing
+//!   coordinate written as zero
+//! - **Duplicate**: The station has exactly the same coordinates as an
+//!   earlier row
+//! - **Water**: The station was sampled as LCZ G (water), which for a land
+//!   station suggests imprecise coordinates
+//! - **Nodata**: The station was located in the raster but its pixel holds
+//!   nodata or a code outside the LCZ classes
+//! - **Near pixel edge**: The station lies within 5% of a pixel of its
+//!   pixel's edge, so a small coordinate error could change its class
+//! - **Whole degrees**: Both coordinates are whole degrees, typical of
+//!   rounded or placeholder positions
+//! - **Unmatched override**: An override names a station ID that is not in
+//!   the input, usually a typo
+//!
+//! Overridden stations are skipped by the checks on their sampled pixel.
+
+use crate::classifier::UrbanClassifier;
+use crate::error::Result;
+use crate::lcz::Lcz;
+use crate::request::ClassificationRequest;
+use crate::source::PixelLocator;
+use polars::prelude::*;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Distance from a pixel edge, as a fraction of the pixel size, below which a
+/// station is reported as near the edge
+const PIXEL_EDGE_MARGIN: f64 = 0.05;
+
+/// Kind of data-quality problem found for a station
+///
+/// Serializes as a snake_case name, e.g. `"null_island"`, or an object for
+/// `Duplicate`: `{"duplicate": {"first_row": 0}}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StationWarningKind {
+    /// Coordinates are exactly (0, 0)
+    NullIsland,
+    /// Coordinates are identical to those of an earlier row
+    Duplicate {
+        /// Zero-based row of the first station at these coordinates
+        first_row: usize,
+    },
+    /// The station was sampled as water
+    Water,
+    /// The station's pixel holds nodata or a code outside the LCZ classes
+    Nodata,
+    /// The station lies close to the edge of its pixel
+    NearPixelEdge,
+    /// Both coordinates are whole degrees
+    WholeDegrees,
+}
+
+impl fmt::Display for StationWarningKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StationWarningKind::NullIsland => f.write_str("located at (0, 0)"),
+            StationWarningKind::Duplicate { first_row } => {
+                write!(f, "same coordinates as row {}", first_row)
+            }
+            StationWarningKind::Water => f.write_str("classified as water"),
+            StationWarningKind::Nodata => f.write_str("sampled as nodata"),
+            StationWarningKind::NearPixelEdge => f.write_str("close to a pixel edge"),
+            StationWarningKind::WholeDegrees => f.write_str("coordinates are whole degrees"),
+        }
+    }
+}
+
+/// A station with a suspected data-quality problem
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StationWarning {
+    /// ID of the station
+    pub station_id: String,
+    /// Zero-based row of the station in the input DataFrame
+    pub row: usize,
+    /// What looks wrong
+    pub kind: StationWarningKind,
+}
+
+impl fmt::Display for StationWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "station {} (row {}): {}",
+            self.station_id, self.row, self.kind
+        )
+    }
+}
+
+/// Every warning raised for one classification run, in row order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ClassificationWarnings {
+    warnings: Vec<StationWarning>,
+    unmatched_overrides: Vec<String>,
+}
+
+impl ClassificationWarnings {
+    /// True when no station raised a warning and every override was used
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty() && self.unmatched_overrides.is_empty()
+    }
+
+    /// Number of warnings, including unmatched overrides
+    pub fn len(&self) -> usize {
+        self.warnings.len() + self.unmatched_overrides.len()
+    }
+
+    /// Iterate over the warnings in row order
+    pub fn iter(&self) -> std::slice::Iter<'_, StationWarning> {
+        self.warnings.iter()
+    }
+
+    /// The warnings in row order
+    pub fn warnings(&self) -> &[StationWarning] {
+        &self.warnings
+    }
+
+    /// Number of null island warnings
+    pub fn null_island_count(&self) -> usize {
+        self.count(|kind| kind == StationWarningKind::NullIsland)
+    }
+
+    /// Number of duplicate coordinate warnings
+    pub fn duplicate_count(&self) -> usize {
+        self.count(|kind| matches!(kind, StationWarningKind::Duplicate { .. }))
+    }
+
+    /// Number of water warnings
+    pub fn water_count(&self) -> usize {
+        self.count(|kind| kind == StationWarningKind::Water)
+    }
+
+    /// Number of nodata warnings
+    pub fn nodata_count(&self) -> usize {
+        self.count(|kind| kind == StationWarningKind::Nodata)
+    }
+
+    /// Number of near pixel edge warnings
+    pub fn near_pixel_edge_count(&self) -> usize {
+        self.count(|kind| kind == StationWarningKind::NearPixelEdge)
+    }
+
+    /// Number of whole degree warnings
+    pub fn whole_degrees_count(&self) -> usize {
+        self.count(|kind| kind == StationWarningKind::WholeDegrees)
+    }
+
+    /// Overridden station IDs that are not in the input, sorted
+    pub fn unmatched_overrides(&self) -> &[String] {
+        &self.unmatched_overrides
+    }
+
+    /// Take ownership of the station warnings
+    pub fn into_vec(self) -> Vec<StationWarning> {
+        self.warnings
+    }
+
+    fn count(&self, matches: impl Fn(StationWarningKind) -> bool) -> usize {
+        self.warnings.iter().filter(|w| matches(w.kind)).count()
+    }
+}
+
+impl fmt::Display for ClassificationWarnings {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} data-quality warning(s)", self.len())?;
+        for warning in &self.warnings {
+            write!(f, "\n  {}", warning)?;
+        }
+        for station_id in &self.unmatched_overrides {
+            write!(f, "\n  override for unknown station {}", station_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for ClassificationWarnings {
+    type Item = StationWarning;
+    type IntoIter = std::vec::IntoIter<StationWarning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a ClassificationWarnings {
+    type Item = &'a StationWarning;
+    type IntoIter = std::slice::Iter<'a, StationWarning>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.warnings.iter()
+    }
+}
+
+/// Outcome of a run for one station, as checked for warnings
+pub(crate) struct StationOutcome<'a> {
+    pub(crate) station_id: &'a str,
+    /// WGS84 coordinates of the station
+    pub(crate) lon: f64,
+    pub(crate) lat: f64,
+    /// Fractional pixel position, `None` if the station could not be located
+    pub(crate) position: Option<(f64, f64)>,
+    /// Sampled LCZ code, `None` for nodata
+    pub(crate) code: Option<u8>,
+    /// Whether the station's class was set by an override
+    pub(crate) overridden: bool,
+}
+
+/// Check stations for data-quality problems
+///
+/// `overrides` are the station overrides of the run, checked against the IDs
+/// of `stations`.
+pub(crate) fn check_stations(
+    stations: &[StationOutcome<'_>],
+    overrides: Option<&HashMap<String, u8>>,
+) -> ClassificationWarnings {
+    let mut warnings = Vec::new();
+    let mut first_rows: HashMap<(u64, u64), usize> = HashMap::new();
+
+    for (row, station) in stations.iter().enumerate() {
+        let mut warn = |kind| {
+            warnings.push(StationWarning {
+                station_id: station.station_id.to_string(),
+                row,
+                kind,
+            })
+        };
+        let (lon, lat) = (station.lon, station.lat);
+
+        let null_island = lon == 0.0 && lat == 0.0;
+        if null_island {
+            warn(StationWarningKind::NullIsland);
+        }
+        // Compare bit patterns so that -0.0 and 0.0 are distinct, as in the input
+        let key = (lon.to_bits(), lat.to_bits());
+        match first_rows.get(&key) {
+            Some(&first_row) => warn(StationWarningKind::Duplicate { first_row }),
+            None => {
+                first_rows.insert(key, row);
+            }
+        }
+        if !null_island && lon.fract() == 0.0 && lat.fract() == 0.0 {
+            warn(StationWarningKind::WholeDegrees);
+        }
+
+        // Skip pixel checks for overridden stations and for (0, 0), which already
+        // has a warning and lies on a pixel corner of most global grids
+        let Some((pixel, line)) = station
+            .position
+            .filter(|_| !station.overridden && !null_island)
+        else {
+            continue;
+        };
+        match station.code {
+            Some(code) if code == Lcz::Water.to_code() => warn(StationWarningKind::Water),
+            Some(_) => {}
+            None => warn(StationWarningKind::Nodata),
+        }
+        let edge_distance = |value: f64| value.fract().abs().min(1.0 - value.fract().abs());
+        if edge_distance(pixel).min(edge_distance(line)) < PIXEL_EDGE_MARGIN {
+            warn(StationWarningKind::NearPixelEdge);
+        }
+    }
+
+    let station_ids: HashSet<&str> = stations.iter().map(|station| station.station_id).collect();
+    let mut unmatched_overrides: Vec<String> = overrides
+        .into_iter()
+        .flat_map(|overrides| overrides.keys())
+        .filter(|id| !station_ids.contains(id.as_str()))
+        .cloned()
+        .collect();
+    unmatched_overrides.sort();
+
+    ClassificationWarnings {
+        warnings,
+        unmatched_overrides,
+    }
+}
+
+impl UrbanClassifier {
+    /// Run LCZ classification as described by a request and check the
+    /// stations for data-quality problems
+    ///
+    /// # Returns
+    /// The `classify_with` output and a report of the data-quality problems
+    /// listed in the module documentation. Warnings never fail the run.
+    ///
+    /// # Examples
+    #[cfg_attr(feature = "gdal", doc = "```no_run")]
+    #[cfg_attr(not(feature = "gdal"), doc = "```ignore")]
+    /// use polars::prelude::*;
+    /// use urban_classifier::request::ClassificationRequest;
+    /// use urban_classifier::UrbanClassifier;
+    ///
+    /// let classifier = UrbanClassifier::new("path/to/wudapt.tif")?;
+    /// let stations = df! {
+    ///     "station_id" => ["A", "B", "C"],
+    ///     "longitude" => [-0.12, 0.0, -0.12],
+    ///     "latitude" => [51.5, 0.0, 51.5],
+    /// }?;
+    /// let (result, warnings) =
+    ///     classifier.classify_with_warnings(&stations, &ClassificationRequest::new())?;
+    /// for warning in &warnings {
+    ///     eprintln!("{}", warning);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn classify_with_warnings(
+        &self,
+        stations_df: &DataFrame,
+        request: &ClassificationRequest<'_>,
+    ) -> Result<(DataFrame, ClassificationWarnings)> {
+        let result = self.classify_with(stations_df, request)?;
+
+        let columns = request.resolve_columns(stations_df)?;
+        let prepared = self.prepare_stations(
+            stations_df,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+            request,
+        )?;
+        let (station_ids, coordinates) = self.extract_coordinates(
+            &prepared,
+            &columns.station_id,
+            &columns.longitude,
+            &columns.latitude,
+        )?;
+        let locator = PixelLocator::new(self.source_metadata())?;
+        let stations: Vec<StationOutcome<'_>> = result
+            .column("lcz_code")?
+            .u32()?
+            .into_iter()
+            .zip(station_ids.iter().zip(&coordinates))
+            .map(|(code, (station_id, &(lon, lat)))| StationOutcome {
+                station_id,
+                lon,
+                lat,
+                position: locator.locate_f(lon, lat).ok(),
+                code: code.map(|code| code as u8),
+                overridden: request
+                    .overrides()
+                    .is_some_and(|overrides| overrides.contains_key(station_id)),
+            })
+            .collect();
+
+        let warnings = check_stations(&stations, request.overrides());
+        Ok((result, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that each check raises its warning in row order
+    #[test]
+    fn test_check_stations() {
+        let station = |station_id, lon, lat, code| StationOutcome {
+            station_id,
+            lon,
+            lat,
+            position: Some((10.5, 20.5)),
+            code,
+            overridden: false,
+        };
+        let stations = [
+            station("A", -0.12, 51.5, Some(6)),
+            station("B", 0.0, 0.0, None),
+            station("C", -0.12, 51.5, Some(6)),
+            station("D", 2.35, 48.9, Some(17)),
+            station("E", 3.0, 50.0, Some(6)),
+            StationOutcome {
+                position: Some((10.02, 20.5)),
+                ..station("F", 2.31, 48.8, None)
+            },
+            StationOutcome {
+                overridden: true,
+                position: None,
+                ..station("G", 2.32, 48.8, None)
+            },
+        ];
+        let overrides = HashMap::from([("G".to_string(), 2u8), ("X".to_string(), 2u8)]);
+
+        let warnings = check_stations(&stations, Some(&overrides));
+        let kinds: Vec<(usize, StationWarningKind)> =
+            warnings.iter().map(|w| (w.row, w.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (1, StationWarningKind::NullIsland),
+                (2, StationWarningKind::Duplicate { first_row: 0 }),
+                (3, StationWarningKind::Water),
+                (4, StationWarningKind::WholeDegrees),
+                (5, StationWarningKind::Nodata),
+                (5, StationWarningKind::NearPixelEdge),
+            ]
+        );
+        assert_eq!(warnings.null_island_count(), 1);
+        assert_eq!(warnings.duplicate_count(), 1);
+        assert_eq!(warnings.water_count(), 1);
+        assert_eq!(warnings.nodata_count(), 1);
+        assert_eq!(warnings.near_pixel_edge_count(), 1);
+        assert_eq!(warnings.whole_degrees_count(), 1);
+        assert_eq!(warnings.unmatched_overrides(), ["X".to_string()]);
+        assert_eq!(warnings.len(), 7);
+        assert!(warnings
+            .to_string()
+            .starts_with("7 data-quality warning(s)"));
+        assert!(warnings
+            .to_string()
+            .ends_with("override for unknown station X"));
+
+        let json = serde_json::to_value(&warnings).unwrap();
+        assert_eq!(json["warnings"][0]["kind"], "null_island");
+        assert_eq!(json["warnings"][1]["kind"]["duplicate"]["first_row"], 0);
+        assert_eq!(json["unmatched_overrides"][0], "X");
+    }
+}
@@ -0,0 +1,168 @@
+//! On-the-fly Reprojection
+//!
+//! The global WUDAPT map is stored in geographic coordinates, where a pixel
+//! covers about 100 m north-south but only 50 m east-west at 60°N. Buffer
+//! radii and class areas are converted using the pixel size at each station's
+//! latitude, which is accurate for small buffers but not exact.
+//!
+//! `OpenOptions::with_warp_to` wraps each opened dataset in a GDAL warped VRT
+//! in another CRS instead. Choosing an equal-area CRS makes a pixel cover the
+//! same ground area everywhere, so "area per class" is exact and a "5 km radius"
+//! is the same number of pixels at all latitudes. Pixels are resampled with
+//! nearest neighbour, which keeps LCZ codes intact, and only when read.
+//!
+//! `CrsSpec::EQUAL_AREA` suits global analyses. For buffers within one
+//! region, `CrsSpec::lambert_equal_area` centred on the region also keeps
+//! shapes and distances nearly true.
+//!
+//! # Examples
+//! ```no_run
+//! use urban_classifier::open::OpenOptions;
+//! use urban_classifier::spatial::CrsSpec;
+//! use urban_classifier::UrbanClassifier;
+//!
+//! let options = OpenOptions::new().with_warp_to(CrsSpec::lambert_equal_area(10.0, 52.0));
+//! let classifier = UrbanClassifier::open_with("path/to/wudapt.tif", &options)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::error::{ClassifierError, Result};
+use crate::source::RasterCrs;
+use crate::spatial::CrsSpec;
+use gdal::errors::GdalError;
+use gdal::Dataset;
+use std::ffi::{CStr, CString};
+use std::ptr;
+
+/// Maximum error of the approximate warp transformer, in pixels (as gdalwarp)
+const MAX_WARP_ERROR: f64 = 0.125;
+
+impl CrsSpec {
+    /// WGS 84 / NSIDC EASE-Grid 2.0 Global (EPSG:6933), a global equal-area CRS
+    pub const EQUAL_AREA: CrsSpec = CrsSpec::Epsg(6933);
+
+    /// Lambert azimuthal equal-area CRS centred on (`lon`, `lat`), in metres
+    pub fn lambert_equal_area(lon: f64, lat: f64) -> Self {
+        CrsSpec::Proj(format!(
+            "+proj=laea +lat_0={} +lon_0={} +x_0=0 +y_0=0 +datum=WGS84 +units=m +no_defs",
+            lat, lon
+        ))
+    }
+}
+
+/// Reprojection applied to each dataset as it is opened
+#[derive(Debug, Clone)]
+pub(crate) struct Warp {
+    /// CRS of datasets without a spatial reference of their own
+    src_wkt: Option<CString>,
+    dst_wkt: CString,
+}
+
+impl Warp {
+    /// Reproject to `dst`, assuming `assumed_crs` for datasets without a CRS
+    pub(crate) fn new(dst: &CrsSpec, assumed_crs: Option<&RasterCrs>) -> Result<Self> {
+        let src_wkt = match assumed_crs {
+            Some(crs) => Some(to_c_wkt(&CrsSpec::from(crs))?),
+            None => None,
+        };
+        Ok(Warp {
+            src_wkt,
+            dst_wkt: to_c_wkt(dst)?,
+        })
+    }
+
+    /// Create a warped VRT reading from `source`
+    ///
+    /// The VRT reads `source` through its raw handle, so `source` must be
+    /// kept open for as long as the returned dataset and dropped after it.
+    pub(crate) fn apply(&self, source: &Dataset) -> Result<Dataset> {
+        let src_wkt = match (source.spatial_ref(), &self.src_wkt) {
+            (Err(_), Some(wkt)) => wkt.as_ptr(),
+            _ => ptr::null(),
+        };
+
+        let c_dataset = unsafe {
+            gdal_sys::GDALAutoCreateWarpedVRT(
+                source.c_dataset(),
+                src_wkt,
+                self.dst_wkt.as_ptr(),
+                gdal_sys::GDALResampleAlg::GRA_NearestNeighbour,
+                MAX_WARP_ERROR,
+                ptr::null(),
+            )
+        };
+        if c_dataset.is_null() {
+            let (class, number, reason) = unsafe {
+                (
+                    gdal_sys::CPLGetLastErrorType(),
+                    gdal_sys::CPLGetLastErrorNo(),
+                    CStr::from_ptr(gdal_sys::CPLGetLastErrorMsg()).to_string_lossy(),
+                )
+            };
+            return Err(ClassifierError::GdalError {
+                message: format!("Failed to create warped VRT: {}", reason),
+                source: Some(GdalError::CplError {
+                    class,
+                    number,
+                    msg: reason.into_owned(),
+                }),
+            });
+        }
+
+        Ok(unsafe { Dataset::from_c_dataset(c_dataset) })
+    }
+}
+
+fn to_c_wkt(crs: &CrsSpec) -> Result<CString> {
+    let wkt = crs.to_spatial_ref()?.to_wkt()?;
+    CString::new(wkt).map_err(|e| ClassifierError::InvalidParameter {
+        message: format!("Invalid WKT: {}", e),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::CrsUnits;
+    use gdal::DriverManager;
+
+    /// Test that a geographic dataset is warped to square metric pixels
+    #[test]
+    fn test_warp_to_equal_area() {
+        let driver = DriverManager::get_driver_by_name("MEM").unwrap();
+        let mut source = driver
+            .create_with_band_type::<u8, _>("", 100, 100, 1)
+            .unwrap();
+        source
+            .set_geo_transform(&[0.0, 0.01, 0.0, 61.0, 0.0, -0.01])
+            .unwrap();
+        source
+            .set_spatial_ref(&CrsSpec::WGS84.to_spatial_ref().unwrap())
+            .unwrap();
+
+        let warp = Warp::new(&CrsSpec::EQUAL_AREA, None).unwrap();
+        let warped = warp.apply(&source).unwrap();
+        let gt = warped.geo_transform().unwrap();
+        assert!(gt[1] > 0.0 && (gt[1] + gt[5]).abs() < 1e-6);
+
+        let crs = RasterCrs::Wkt(warped.spatial_ref().unwrap().to_wkt().unwrap());
+        assert_eq!(crs.units().unwrap(), CrsUnits::METRES);
+    }
+
+    /// Test that datasets without a CRS need an assumed one
+    #[test]
+    fn test_warp_assumed_crs() {
+        let driver = DriverManager::get_driver_by_name("MEM").unwrap();
+        let mut source = driver
+            .create_with_band_type::<u8, _>("", 10, 10, 1)
+            .unwrap();
+        source
+            .set_geo_transform(&[0.0, 0.01, 0.0, 1.0, 0.0, -0.01])
+            .unwrap();
+
+        let target = CrsSpec::lambert_equal_area(0.05, 0.95);
+        assert!(Warp::new(&target, None).unwrap().apply(&source).is_err());
+        let warp = Warp::new(&target, Some(&RasterCrs::Wgs84)).unwrap();
+        assert!(warp.apply(&source).is_ok());
+    }
+}
@@ -1,5 +1,12 @@
 use polars::prelude::*;
 use std::collections::HashMap;
+use urban_classifier::classifier::ValidationMode;
+use urban_classifier::failures::FailureMode;
+use urban_classifier::request::{ClassificationRequest, OutputColumns};
+use urban_classifier::smoothing::MajorityFilter;
+use urban_classifier::source::{RasterCrs, RasterMetadata, RasterSource};
+use urban_classifier::spatial::PixelWindow;
+use urban_classifier::stability::StabilityConfig;
 use urban_classifier::{ClassifierError, Lcz, LczCategory, UrbanClassifier};
 
 #[test]
@@ -38,6 +45,7 @@ fn test_lcz_category_as_ref() {
 }
 
 #[test]
+#[cfg(feature = "gdal")]
 fn test_file_not_found_error() {
     let result = UrbanClassifier::new("/nonexistent/file.tif");
     assert!(matches!(result, Err(ClassifierError::FileNotFound { .. })));
@@ -186,6 +194,7 @@ fn test_polars_series_creation() {
 // Integration test that would work with a real GeoTIFF file
 // This is disabled by default since we don't have test data
 #[test]
+#[cfg(feature = "gdal")]
 #[ignore]
 fn test_full_classification_with_real_data() {
     // This test would require a real WUDAPT GeoTIFF file
@@ -215,3 +224,1078 @@ fn test_full_classification_with_real_data() {
         assert!(result_df.get_column_names().contains(&"simple_class"));
     }
 }
+
+// In-memory WGS84 grid: 1 degree pixels, LCZ code = 1 + (column % 17)
+struct GridSource;
+
+impl RasterSource for GridSource {
+    fn metadata(&self) -> urban_classifier::error::Result<RasterMetadata> {
+        Ok(RasterMetadata {
+            width: 360,
+            height: 180,
+            geo_transform: [-180.0, 1.0, 0.0, 90.0, 0.0, -1.0],
+            crs: RasterCrs::Wgs84,
+            block_size: (360, 1),
+            nodata: None,
+        })
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> urban_classifier::error::Result<u8> {
+        if !(0..360).contains(&pixel) || !(0..180).contains(&line) {
+            return Err(ClassifierError::RasterSampling {
+                pixel,
+                line,
+                message: "Coordinates out of bounds".to_string(),
+                station_id: None,
+                row_index: None,
+            });
+        }
+        Ok(1 + (pixel % 17) as u8)
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> urban_classifier::error::Result<PixelWindow> {
+        let x_start = pixel.max(0);
+        let y_start = line.max(0);
+        let x_end = (pixel + width as isize).min(360).max(x_start);
+        let y_end = (line + height as isize).min(180).max(y_start);
+        let mut data = Vec::new();
+        for _ in y_start..y_end {
+            for x in x_start..x_end {
+                data.push(1 + (x % 17) as u8);
+            }
+        }
+        Ok(PixelWindow {
+            x_off: x_start,
+            y_off: y_start,
+            width: (x_end - x_start) as usize,
+            height: (y_end - y_start) as usize,
+            data,
+        })
+    }
+}
+
+/// `GridSource` with a block of pixels replaced and an optional nodata value
+struct PatchedGrid {
+    /// (pixel, line) of the block's top-left pixel
+    origin: (isize, isize),
+    block: Vec<Vec<u8>>,
+    nodata: Option<u8>,
+}
+
+impl PatchedGrid {
+    fn patched(&self, pixel: isize, line: isize) -> Option<u8> {
+        let row = self
+            .block
+            .get(usize::try_from(line - self.origin.1).ok()?)?;
+        row.get(usize::try_from(pixel - self.origin.0).ok()?)
+            .copied()
+    }
+}
+
+impl RasterSource for PatchedGrid {
+    fn metadata(&self) -> urban_classifier::error::Result<RasterMetadata> {
+        Ok(RasterMetadata {
+            nodata: self.nodata,
+            ..GridSource.metadata()?
+        })
+    }
+
+    fn sample(&self, pixel: isize, line: isize) -> urban_classifier::error::Result<u8> {
+        let value = GridSource.sample(pixel, line)?;
+        Ok(self.patched(pixel, line).unwrap_or(value))
+    }
+
+    fn read_window(
+        &self,
+        pixel: isize,
+        line: isize,
+        width: usize,
+        height: usize,
+    ) -> urban_classifier::error::Result<PixelWindow> {
+        let mut window = GridSource.read_window(pixel, line, width, height)?;
+        for row in 0..window.height {
+            for col in 0..window.width {
+                let (x, y) = (window.x_off + col as isize, window.y_off + row as isize);
+                if let Some(value) = self.patched(x, y) {
+                    window.data[row * window.width + col] = value;
+                }
+            }
+        }
+        Ok(window)
+    }
+}
+
+/// The `lcz_code` column of a classification result
+fn lcz_codes(result: &DataFrame) -> Vec<Option<u32>> {
+    result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_classification_with_custom_source() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_tile_cache(4);
+
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -170.5],
+        "latitude" => [51.5, 0.5],
+    }
+    .unwrap();
+
+    let result = classifier
+        .run_classification(&df, "station_id", "longitude", "latitude", None)
+        .unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+
+    // Column 0 → LCZ 1, column 9 → LCZ 10
+    assert_eq!(codes, vec![Some(1), Some(10)]);
+    assert_eq!(classifier.tile_cache_stats().unwrap().misses, 2);
+
+    let report = classifier
+        .validate_stations(&df, "station_id", "longitude", "latitude")
+        .unwrap();
+    assert!(report.is_valid());
+}
+
+#[test]
+fn test_classification_metrics() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_tile_cache(4);
+
+    // All stations fall in the same raster row, i.e. the same cached block
+    let df = df! {
+        "station_id" => ["A", "B", "C"],
+        "longitude" => [-179.5, -170.5, -160.5],
+        "latitude" => [51.5, 51.5, 51.5],
+    }
+    .unwrap();
+
+    let (result, metrics) = classifier
+        .run_classification_with_metrics(&df, "station_id", "longitude", "latitude", None)
+        .unwrap();
+
+    assert_eq!(result.height(), 3);
+    assert_eq!(metrics.stations, 3);
+    assert_eq!(metrics.raster_reads, 1);
+    assert_eq!(metrics.cache_hits, 2);
+    assert_eq!(metrics.preloaded_samples, 0);
+    assert!(metrics.total >= metrics.sampling);
+}
+
+#[test]
+fn test_classification_collects_station_failures() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_validation_mode(ValidationMode::Strict);
+
+    let df = df! {
+        "station_id" => ["A", "B", "C", "D"],
+        "longitude" => [-179.5, -170.5, 0.5, -160.5],
+        "latitude" => [51.5, 95.0, 51.5, 51.5],
+    }
+    .unwrap();
+    let overrides = HashMap::from([("C".to_string(), 42u8)]);
+
+    let fail_fast = classifier.run_classification_with_failures(
+        &df,
+        "station_id",
+        "longitude",
+        "latitude",
+        Some(&overrides),
+        FailureMode::FailFast,
+    );
+    assert!(fail_fast.is_err());
+
+    let (result, errors) = classifier
+        .run_classification_with_failures(
+            &df,
+            "station_id",
+            "longitude",
+            "latitude",
+            Some(&overrides),
+            FailureMode::CollectAll,
+        )
+        .unwrap();
+
+    let failed: Vec<(&str, usize)> = errors
+        .iter()
+        .map(|e| (e.station_id.as_str(), e.row))
+        .collect();
+    assert_eq!(failed, vec![("B", 1), ("C", 2)]);
+    assert!(matches!(
+        errors.errors()[1].cause,
+        ClassifierError::OverrideApplication { .. }
+    ));
+
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), None, None, Some(3)]);
+}
+
+#[test]
+fn test_nodata_override() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_nodata_override(3);
+    assert_eq!(classifier.nodata_override(), Some(3));
+
+    // Column 0 → raw 1, column 19 → raw 3
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+
+    let result = classifier
+        .run_classification(&df, "station_id", "longitude", "latitude", None)
+        .unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), None]);
+}
+
+#[test]
+fn test_source_nodata() {
+    // Column 0 → raw 0 (nodata), column 1 → raw 6
+    let source = || PatchedGrid {
+        origin: (0, 38),
+        block: vec![vec![0, 6]],
+        nodata: Some(0),
+    };
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -178.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let codes = |classifier: UrbanClassifier| {
+        lcz_codes(
+            &classifier
+                .run_classification(&df, "station_id", "longitude", "latitude", None)
+                .unwrap(),
+        )
+    };
+
+    let classifier = UrbanClassifier::from_source(source()).unwrap();
+    assert_eq!(classifier.nodata_value(), Some(0));
+    assert_eq!(codes(classifier), vec![None, Some(6)]);
+
+    let overridden = UrbanClassifier::from_source(source())
+        .unwrap()
+        .with_nodata_override(6);
+    assert_eq!(codes(overridden), vec![Some(0), None]);
+}
+
+#[test]
+fn test_raw_value_column() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_nodata_override(3)
+        .with_raw_value_column(true);
+
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let overrides = HashMap::from([("A".to_string(), 5u8)]);
+
+    let result = classifier
+        .run_classification(&df, "station_id", "longitude", "latitude", Some(&overrides))
+        .unwrap();
+    let column = |name: &str| -> Vec<Option<u32>> {
+        result
+            .column(name)
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+    assert_eq!(column("lcz_code"), vec![Some(5), None]);
+    assert_eq!(column("raw_value"), vec![Some(1), Some(3)]);
+}
+
+#[test]
+fn test_classify_with_request() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+
+    let df = df! {
+        "src_id" => ["A", "B"],
+        "lon" => [-179.5, -160.5],
+        "lat" => [51.5, 51.5],
+    }
+    .unwrap();
+    let overrides = HashMap::from([("B".to_string(), 6u8)]);
+
+    let request = ClassificationRequest::new()
+        .with_station_id_column("src_id")
+        .with_longitude_column("lon")
+        .with_latitude_column("lat")
+        .with_overrides(&overrides)
+        .with_buffer(0.0)
+        .with_stability(StabilityConfig::default());
+    let result = classifier.classify_with(&df, &request).unwrap();
+
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(6)]);
+    assert!(result.column("lcz_1_pct").is_ok());
+    assert!(result.column("lcz_stability").is_ok());
+
+    let invalid = ClassificationRequest::new().with_buffer(-1.0);
+    assert!(matches!(
+        classifier.classify_with(&df, &invalid),
+        Err(ClassifierError::InvalidParameter { .. })
+    ));
+}
+
+#[test]
+fn test_classify_with_report() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+
+    let df = df! {
+        "station_id" => ["A", "B", "C"],
+        "longitude" => [-179.5, 200.0, -160.5],
+        "latitude" => [51.5, 51.5, 51.5],
+    }
+    .unwrap();
+    let request = ClassificationRequest::new()
+        .with_buffer(0.0)
+        .with_stability(StabilityConfig::default())
+        .with_failure_mode(FailureMode::CollectAll);
+    let (result, errors) = classifier.classify_with_report(&df, &request).unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors.errors()[0].station_id, "B");
+    assert_eq!(errors.errors()[0].row, 1);
+    for column in ["lcz_code", "lcz_1_pct", "lcz_stability"] {
+        let nulls: Vec<bool> = result
+            .column(column)
+            .unwrap()
+            .is_null()
+            .into_iter()
+            .map(Option::unwrap)
+            .collect();
+        assert_eq!(nulls, vec![false, true, false], "{}", column);
+    }
+    assert_eq!(
+        result.column("lcz_1_pct").unwrap().f64().unwrap().get(0),
+        Some(100.0)
+    );
+
+    // Failing fast, the same station aborts the run
+    let request = request.with_failure_mode(FailureMode::FailFast);
+    assert!(matches!(
+        classifier.classify_with(&df, &request),
+        Err(ClassifierError::InvalidCoordinate { .. })
+    ));
+}
+
+#[test]
+fn test_request_sampling_options() {
+    let classifier = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_nodata_override(3);
+
+    // Column 0 → raw 1, column 19 → raw 3 (nodata)
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let request = ClassificationRequest::new()
+        .with_majority_filter(MajorityFilter::square(1))
+        .with_nodata_category(LczCategory::Unknown);
+    let result = classifier.classify_with(&df, &request).unwrap();
+
+    let simple_class = |result: &DataFrame| -> Vec<Option<String>> {
+        result
+            .column("simple_class")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|class| class.map(str::to_string))
+            .collect()
+    };
+    assert_eq!(simple_class(&result)[1].as_deref(), Some("Unknown"));
+
+    // The request's filter smooths as if it were set on the classifier
+    let smoothed = UrbanClassifier::from_source(GridSource)
+        .unwrap()
+        .with_nodata_override(3)
+        .with_majority_filter(MajorityFilter::square(1));
+    let expected = smoothed.classify(&df).unwrap();
+    assert!(result
+        .column("lcz_code")
+        .unwrap()
+        .equals_missing(expected.column("lcz_code").unwrap()));
+
+    // The classifier itself is left unchanged
+    assert!(classifier.majority_filter().is_none());
+    assert_eq!(simple_class(&classifier.classify(&df).unwrap())[1], None);
+}
+
+#[test]
+fn test_majority_filter() {
+    // Isolated LCZ 2 and 14 pixels at (1, 38) and (2, 38) within LCZ 6
+    let source = || PatchedGrid {
+        origin: (0, 37),
+        block: vec![vec![6, 6, 6], vec![6, 2, 14], vec![6, 6, 6]],
+        nodata: None,
+    };
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-178.5, -177.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let codes = |classifier: UrbanClassifier| {
+        lcz_codes(
+            &classifier
+                .run_classification(&df, "station_id", "longitude", "latitude", None)
+                .unwrap(),
+        )
+    };
+
+    let classifier = UrbanClassifier::from_source(source()).unwrap();
+    assert_eq!(codes(classifier), vec![Some(2), Some(14)]);
+
+    let smoothed = UrbanClassifier::from_source(source())
+        .unwrap()
+        .with_majority_filter(MajorityFilter::square(1));
+    assert_eq!(codes(smoothed), vec![Some(6), Some(6)]);
+}
+
+#[test]
+fn test_stability_with_majority_filter() {
+    // An isolated LCZ 2 pixel at (12, 38) inside a 5x5 patch of LCZ 6
+    let mut block = vec![vec![6; 5]; 5];
+    block[2][2] = 2;
+    let classifier = UrbanClassifier::from_source(PatchedGrid {
+        origin: (10, 36),
+        block,
+        nodata: None,
+    })
+    .unwrap();
+
+    let df = df! {
+        "station_id" => ["A"],
+        "longitude" => [-167.5],
+        "latitude" => [51.5],
+    }
+    .unwrap();
+    // Jitter reaches the neighbouring pixels but stays inside the patch
+    let config = StabilityConfig {
+        radius_m: 60_000.0,
+        ..StabilityConfig::default()
+    };
+    let request = ClassificationRequest::new().with_stability(config);
+    let stability = |result: &DataFrame| {
+        result
+            .column("lcz_stability")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .get(0)
+            .unwrap()
+    };
+
+    let unfiltered = classifier.classify_with(&df, &request).unwrap();
+    assert!(stability(&unfiltered) < 1.0);
+
+    // Smoothed, the station and every jittered position are LCZ 6
+    let request = request.with_majority_filter(MajorityFilter::square(1));
+    let smoothed = classifier.classify_with(&df, &request).unwrap();
+    assert_eq!(
+        smoothed.column("lcz_code").unwrap().u32().unwrap().get(0),
+        Some(6)
+    );
+    assert_eq!(stability(&smoothed), 1.0);
+}
+
+#[test]
+fn test_classify_default_columns() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A"],
+        "longitude" => [-179.5],
+        "latitude" => [51.5],
+    }
+    .unwrap();
+
+    let result = classifier.classify(&df).unwrap();
+    assert_eq!(
+        result.column("lcz_code").unwrap().u32().unwrap().get(0),
+        Some(1)
+    );
+
+    let renamed = df.clone().rename("station_id", "id").unwrap().clone();
+    assert!(classifier.classify(&renamed).is_err());
+}
+
+#[test]
+fn test_classify_stations_records() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let overrides = HashMap::from([("B".to_string(), 14u8)]);
+
+    let classified = classifier
+        .classify_stations(
+            &df,
+            &ClassificationRequest::new().with_overrides(&overrides),
+        )
+        .unwrap();
+    assert_eq!(classified.len(), 2);
+    assert_eq!(classified.dataframe().height(), 2);
+
+    let records: Vec<_> = classified.iter().collect();
+    assert_eq!(records[0].id, "A");
+    assert_eq!(records[0].lcz, Some(Lcz::CompactHighRise));
+    assert_eq!(records[0].category, Some(LczCategory::Urban));
+    assert!(!records[0].overridden);
+    assert_eq!(records[1].lcz, Some(Lcz::LowPlants));
+    assert_eq!(records[1].lon, -160.5);
+    assert!(records[1].overridden);
+}
+
+#[test]
+fn test_lcz_only_output() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+        "elevation" => [12.0, 340.0],
+    }
+    .unwrap();
+
+    let request = ClassificationRequest::new().with_output_columns(OutputColumns::LczOnly);
+    let result = classifier.classify_with(&df, &request).unwrap();
+    assert_eq!(
+        result.get_column_names(),
+        vec!["station_id", "lcz_code", "lcz_name", "simple_class"]
+    );
+    assert_eq!(result.height(), 2);
+}
+
+#[test]
+fn test_reclassify_replaces_lcz_columns() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A"],
+        "longitude" => [-179.5],
+        "latitude" => [51.5],
+    }
+    .unwrap();
+
+    let once = classifier.classify(&df).unwrap();
+    let twice = classifier.classify(&once).unwrap();
+    assert_eq!(once.get_column_names(), twice.get_column_names());
+    assert!(once.equals_missing(&twice));
+}
+
+#[test]
+fn test_column_aliases() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A"],
+        "LNG" => [-179.5],
+        "Latitude" => [51.5],
+    }
+    .unwrap();
+
+    assert!(classifier.classify(&df).is_err());
+
+    let request = ClassificationRequest::new().with_column_aliases(true);
+    let result = classifier.classify_with(&df, &request).unwrap();
+    assert_eq!(
+        result.column("lcz_code").unwrap().u32().unwrap().get(0),
+        Some(1)
+    );
+}
+
+#[test]
+fn test_classify_detected() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "Site" => ["A", "B"],
+        "y" => [51.5, -33.9],
+        "x" => [-179.5, -160.5],
+    }
+    .unwrap();
+
+    let result = classifier.classify_detected(&df).unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(3)]);
+}
+
+#[test]
+fn test_swapped_coordinates() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [51.5, 51.5],
+        "latitude" => [-179.5, -160.5],
+    }
+    .unwrap();
+
+    assert!(matches!(
+        classifier.classify(&df),
+        Err(ClassifierError::SchemaValidation { .. })
+    ));
+    // The positional method reads the columns as named
+    assert!(matches!(
+        classifier.run_classification(&df, "station_id", "longitude", "latitude", None),
+        Err(ClassifierError::InvalidCoordinate { .. })
+    ));
+
+    let request = ClassificationRequest::new().with_swap_correction(true);
+    let result = classifier.classify_with(&df, &request).unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(3)]);
+}
+
+#[test]
+#[cfg(feature = "gdal")]
+fn test_input_crs() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    // Web Mercator (x, y) of (-179.5, 0.5) and (-160.5, 51.5)
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-19_981_849.0, -17_866_778.0],
+        "latitude" => [55_660.0, 6_710_219.0],
+    }
+    .unwrap();
+
+    let request = ClassificationRequest::new().with_input_crs(RasterCrs::from_epsg(3857).unwrap());
+    let result = classifier.classify_with(&df, &request).unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(3)]);
+    assert!(result
+        .column("longitude")
+        .unwrap()
+        .equals(df.column("longitude").unwrap()));
+}
+
+#[test]
+fn test_string_coordinates() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => ["179.5 W", "-160.5"],
+        "latitude" => ["51.5N", "51.5"],
+    }
+    .unwrap();
+
+    assert!(matches!(
+        classifier.classify(&df),
+        Err(ClassifierError::SchemaValidation { .. })
+    ));
+
+    let request = ClassificationRequest::new().with_coordinate_parsing(true);
+    let result = classifier.classify_with(&df, &request).unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(3)]);
+    assert_eq!(
+        result.column("longitude").unwrap().dtype(),
+        &DataType::String
+    );
+
+    let bad = df! {
+        "station_id" => ["A"],
+        "longitude" => ["unknown"],
+        "latitude" => ["51.5"],
+    }
+    .unwrap();
+    assert!(matches!(
+        classifier.classify_with(&bad, &request),
+        Err(ClassifierError::CoordinateParse { .. })
+    ));
+}
+
+#[test]
+fn test_classify_with_warnings() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    // Column 16 of the grid holds LCZ 17 (water)
+    let df = df! {
+        "station_id" => ["A", "B", "C", "D"],
+        "longitude" => [-179.5, 0.0, -179.5, -163.5],
+        "latitude" => [51.5, 0.0, 51.5, 51.5],
+    }
+    .unwrap();
+
+    let (result, warnings) = classifier
+        .classify_with_warnings(&df, &ClassificationRequest::new())
+        .unwrap();
+    assert_eq!(result.height(), 4);
+    let rows: Vec<usize> = warnings.iter().map(|w| w.row).collect();
+    assert_eq!(rows, vec![1, 2, 3]);
+    assert_eq!(warnings.water_count(), 1);
+
+    let overrides = HashMap::from([("D".to_string(), 17u8), ("Z".to_string(), 1u8)]);
+    let request = ClassificationRequest::new().with_overrides(&overrides);
+    let (_, warnings) = classifier.classify_with_warnings(&df, &request).unwrap();
+    assert_eq!(warnings.water_count(), 0);
+    assert_eq!(warnings.unmatched_overrides(), ["Z".to_string()]);
+}
+
+#[test]
+#[cfg(feature = "gdal")]
+fn test_from_in_memory_dataset() {
+    use gdal::raster::Buffer;
+    use gdal::spatial_ref::SpatialRef;
+    use gdal::DriverManager;
+
+    // Same layout as GridSource
+    let driver = DriverManager::get_driver_by_name("MEM").unwrap();
+    let mut dataset = driver
+        .create_with_band_type::<u8, _>("", 360, 180, 1)
+        .unwrap();
+    dataset
+        .set_geo_transform(&[-180.0, 1.0, 0.0, 90.0, 0.0, -1.0])
+        .unwrap();
+    dataset
+        .set_spatial_ref(&SpatialRef::from_epsg(4326).unwrap())
+        .unwrap();
+    let data: Vec<u8> = (0..180)
+        .flat_map(|_| (0..360).map(|x| 1 + (x % 17) as u8))
+        .collect();
+    dataset
+        .rasterband(1)
+        .unwrap()
+        .write((0, 0), (360, 180), &Buffer::new((360, 180), data))
+        .unwrap();
+
+    let classifier = UrbanClassifier::from_dataset(dataset).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -160.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+
+    let result = classifier
+        .classify_with(&df, &ClassificationRequest::new().with_buffer(0.0))
+        .unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes, vec![Some(1), Some(3)]);
+    assert!(classifier.raster_info().is_ok());
+}
+
+#[test]
+#[cfg(feature = "gdal")]
+fn test_out_of_bounds_reports_raster_extent() {
+    use gdal::spatial_ref::SpatialRef;
+    use gdal::DriverManager;
+
+    // Regional map of Great Britain
+    let driver = DriverManager::get_driver_by_name("MEM").unwrap();
+    let mut dataset = driver
+        .create_with_band_type::<u8, _>("", 100, 100, 1)
+        .unwrap();
+    dataset
+        .set_geo_transform(&[-8.0, 0.1, 0.0, 60.0, 0.0, -0.1])
+        .unwrap();
+    dataset
+        .set_spatial_ref(&SpatialRef::from_epsg(4326).unwrap())
+        .unwrap();
+    let classifier = UrbanClassifier::from_dataset(dataset).unwrap();
+
+    let df = df! {
+        "station_id" => ["LON", "PAR"],
+        "longitude" => [-0.12, 2.35],
+        "latitude" => [51.5, 48.86],
+    }
+    .unwrap();
+    let error = classifier.classify(&df).unwrap_err();
+    match &error {
+        ClassifierError::OutOfBounds {
+            lon,
+            lat,
+            raster_extent,
+            ..
+        } => {
+            assert_eq!((*lon, *lat), (2.35, 48.86));
+            assert!((raster_extent.min_lat - 50.0).abs() < 1e-9);
+            assert!((raster_extent.max_lon - 2.0).abs() < 1e-9);
+        }
+        other => panic!("expected OutOfBounds, got {:?}", other),
+    }
+    assert_eq!(error.station_id(), Some("PAR"));
+    assert_eq!(error.code(), "E_OUT_OF_BOUNDS");
+    assert!(error
+        .to_string()
+        .contains("the raster covers lon -8.0000 to 2.0000, lat 50.0000 to 60.0000"));
+
+    // Collecting failures leaves the station outside the raster unclassified
+    let request = ClassificationRequest::new().with_failure_mode(FailureMode::CollectAll);
+    let result = classifier.classify_with(&df, &request).unwrap();
+    let codes: Vec<Option<u32>> = result
+        .column("lcz_code")
+        .unwrap()
+        .u32()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(codes[1], None);
+}
+
+#[test]
+fn test_plan_classification() {
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B", "C"],
+        "longitude" => [-179.5, -160.5, 200.0],
+        "latitude" => [51.5, 51.5, 0.0],
+    }
+    .unwrap();
+
+    let plan = classifier
+        .plan_classification(&df, &ClassificationRequest::new())
+        .unwrap();
+    assert_eq!(
+        plan.stations.get_column_names(),
+        vec!["station_id", "pixel", "line", "in_extent"]
+    );
+    let pixels: Vec<Option<i64>> = plan
+        .stations
+        .column("pixel")
+        .unwrap()
+        .i64()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(pixels, vec![Some(0), Some(19), None]);
+    assert_eq!(plan.summary.inside_extent, 2);
+    assert_eq!(plan.summary.untransformable, 1);
+    // Both stations share one 360x1 block row
+    assert_eq!(plan.summary.distinct_blocks, 1);
+}
+
+#[test]
+fn test_category_scheme() {
+    use urban_classifier::category::CategoryScheme;
+
+    // Columns 8 and 9 of the grid hold LCZ 9 and LCZ 10
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-171.5, -170.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let simple_classes = |classifier: &UrbanClassifier| -> Vec<Option<String>> {
+        let result = classifier
+            .run_classification(&df, "station_id", "longitude", "latitude", None)
+            .unwrap();
+        result
+            .column("simple_class")
+            .unwrap()
+            .str()
+            .unwrap()
+            .into_iter()
+            .map(|class| class.map(str::to_string))
+            .collect()
+    };
+
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    assert_eq!(
+        simple_classes(&classifier),
+        vec![Some("Suburban".to_string()), Some("Suburban".to_string())]
+    );
+
+    let classifier = classifier.with_category_scheme(CategoryScheme::sparsely_built_rural());
+    assert_eq!(
+        simple_classes(&classifier),
+        vec![Some("Rural".to_string()), Some("Suburban".to_string())]
+    );
+}
+
+#[test]
+fn test_grouping_columns() {
+    use urban_classifier::category::Grouping;
+
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-179.5, -163.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let request = ClassificationRequest::new()
+        .with_grouping(Grouping::UrbanForm)
+        .with_grouping(Grouping::BuiltNatural)
+        .with_grouping(Grouping::UrbanForm);
+
+    let result = classifier.classify_with(&df, &request).unwrap();
+    let names = result.get_column_names();
+    assert_eq!(&names[names.len() - 2..], ["urban_form", "built_natural"]);
+    let forms: Vec<Option<&str>> = result
+        .column("urban_form")
+        .unwrap()
+        .str()
+        .unwrap()
+        .into_iter()
+        .collect();
+    assert_eq!(forms, vec![Some("Compact"), Some("Water")]);
+}
+
+#[test]
+fn test_raster_bounds() {
+    use urban_classifier::spatial::BoundingBox;
+
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let bounds = classifier.raster_bounds().unwrap();
+    assert_eq!(
+        bounds.wgs84,
+        BoundingBox::new(-180.0, -90.0, 180.0, 90.0).unwrap()
+    );
+    assert_eq!((bounds.native.min_x, bounds.native.max_y), (-180.0, 90.0));
+    assert!(bounds.contains(-0.1278, 51.5074));
+
+    let london = BoundingBox::new(-0.6, 51.2, 0.4, 51.8).unwrap();
+    assert_eq!(bounds.intersection(&london), Some(london));
+}
+
+#[test]
+#[cfg(feature = "gdal")]
+fn test_export_region() {
+    use urban_classifier::spatial::BoundingBox;
+
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("extract.tif");
+
+    let bbox = BoundingBox::new(-171.8, 50.2, -169.2, 52.8).unwrap();
+    classifier.export_region(&bbox, &path).unwrap();
+
+    let extract = UrbanClassifier::new(&path).unwrap();
+    let info = extract.raster_info().unwrap();
+    assert_eq!((info.width, info.height), (3, 3));
+    assert_eq!(info.geo_transform, [-172.0, 1.0, 0.0, 53.0, 0.0, -1.0]);
+
+    let df = df! {
+        "station_id" => ["A", "B"],
+        "longitude" => [-171.5, -169.5],
+        "latitude" => [51.5, 51.5],
+    }
+    .unwrap();
+    let codes = |classifier: &UrbanClassifier| -> Vec<Option<u32>> {
+        let result = classifier.classify(&df).unwrap();
+        result
+            .column("lcz_code")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_iter()
+            .collect()
+    };
+    assert_eq!(codes(&extract), codes(&classifier));
+
+    let ocean = BoundingBox::new(-10.0, -10.0, 10.0, 10.0).unwrap();
+    let outside = UrbanClassifier::new(&path)
+        .unwrap()
+        .export_region(&ocean, dir.path().join("none.tif"));
+    assert!(outside.is_err());
+}
+
+#[test]
+fn test_lcz_area_km2() {
+    use geo_types::polygon;
+    use urban_classifier::composition::Region;
+    use urban_classifier::spatial::BoundingBox;
+
+    let classifier = UrbanClassifier::from_source(GridSource).unwrap();
+
+    // One 1-degree pixel of LCZ 11 (column 180) at the equator and at 60°N
+    let equator = classifier
+        .lcz_area_km2(&BoundingBox::new(0.0, 0.0, 1.0, 1.0).unwrap().into())
+        .unwrap();
+    let north = classifier
+        .lcz_area_km2(&BoundingBox::new(0.0, 60.0, 1.0, 61.0).unwrap().into())
+        .unwrap();
+    assert_eq!(equator.len(), 1);
+    let equator_km2 = equator[&Lcz::DenseTrees];
+    assert!((equator_km2 - 12_364.0).abs() < 10.0);
+    assert!((north[&Lcz::DenseTrees] / equator_km2 - 0.5).abs() < 0.01);
+
+    // A triangle covering the centres of three pixels in columns 180 and 181
+    let triangle = polygon![(x: 0.0, y: 0.0), (x: 2.2, y: 0.0), (x: 0.0, y: 2.2)];
+    let areas = classifier.lcz_area_km2(&Region::from(triangle)).unwrap();
+    assert_eq!(areas.len(), 2);
+    assert!(areas[&Lcz::DenseTrees] > areas[&Lcz::ScatteredTrees]);
+}